@@ -0,0 +1,595 @@
+//! The zombiesplit database management binary.
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use zombiesplit::{
+    cli,
+    config::{server::display::Display, Server as Config},
+    model::{
+        game,
+        game::category::ShortDescriptor,
+        history,
+        load::{self, schema, Loadable},
+        short,
+    },
+    Db,
+};
+
+fn main() {
+    cli::handle_error(run())
+}
+
+/// Database manager for zombiesplit.
+#[derive(Parser, Debug)]
+#[clap(name = "zsdb", about, version, author)]
+struct Args {
+    /// Use this system config file
+    #[clap(short, long)]
+    config: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Initialises the database
+    Init,
+    /// Applies any pending schema migrations to the database
+    Migrate {
+        /// Show the current and latest schema versions, and any pending migrations, without applying them
+        #[clap(long)]
+        status: bool,
+    },
+    /// Adds a game from a TOML game specification, named after the file
+    AddGame {
+        /// Path to the game specification
+        path: PathBuf,
+
+        /// Read the file as a CSV of one category's splits (short,name[,group])
+        #[clap(long, requires_all = &["name", "category"])]
+        csv: bool,
+        /// The game's display name (CSV only)
+        #[clap(long)]
+        name: Option<String>,
+        /// The CSV category's short name (CSV only)
+        #[clap(long)]
+        category: Option<short::Name>,
+        /// The CSV category's display name, if different from its short name (CSV only)
+        #[clap(long)]
+        category_name: Option<String>,
+    },
+    /// Adds a run from a TOML run file
+    AddRun {
+        /// Path to the run file
+        path: PathBuf,
+
+        /// Reject the run, rather than warning, if its cumulative time goes backwards
+        #[clap(long)]
+        strict: bool,
+        /// If the run's category doesn't exist, use the closest one without asking
+        #[clap(short, long)]
+        yes: bool,
+    },
+    /// Adds every TOML run file in a directory, all or nothing
+    AddRuns {
+        /// Path to the directory of run files
+        dir: PathBuf,
+
+        /// Reject the runs, rather than warning, if any cumulative time goes backwards
+        #[clap(long)]
+        strict: bool,
+        /// If a run's category doesn't exist, use the closest one without asking
+        #[clap(short, long)]
+        yes: bool,
+    },
+    /// Renames a game, keeping its runs
+    RenameGame {
+        /// The game's current short name
+        old: short::Name,
+        /// The game's new short name
+        new: short::Name,
+    },
+    /// Renames a category of a game, keeping its runs
+    RenameCategory {
+        /// The short name of the game owning the category
+        game: short::Name,
+        /// The category's current short name
+        old: short::Name,
+        /// The category's new short name
+        new: short::Name,
+    },
+    /// Lists the game/category pairs in the database
+    List {
+        /// Only list categories of the game with this short name
+        game: Option<short::Name>,
+    },
+    /// Lists the runs stored for a game/category, best first
+    ListRuns {
+        /// The game/category to list (for example, "scd11/btg-sonic")
+        target: ShortDescriptor,
+
+        /// Only list runs whose metadata has this key set to this value (as "key=value")
+        #[clap(long = "filter", value_parser = parse_filter)]
+        filters: Vec<(String, String)>,
+    },
+    /// Summarises the runs stored for a game/category
+    Stats {
+        /// The game/category to summarise (for example, "scd11/btg-sonic")
+        target: ShortDescriptor,
+    },
+    /// Compares two runs of a game/category split by split
+    Diff {
+        /// The game/category of the runs (for example, "scd11/btg-sonic")
+        target: ShortDescriptor,
+        /// Index of the first run, counting from the oldest run (0)
+        a: usize,
+        /// Index of the run to compare it against
+        b: usize,
+    },
+    /// Backs up the database to a new file
+    Backup {
+        /// Path to the backup file
+        path: PathBuf,
+    },
+    /// Restores the database from a backup file
+    Restore {
+        /// Path to the backup file
+        path: PathBuf,
+
+        /// Overwrite the database even if it already exists
+        #[clap(long)]
+        force: bool,
+    },
+    /// Checks the database for orphaned rows, such as split times whose runs are missing
+    Fsck {
+        /// Delete any orphans found, all or nothing
+        #[clap(long)]
+        repair: bool,
+    },
+    /// Exports run totals over time for a game/category as CSV
+    Trend {
+        /// The game/category to export (for example, "scd11/btg-sonic")
+        target: ShortDescriptor,
+
+        /// Write the CSV to this file instead of standard output
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Prints the JSON schema of a TOML file format, for use by editors and other tools
+    Schema {
+        /// The format whose schema to print ("game" or "run")
+        kind: schema::Kind,
+    },
+}
+
+fn run() -> anyhow::Result<()> {
+    env_logger::try_init()?;
+
+    let args = Args::parse();
+    // Schemas don't depend on the database, so there's no need to open one.
+    if let Command::Schema { kind } = args.command {
+        return print_schema(kind);
+    }
+    let cfg = Config::load(args.config)?;
+    let db = Db::new(&cfg.db.path)?;
+
+    match args.command {
+        Command::Init => db.init()?,
+        Command::Migrate { status } => migrate(&db, status)?,
+        Command::AddGame {
+            path,
+            csv,
+            name,
+            category,
+            category_name,
+        } => {
+            let csv = if csv {
+                name.zip(category)
+                    .map(|(game_name, category)| load::csv::Names {
+                        game: game_name,
+                        category,
+                        category_name: category_name.unwrap_or_else(|| category.to_string()),
+                    })
+            } else {
+                None
+            };
+            add_game(&db, &path, csv.as_ref())?;
+        }
+        Command::AddRun { path, strict, yes } => add_run(&db, &path, strict, yes)?,
+        Command::AddRuns { dir, strict, yes } => add_runs(&db, &dir, strict, yes)?,
+        Command::RenameGame { old, new } => db
+            .rename_game(old, new)
+            .with_context(|| format!("couldn't rename game {old} to {new}"))?,
+        Command::RenameCategory { game, old, new } => db
+            .rename_category(game, old, new)
+            .with_context(|| format!("couldn't rename category {game}/{old} to {new}"))?,
+        Command::List { game } => list(&db, game)?,
+        Command::ListRuns { target, filters } => list_runs(&db, &target, &filters, &cfg.display)?,
+        Command::Stats { target } => stats(&db, &target, &cfg.display)?,
+        Command::Diff { target, a, b } => diff(&db, &target, a, b, &cfg.display)?,
+        Command::Backup { path } => db
+            .backup(&path)
+            .with_context(|| format!("couldn't back up to {}", path.display()))?,
+        Command::Restore { path, force } => db
+            .restore(&path, force)
+            .with_context(|| format!("couldn't restore from {}", path.display()))?,
+        Command::Fsck { repair } => fsck(&db, repair)?,
+        Command::Trend { target, output } => trend(&db, &target, output, &cfg.display)?,
+        Command::Schema { .. } => unreachable!("handled before opening the database"),
+    }
+    Ok(())
+}
+
+/// Prints the JSON schema for files of kind `kind` to standard output.
+fn print_schema(kind: schema::Kind) -> anyhow::Result<()> {
+    let schema = serde_json::to_string_pretty(&kind.schema())
+        .with_context(|| format!("couldn't serialise {kind} schema"))?;
+    println!("{schema}");
+    Ok(())
+}
+
+/// Applies pending migrations to `db` or, if `status_only`, shows them.
+fn migrate(db: &Db, status_only: bool) -> anyhow::Result<()> {
+    let status = db.migration_status()?;
+    if status_only {
+        println!("current version: {}", status.current);
+        println!("latest version: {}", status.latest);
+        for (version, migration) in (status.current + 1..).zip(status.pending()) {
+            println!("pending: {version}: {}", migration.description);
+        }
+    } else if status.is_up_to_date() {
+        println!("database is up to date (version {})", status.current);
+    } else {
+        let applied = db.migrate()?;
+        println!(
+            "applied {applied} migration(s); now at version {}",
+            status.latest
+        );
+    }
+    Ok(())
+}
+
+/// Checks the database for orphaned rows, deleting them if `repair` is set.
+fn fsck(db: &Db, repair: bool) -> anyhow::Result<()> {
+    let report = if repair { db.repair()? } else { db.check()? };
+    for (problem, count) in report.0.iter().filter(|(_, count)| 0 < **count) {
+        println!("{count} {problem}");
+    }
+    if report.is_clean() {
+        println!("no problems found");
+    } else if repair {
+        println!("deleted {} orphaned row(s)", report.total());
+    } else {
+        println!(
+            "found {} orphaned row(s); run with --repair to delete them",
+            report.total()
+        );
+    }
+    Ok(())
+}
+
+/// Adds the game at `path`, reading it as CSV with `csv` names if given and as TOML otherwise.
+fn add_game(db: &Db, path: &Path, csv: Option<&load::csv::Names>) -> anyhow::Result<()> {
+    let short = path
+        .file_stem()
+        .and_then(std::ffi::OsStr::to_str)
+        .with_context(|| format!("can't derive a game short name from {path:?}"))?;
+    short::Name::new(short).with_context(|| format!("bad game short name in {path:?}"))?;
+    let game = match csv {
+        Some(names) => load::csv::load(path, names)?,
+        None => game::Config::load(path)?,
+    };
+    db.add_game(short, &game)?;
+    Ok(())
+}
+
+/// Loads the run at `path`, checking that its cumulative time never goes backwards.
+///
+/// If `strict`, a run going backwards is an error; otherwise, it is a warning.
+fn load_run(
+    path: &Path,
+    strict: bool,
+) -> anyhow::Result<history::run::FullyTimed<ShortDescriptor>> {
+    let run = history::run::FullyTimed::from_toml_file(path)
+        .with_context(|| format!("couldn't load run from {path:?}"))?;
+
+    let backwards = run.timing.backwards_splits();
+    if !backwards.is_empty() {
+        let shorts = backwards
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if strict {
+            anyhow::bail!("cumulative time in {path:?} goes backwards at: {shorts}");
+        }
+        log::warn!("cumulative time in {path:?} goes backwards at: {shorts}");
+    }
+    Ok(run)
+}
+
+/// Resolves `wanted` to a category in the database, offering the closest existing category if
+/// there is no exact match.
+///
+/// The closest category is only used if `yes`, or if the user confirms it when asked; ties for
+/// closest are never used.
+fn resolve_category(
+    db: &Db,
+    wanted: ShortDescriptor,
+    yes: bool,
+) -> anyhow::Result<ShortDescriptor> {
+    let candidates = db.category_candidates(&wanted)?;
+    let best = match candidates.first() {
+        Some(c) if c.distance == 0 => return Ok(wanted),
+        Some(c) => c,
+        None => anyhow::bail!("there is no category {wanted}, and none close to it"),
+    };
+
+    let tied = candidates
+        .iter()
+        .take_while(|c| c.distance == best.distance)
+        .map(|c| c.target.short.to_string())
+        .collect::<Vec<_>>();
+    if 1 < tied.len() {
+        anyhow::bail!(
+            "there is no category {wanted}; did you mean one of: {}?",
+            tied.join(", ")
+        );
+    }
+
+    let suggestion = best.target.short;
+    let question = format!(
+        "there is no category {wanted}; use {suggestion} ({}: {}) instead?",
+        best.target.game, best.target.category
+    );
+    if yes || confirm(&question)? {
+        log::info!("using category {suggestion} in place of {wanted}");
+        Ok(suggestion)
+    } else {
+        anyhow::bail!("there is no category {wanted} (did you mean {suggestion}?)")
+    }
+}
+
+/// Asks the user `question` on standard error, returning whether they answered yes.
+///
+/// Anything other than a yes, including the end of input, counts as a no.
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn add_run(db: &Db, path: &Path, strict: bool, yes: bool) -> anyhow::Result<()> {
+    let run = load_run(path, strict)?;
+    let target = resolve_category(db, run.category_locator, yes)?;
+    db.add_run(&run.with_locator(target))?;
+    Ok(())
+}
+
+fn add_runs(db: &Db, dir: &Path, strict: bool, yes: bool) -> anyhow::Result<()> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .filter(|path| {
+            path.as_ref()
+                .map_or(true, |p| p.extension().map_or(false, |e| e == "toml"))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    // Load everything up front, so that a bad file stops us before we touch
+    // the database.
+    let runs = paths
+        .iter()
+        .map(|p| load_run(p, strict))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // Ask about each unknown category once, however many runs name it.
+    let mut resolved = std::collections::HashMap::new();
+    let runs = runs
+        .iter()
+        .map(|run| {
+            let wanted = run.category_locator;
+            let target = if let Some(target) = resolved.get(&wanted) {
+                *target
+            } else {
+                let target = resolve_category(db, wanted, yes)?;
+                resolved.insert(wanted, target);
+                target
+            };
+            Ok(run.with_locator(target))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let count = db
+        .add_runs(&runs)
+        .context("couldn't insert runs; no runs were added")?;
+
+    let mut per_target = std::collections::BTreeMap::<String, usize>::new();
+    for run in &runs {
+        *per_target
+            .entry(run.category_locator.to_string())
+            .or_default() += 1;
+    }
+
+    let mut w = tabwriter::TabWriter::new(io::stdout().lock());
+    for (target, n) in per_target {
+        writeln!(w, "{target}\t{n}")?;
+    }
+    writeln!(w, "total\t{count}")?;
+    Ok(w.flush()?)
+}
+
+fn list(db: &Db, game: Option<short::Name>) -> anyhow::Result<()> {
+    let targets = match game {
+        Some(game) => db.categories_of(game)?,
+        None => db.game_categories()?,
+    };
+
+    let mut w = tabwriter::TabWriter::new(io::stdout().lock());
+    for target in targets {
+        writeln!(w, "{}\t{}\t{}", target.short, target.game, target.category)?;
+    }
+    Ok(w.flush()?)
+}
+
+fn list_runs(
+    db: &Db,
+    target: &ShortDescriptor,
+    filters: &[(String, String)],
+    display: &Display,
+) -> anyhow::Result<()> {
+    let rounding = db.rounding(target)?;
+    let mut w = tabwriter::TabWriter::new(io::stdout().lock());
+    writeln!(w, "rank\tdate\ttotal\tcompleted")?;
+    let runs = db
+        .runs_for(target)?
+        .into_iter()
+        .filter(|run| filters.iter().all(|(k, v)| run.has_metadata(k, v)));
+    for run in runs {
+        writeln!(
+            w,
+            "{}\t{}\t{}\t{}",
+            run.timing.rank.map_or_else(String::new, |r| r.to_string()),
+            display.date(run.date),
+            display.time(Some(run.timing.total), rounding),
+            run.was_completed
+        )?;
+    }
+    Ok(w.flush()?)
+}
+
+/// Parses a metadata filter of the form `key=value`.
+fn parse_filter(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .ok_or_else(|| format!("expected a filter of the form key=value, got {s:?}"))
+}
+
+fn stats(db: &Db, target: &ShortDescriptor, display: &Display) -> anyhow::Result<()> {
+    let reader = db.reader()?;
+    let stats = reader
+        .inspect(target)
+        .and_then(|mut insp| insp.stats())
+        .with_context(|| format!("couldn't get stats for {target}"))?;
+    let rounding = db.rounding(target)?;
+
+    let mut w = tabwriter::TabWriter::new(io::stdout().lock());
+    writeln!(w, "attempts\t{}", stats.attempts)?;
+    if stats.attempts == 0 {
+        writeln!(w, "completed\t0")?;
+    } else {
+        writeln!(
+            w,
+            "completed\t{} ({}%)",
+            stats.completed,
+            stats.completed * 100 / stats.attempts
+        )?;
+    }
+    if stats.pb.is_some() {
+        writeln!(w, "personal best\t{}", display.time(stats.pb, rounding))?;
+    } else {
+        writeln!(w, "personal best\tno completed runs")?;
+    }
+    writeln!(
+        w,
+        "sum of best\t{}",
+        display.time(stats.sum_of_best, rounding)
+    )?;
+    writeln!(w, "average\t{}", display.time(stats.average, rounding))?;
+    writeln!(w, "median\t{}", display.time(stats.median, rounding))?;
+
+    if !stats.splits.is_empty() {
+        writeln!(w, "\nsplit\tbest\tworst\taverage")?;
+        for (short, split) in &stats.splits {
+            writeln!(
+                w,
+                "{short}\t{}\t{}\t{}",
+                display.time(Some(split.best), rounding),
+                display.time(Some(split.worst), rounding),
+                display.time(Some(split.average), rounding)
+            )?;
+        }
+    }
+    Ok(w.flush()?)
+}
+
+fn diff(
+    db: &Db,
+    target: &ShortDescriptor,
+    a: usize,
+    b: usize,
+    display: &Display,
+) -> anyhow::Result<()> {
+    let reader = db.reader()?;
+    let diff = reader
+        .inspect(target)
+        .and_then(|mut insp| insp.diff(a, b))
+        .with_context(|| format!("couldn't compare runs {a} and {b} of {target}"))?;
+    let rounding = db.rounding(target)?;
+
+    let mut w = tabwriter::TabWriter::new(io::stdout().lock());
+    writeln!(w, "split\t{a}\t{b}\tsplit delta\ttotal delta")?;
+    for (short, split) in &diff.splits {
+        writeln!(
+            w,
+            "{short}\t{}\t{}\t{}\t{}",
+            display.time(split.a.map(|a| a.split), rounding),
+            display.time(split.b.map(|b| b.split), rounding),
+            display.delta(split.split_delta(), rounding),
+            display.delta(split.cumulative_delta(), rounding)
+        )?;
+    }
+    Ok(w.flush()?)
+}
+
+fn trend(
+    db: &Db,
+    target: &ShortDescriptor,
+    output: Option<PathBuf>,
+    display: &Display,
+) -> anyhow::Result<()> {
+    let runs = db.runs_by_date(target)?;
+    if let Some(path) = output {
+        write_trend(std::fs::File::create(path)?, &runs, display)
+    } else {
+        write_trend(io::stdout().lock(), &runs, display)
+    }
+}
+
+fn write_trend<L>(
+    mut w: impl Write,
+    runs: &[history::run::Summary<L>],
+    display: &Display,
+) -> anyhow::Result<()> {
+    writeln!(w, "date,total_ms,was_completed")?;
+    for run in runs {
+        writeln!(
+            w,
+            "{},{},{}",
+            display.date(run.date),
+            run.timing.total.into_millis(),
+            run.was_completed
+        )?;
+    }
+    Ok(w.flush()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks that the clap app works properly.
+    #[test]
+    fn verify_app() {
+        use clap::IntoApp;
+        Args::command().debug_assert();
+    }
+}
@@ -1,8 +1,8 @@
 //! The zombiesplit server binary.
 
 use clap::Parser;
-use zombiesplit::model::game::category::ShortDescriptor;
-use zombiesplit::{cli, config::Server as Config, net};
+use zombiesplit::model::game::category::{PreferredComparison, ShortDescriptor};
+use zombiesplit::{cli, config, config::Server as Config, net};
 
 #[tokio::main]
 async fn main() {
@@ -19,18 +19,59 @@ struct Args {
     /// Use this system config file
     #[clap(short, long)]
     config: Option<std::path::PathBuf>,
+
+    /// Compare against this comparison (none, database, balanced, latest, or weighted), rather
+    /// than the category's or configured comparison
+    #[clap(long)]
+    comparison: Option<PreferredComparison>,
+
+    /// Compare against the goal times in this file, rather than the configured comparison
+    #[clap(short, long, conflicts_with = "vs-file")]
+    goal: Option<std::path::PathBuf>,
+
+    /// Compare against the comparison in this file, rather than the configured comparison
+    #[clap(long, conflicts_with = "goal")]
+    vs_file: Option<std::path::PathBuf>,
+
+    /// Treat entered times as cumulative times, rather than segment times
+    #[clap(long)]
+    cumulative: bool,
+
+    /// Resume the run in progress from the configured checkpoint directory, if there is one
+    #[clap(short, long)]
+    resume: bool,
 }
 
 async fn run() -> anyhow::Result<()> {
     env_logger::try_init()?;
 
     let args = Args::parse();
-    let cfg = Config::load(args.config)?;
+    let mut cfg = Config::load(args.config)?;
+    if let Some(comparison) = args.comparison {
+        cfg.comparison.provider = Some(comparison.into());
+    }
+    if let Some(goal) = args.goal {
+        cfg.comparison.provider = Some(config::server::comparison::Provider::Goal);
+        cfg.comparison.goal = Some(goal);
+    }
+    if let Some(file) = args.vs_file {
+        cfg.comparison.provider = Some(config::server::comparison::Provider::File);
+        cfg.comparison.file = Some(file);
+    }
+    cfg.checkpoint.resume |= args.resume;
+    if args.cumulative {
+        cfg.entry_mode = zombiesplit::model::session::EntryMode::Cumulative;
+    }
 
     let manager = net::server::Manager::new(cfg)?;
     let server = manager.server(&args.target)?;
 
-    server.run().await;
+    server
+        .run_until(net::server::shutdown::signal(
+            net::server::shutdown::DEFAULT_TIMEOUT,
+        ))
+        .await;
+    manager.shutdown();
     Ok(())
 }
 
@@ -1,6 +1,6 @@
 //! Utility functions for the zombiesplit command line interfaces.
 
-use crate::model::game::category::ShortDescriptor;
+use crate::model::{game::category::ShortDescriptor, short};
 use anyhow;
 use std::process::exit;
 use thiserror::Error;
@@ -16,10 +16,14 @@ pub fn handle_error(res: anyhow::Result<()>) {
 ///
 /// # Errors
 ///
-/// Fails if the match set does not have a 'game' key or a 'category' key.
+/// Fails if the match set does not have a 'game' key or a 'category' key, or if either isn't a
+/// valid short name.
 pub fn get_short_descriptor(matches: &clap::ArgMatches) -> Result<ShortDescriptor> {
-    let game = matches.value_of("game").ok_or(Error::Game)?;
-    let category = matches.value_of("category").ok_or(Error::Category)?;
+    let game: short::Name = matches.value_of("game").ok_or(Error::Game)?.parse()?;
+    let category: short::Name = matches
+        .value_of("category")
+        .ok_or(Error::Category)?
+        .parse()?;
     Ok(ShortDescriptor::new(game, category))
 }
 
@@ -35,6 +39,9 @@ pub enum Error {
     /// Error getting a run from the command line.
     #[error("no run provided")]
     Run,
+    /// A short name given on the command line was invalid.
+    #[error("invalid short name")]
+    Short(#[from] short::Error),
 }
 
 /// Shorthand for results over [Error].
@@ -1,7 +1,14 @@
 //! Main system configuration.
 
+pub mod checkpoint;
 pub mod comparison;
 mod db;
+pub mod discovery;
+pub mod display;
+pub mod metrics;
+pub mod observer;
+pub mod sound;
+pub mod trigger;
 
 use db::Database;
 use serde::{Deserialize, Serialize};
@@ -10,12 +17,39 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 #[serde(default)]
 pub struct Server {
+    /// Checkpoint configuration.
+    pub checkpoint: checkpoint::Checkpoint,
     /// The comparison configuration.
     pub comparison: comparison::Comparison,
     /// Database configuration.
     pub db: Database,
+    /// LAN discovery configuration.
+    pub discovery: discovery::Discovery,
+    /// Display configuration.
+    pub display: display::Display,
+    /// How times pushed to splits are interpreted.
+    pub entry_mode: crate::model::session::EntryMode,
+    /// The directory into which clients can export snapshots of the run, if any.
+    ///
+    /// Clients only name the file to export to, which always lands in this directory.  If this is
+    /// missing, snapshots can't be exported.
+    pub export_dir: Option<std::path::PathBuf>,
+    /// Metrics endpoint configuration.
+    pub metrics: metrics::Metrics,
+    /// What the server does when the last split of a run gets a time.
+    pub on_last_split: crate::model::session::OnLastSplit,
     /// Network configuration.
     pub net: Net,
+    /// Extra observers to attach to the server.
+    pub observers: Vec<observer::Observer>,
+    /// Buckets into which the server classifies splits by pace, in the order they are tried.
+    ///
+    /// These must cover every possible delta; the server checks this when it starts.
+    pub pace_buckets: crate::model::timing::comparison::pace::bucket::Buckets,
+    /// Sound cue configuration.
+    pub sounds: sound::Sounds,
+    /// Trigger endpoint configuration.
+    pub trigger: trigger::Trigger,
 }
 
 impl Server {
@@ -36,6 +70,20 @@ impl Server {
             .build()?
             .try_deserialize()
     }
+
+    /// Gets the address of the metrics endpoint, if it is enabled.
+    ///
+    /// This comes from the `[metrics]` table if it has an address, and otherwise from the first
+    /// metrics observer.
+    #[must_use]
+    pub fn metrics_address(&self) -> Option<std::net::SocketAddr> {
+        self.metrics.address.or_else(|| {
+            self.observers.iter().find_map(|o| match o {
+                observer::Observer::Metrics { address } => Some(*address),
+                _ => None,
+            })
+        })
+    }
 }
 
 /// Server network configuration.
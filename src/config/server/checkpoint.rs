@@ -0,0 +1,20 @@
+//! Checkpoint configuration for the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for checkpointing runs in progress.
+///
+/// If checkpointing is enabled, the server snapshots the run in progress to a file whenever its
+/// times change, so that the run can be recovered if the server crashes.
+#[derive(Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Checkpoint {
+    /// Directory in which to keep checkpoint files.
+    ///
+    /// If this is missing, which is the default, checkpointing is disabled.
+    pub dir: Option<std::path::PathBuf>,
+    /// Whether to resume the run in any checkpoint found on startup.
+    ///
+    /// If this is false, any such checkpoint is overwritten when the new run starts.
+    pub resume: bool,
+}
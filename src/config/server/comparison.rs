@@ -2,12 +2,44 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::model::game::category::PreferredComparison;
+
 /// Server configuration for comparisons.
-#[derive(Copy, Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
 #[serde(default)]
 pub struct Comparison {
     /// The configured provider for comparisons.
-    pub provider: Provider,
+    ///
+    /// If this isn't set, the server uses the category's preferred comparison, if it has one, and
+    /// otherwise doesn't compare against anything.
+    pub provider: Option<Provider>,
+    /// Providers for extra comparisons to show alongside the main one, in order.
+    ///
+    /// These never affect paces or colours, which come only from the main comparison.
+    pub overlays: Vec<Provider>,
+    /// Provider for a ghost: a past run replayed in real time alongside the current run.
+    ///
+    /// The ghost's run is chosen again, like the comparison, whenever the run resets.  If this
+    /// isn't set, there is no ghost.
+    pub ghost: Option<Provider>,
+    /// Half-life, in runs, of the [Provider::Weighted] provider.
+    ///
+    /// A run counts half as much as the run this many places after it.  If this isn't set, the
+    /// provider uses [crate::model::timing::comparison::weighted::DEFAULT_HALF_LIFE].
+    pub half_life: Option<u32>,
+    /// How far a split's time can stray from its comparison and still count as even ('close
+    /// call').
+    ///
+    /// Splits can override this in the game file.  If this isn't set, there are no close calls.
+    pub tolerance: Option<crate::model::timing::time::Time>,
+    /// Path to the goal file used by the [Provider::Goal] provider.
+    pub goal: Option<std::path::PathBuf>,
+    /// Path to the comparison file used by the [Provider::File] provider.
+    pub file: Option<std::path::PathBuf>,
+    /// Providers merged, highest-ranked first, by the [Provider::Composite] provider.
+    ///
+    /// None of these can themselves be composite.
+    pub composite: Vec<Provider>,
 }
 
 /// Enumerates the various up-front ways in which zombiesplit knows to source
@@ -24,6 +56,29 @@ pub enum Provider {
     None,
     /// Compare against the PB run in the database.
     Database,
+    /// Compare against the PB run in the database, balanced against the run history.
+    ///
+    /// This keeps the PB's total, but spreads it across the splits according to how each split
+    /// usually goes, so early leads aren't overstated.
+    Balanced,
+    /// Compare against the most recently dated run in the database, whether or not it finished.
+    ///
+    /// This follows each reset, so it always races the run just done.
+    Latest,
+    /// Compare against the run history in the database, weighted towards recent runs.
+    ///
+    /// Each split's time is a mean over every run that timed it, with older runs counting less;
+    /// the configured half-life sets how quickly they fade.
+    Weighted,
+    /// Compare against hand-authored goal times, loaded from the configured goal file.
+    Goal,
+    /// Compare against a comparison loaded from the configured comparison file.
+    File,
+    /// Compare against a merge of the configured composite providers.
+    ///
+    /// Each split takes its time from the first of those providers that has one, and cumulative
+    /// times are summed from the merged splits.
+    Composite,
 }
 
 /// By default, there are no comparisons.
@@ -32,3 +87,15 @@ impl Default for Provider {
         Self::None
     }
 }
+
+impl From<PreferredComparison> for Provider {
+    fn from(pref: PreferredComparison) -> Self {
+        match pref {
+            PreferredComparison::None => Self::None,
+            PreferredComparison::Database => Self::Database,
+            PreferredComparison::Balanced => Self::Balanced,
+            PreferredComparison::Latest => Self::Latest,
+            PreferredComparison::Weighted => Self::Weighted,
+        }
+    }
+}
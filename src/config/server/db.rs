@@ -9,12 +9,18 @@ use std::path::PathBuf;
 pub struct Database {
     /// The database location.
     pub path: PathBuf,
+    /// A directory of game files to load into the database at startup, if any.
+    ///
+    /// Each `*.toml` file in the directory is added as a game named after the file less its
+    /// extension, or updated if the game is already in the database.
+    pub games: Option<PathBuf>,
 }
 
 impl Default for Database {
     fn default() -> Self {
         Self {
             path: default_path(),
+            games: None,
         }
     }
 }
@@ -0,0 +1,41 @@
+//! LAN discovery configuration for the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for LAN discovery.
+///
+/// When enabled, the server broadcasts a small UDP announcement of its address and the
+/// game/category it is running every few seconds, so that clients on the same network can find it;
+/// see [crate::net::discovery].
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Discovery {
+    /// Address to which announcements should be broadcast, such as `255.255.255.255:1338`.
+    ///
+    /// If this is missing, which is the default, the server doesn't announce itself.
+    pub address: Option<std::net::SocketAddr>,
+    /// Number of seconds between announcements.
+    ///
+    /// Intervals below one second are raised to one second.
+    pub interval_secs: u64,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self {
+            address: None,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+        }
+    }
+}
+
+impl Discovery {
+    /// Gets the interval between announcements.
+    #[must_use]
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.interval_secs)
+    }
+}
+
+/// Default number of seconds between announcements.
+pub const DEFAULT_INTERVAL_SECS: u64 = 5;
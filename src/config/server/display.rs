@@ -0,0 +1,132 @@
+//! Display configuration, used when presenting stored data to the user.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+use crate::model::timing::time::{Format, Rounding, Time};
+
+/// Configuration for displaying stored data.
+#[derive(Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Display {
+    /// The time zone in which dates are displayed.
+    pub time_zone: TimeZone,
+    /// How times are laid out, in the same form as the UI's time layouts (such as `m:ss.uu`).
+    pub time_format: Format,
+}
+
+impl Display {
+    /// Formats `date` for display in the configured time zone.
+    #[must_use]
+    pub fn date(&self, date: DateTime<Utc>) -> String {
+        self.time_zone.localise(date).to_rfc3339()
+    }
+
+    /// Formats `time` for display in the configured time format, or `-` if there is no time.
+    ///
+    /// Digits the format doesn't show are rounded away according to `rounding`, which is usually
+    /// that of the category the time belongs to.
+    #[must_use]
+    pub fn time(&self, time: Option<Time>, rounding: Rounding) -> String {
+        time.map_or_else(|| "-".to_owned(), |t| self.time_format.format(t, rounding))
+    }
+
+    /// Formats `delta` for display, with its sign, in the configured time format, or `-` if there
+    /// is no delta.
+    ///
+    /// As with [Display::time], hidden digits are rounded according to `rounding`.
+    #[must_use]
+    pub fn delta(&self, delta: Option<Time>, rounding: Rounding) -> String {
+        delta.map_or_else(|| "-".to_owned(), |d| self.time_format.delta(d, rounding))
+    }
+}
+
+/// A time zone for displaying dates.
+///
+/// Dates are always stored in UTC; this only affects how they are shown.
+#[derive(Copy, Clone, SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq)]
+pub enum TimeZone {
+    /// The system's local time zone.
+    Local,
+    /// A time zone from the IANA database.
+    Named(chrono_tz::Tz),
+}
+
+/// By default, dates display in the system's local time zone.
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self::Local
+    }
+}
+
+impl TimeZone {
+    /// Converts `date` into this time zone.
+    ///
+    /// ```
+    /// use zombiesplit::config::server::display::TimeZone;
+    ///
+    /// let date = chrono::DateTime::parse_from_rfc3339("2021-06-11T18:25:48Z").unwrap();
+    /// let tz: TimeZone = "Europe/London".parse().unwrap();
+    /// let local = tz.localise(date.into());
+    /// assert_eq!("2021-06-11T19:25:48+01:00", local.to_rfc3339());
+    /// ```
+    #[must_use]
+    pub fn localise(self, date: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Self::Local => fix(&date.with_timezone(&Local)),
+            Self::Named(tz) => fix(&date.with_timezone(&tz)),
+        }
+    }
+}
+
+fn fix<Tz: chrono::TimeZone>(date: &DateTime<Tz>) -> DateTime<FixedOffset> {
+    let offset = date.offset().fix();
+    date.with_timezone(&offset)
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => f.write_str(LOCAL),
+            Self::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = Error;
+
+    /// Parses a time zone, which is either `local` or an IANA name.
+    ///
+    /// ```
+    /// use zombiesplit::config::server::display::TimeZone;
+    ///
+    /// assert_eq!(TimeZone::Local, "local".parse().unwrap());
+    /// assert_eq!("Asia/Tokyo", "Asia/Tokyo".parse::<TimeZone>().unwrap().to_string());
+    /// "Mars/Olympus_Mons".parse::<TimeZone>().expect_err("not a real time zone");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == LOCAL {
+            Ok(Self::Local)
+        } else {
+            s.parse()
+                .map(Self::Named)
+                .map_err(|e| Error::BadTimeZone(s.to_owned(), e))
+        }
+    }
+}
+
+/// Errors arising from display configuration.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A time zone wasn't `local` or a valid IANA name.
+    #[error("unknown time zone {0}: {1}")]
+    BadTimeZone(String, String),
+}
+
+/// The name used in configuration for the system's local time zone.
+const LOCAL: &str = "local";
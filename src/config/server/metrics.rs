@@ -0,0 +1,16 @@
+//! Metrics endpoint configuration for the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for the metrics endpoint.
+///
+/// The metrics endpoint serves the current run's times over HTTP, in the Prometheus text format,
+/// for use by external dashboards.
+#[derive(Copy, Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Metrics {
+    /// Address to which the metrics endpoint should be bound.
+    ///
+    /// If this is missing, which is the default, the endpoint is disabled.
+    pub address: Option<std::net::SocketAddr>,
+}
@@ -0,0 +1,54 @@
+//! Configuration for extra observers attached to the server.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::sound;
+
+/// Server configuration for one extra observer.
+///
+/// Observers are declared as an array of tables, each with a `type` saying which kind of observer
+/// it is, alongside that kind's own settings.  Any number of observers can be declared together,
+/// and all of them see every event from the session.  In TOML, this looks like:
+///
+/// ```toml
+/// [[observers]]
+/// type = "file"
+/// path = "events.log"
+///
+/// [[observers]]
+/// type = "sounds"
+/// [observers.cues]
+/// personal-best = "sounds/pb.ogg"
+///
+/// [[observers]]
+/// type = "metrics"
+/// address = "127.0.0.1:9090"
+/// ```
+///
+/// Unknown types are rejected when the configuration loads.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Observer {
+    /// Appends every event to a file, one per line.
+    File {
+        /// Path to the log file; it is created if it doesn't exist.
+        path: PathBuf,
+    },
+    /// Plays sound cues (see [sound::Sounds]).
+    Sounds {
+        /// The sound file to play for each cue.
+        #[serde(default)]
+        cues: sound::Sounds,
+    },
+    /// Serves the current run's times over HTTP (see [super::metrics::Metrics]).
+    ///
+    /// Only one metrics endpoint can be active; the `[metrics]` table, if it has an address, takes
+    /// priority over any observers of this type.
+    Metrics {
+        /// Address to which the metrics endpoint should be bound.
+        address: std::net::SocketAddr,
+    },
+}
@@ -0,0 +1,32 @@
+//! Sound cue configuration for the server.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for sound cues.
+///
+/// This maps the kinds of event that can trigger a sound to the sound file to play for each; kinds
+/// not in the map stay silent.  In TOML, this looks like:
+///
+/// ```toml
+/// [sounds]
+/// personal-best = "sounds/pb.ogg"
+/// pace-behind = "sounds/behind.wav"
+/// ```
+pub type Sounds = BTreeMap<Cue, PathBuf>;
+
+/// Enumerates the kinds of event that can trigger a sound.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+pub enum Cue {
+    /// The run finished ahead of its comparison (with a PB comparison, a new personal best).
+    PersonalBest,
+    /// A split was a personal best ('gold split').
+    GoldSplit,
+    /// The run moved ahead of its comparison.
+    PaceAhead,
+    /// The run fell behind its comparison.
+    PaceBehind,
+}
@@ -0,0 +1,16 @@
+//! Trigger endpoint configuration for the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Server configuration for the trigger endpoint.
+///
+/// The trigger endpoint lets external tools, such as autosplitters and streamdeck buttons, split
+/// and undo splits on the current split over a plain line-based TCP protocol.
+#[derive(Copy, Clone, Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[serde(default)]
+pub struct Trigger {
+    /// Address to which the trigger endpoint should be bound.
+    ///
+    /// If this is missing, which is the default, the endpoint is disabled.
+    pub address: Option<std::net::SocketAddr>,
+}
@@ -1,11 +1,15 @@
 //! Top-level module for the model's sqlite database.
 
+mod backup;
 pub mod category;
 pub mod comparison;
 pub mod error;
+pub mod fsck;
 mod game;
 mod init;
 pub mod inspect;
+pub mod migrate;
+mod rename;
 pub mod run;
 pub mod util;
 
@@ -62,6 +66,77 @@ impl Db {
         init::on_db(&self.manager.connect()?)
     }
 
+    /// Gets where the database stands against the schema migrations this
+    /// version of zombiesplit knows about.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the database hasn't been initialised, if its schema
+    /// is newer than this version of zombiesplit knows about, or if the
+    /// underlying SQL queries fail.
+    pub fn migration_status(&self) -> Result<migrate::Status> {
+        migrate::status(&self.manager.connect()?)
+    }
+
+    /// Applies any pending schema migrations, returning how many there were.
+    ///
+    /// Each migration is all-or-nothing.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the database hasn't been initialised, if its schema
+    /// is newer than this version of zombiesplit knows about, or if a
+    /// migration fails.
+    pub fn migrate(&self) -> Result<usize> {
+        migrate::run(&self.manager.connect()?)
+    }
+
+    /// Backs up the database to a new database file at `path`.
+    ///
+    /// The backup is consistent even if the database is in use.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from the database if the backup fails.
+    pub fn backup<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        backup::backup(&self.manager.connect()?, path)
+    }
+
+    /// Replaces the database with the backup at `path`.
+    ///
+    /// Unless `force` is set, this refuses to replace a database that has
+    /// already been initialised.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the backup's schema doesn't match the one this
+    /// version of zombiesplit expects, if the database is already initialised
+    /// and `force` isn't set, or if the restore fails.
+    pub fn restore<P: AsRef<Path>>(&self, path: P, force: bool) -> Result<()> {
+        backup::restore(&mut self.manager.connect()?, path, force)
+    }
+
+    /// Checks the database for orphaned rows, counting them by problem.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from the database if the check fails.
+    pub fn check(&self) -> Result<fsck::Report> {
+        fsck::check(&self.manager.connect()?)
+    }
+
+    /// Deletes orphaned rows from the database, counting them by problem.
+    ///
+    /// The repair happens in one transaction, so it either deletes every
+    /// orphan or nothing.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from the database if the repair fails.
+    pub fn repair(&self) -> Result<fsck::Report> {
+        fsck::repair(&mut self.manager.connect()?)
+    }
+
     /// Adds the game `game` to the database, assigning it shortname `short`.
     ///
     /// # Errors
@@ -75,6 +150,62 @@ impl Db {
         Ok(tx.commit()?)
     }
 
+    /// Adds the game `game` to the database under shortname `short`, or updates it if it is
+    /// already there.
+    ///
+    /// Existing splits, segments, and categories keep their runs; see [Self::add_game] for adding
+    /// games that must be new.  Returns `true` if the game was newly added.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if any of the SQL queries relating to inserting or
+    /// updating a game fail; the database is unchanged in that case.
+    pub fn upsert_game(&self, short: &str, game: &Config) -> Result<bool> {
+        let mut conn = self.manager.connect()?;
+        let tx = conn.transaction()?;
+        let added = game::Inserter::new(&tx)?.upsert_game(short, game)?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Renames the game with short name `old` to `new`.
+    ///
+    /// Runs of the game follow it to its new name.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if there is no game `old`, if there is already a game
+    /// `new`, or if any of the SQL queries fail; the database is unchanged in
+    /// that case.
+    pub fn rename_game(&self, old: model::short::Name, new: model::short::Name) -> Result<()> {
+        let mut conn = self.manager.connect()?;
+        let tx = conn.transaction()?;
+        rename::game(&tx, old, new)?;
+        Ok(tx.commit()?)
+    }
+
+    /// Renames the category with short name `old`, in the game with short
+    /// name `game`, to `new`.
+    ///
+    /// Runs of the category follow it to its new name.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if there is no such game or category, if the game
+    /// already has a category `new`, or if any of the SQL queries fail; the
+    /// database is unchanged in that case.
+    pub fn rename_category(
+        &self,
+        game: model::short::Name,
+        old: model::short::Name,
+        new: model::short::Name,
+    ) -> Result<()> {
+        let mut conn = self.manager.connect()?;
+        let tx = conn.transaction()?;
+        rename::category(&tx, game, old, new)?;
+        Ok(tx.commit()?)
+    }
+
     /// Adds the historic run `run` to the database.
     ///
     /// # Errors
@@ -89,6 +220,33 @@ impl Db {
         Ok(tx.commit()?)
     }
 
+    /// Adds every historic run in `runs` to the database in one transaction.
+    ///
+    /// If any run fails to insert, none of the runs are inserted.
+    /// Returns the number of runs inserted.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if any run's locator doesn't resolve, or if any of
+    /// the SQL queries relating to inserting a run fail.
+    pub fn add_runs<L: Locator>(&self, runs: &[history::run::FullyTimed<L>]) -> Result<usize> {
+        let runs = runs
+            .iter()
+            .map(|run| Ok(run.with_locator(self.resolve_gcid(&run.category_locator)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut conn = self.manager.connect()?;
+        let tx = conn.transaction()?;
+        {
+            let mut inserter = run::Inserter::new(&tx)?;
+            for run in &runs {
+                inserter.add(run)?;
+            }
+        }
+        tx.commit()?;
+        Ok(runs.len())
+    }
+
     /// Gets summaries for all game-category pairs in the database.
     ///
     /// # Errors
@@ -100,6 +258,35 @@ impl Db {
         getter.all_game_category_info()
     }
 
+    /// Finds the categories in the database that `wanted` most likely names, closest first.
+    ///
+    /// An exact match, if there is one, comes first with a distance of zero.  This never changes
+    /// the database or any run; see [category::fuzzy] for how candidates are chosen.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the underlying SQL query fails.
+    pub fn category_candidates(
+        &self,
+        wanted: &model::game::category::ShortDescriptor,
+    ) -> Result<Vec<category::fuzzy::Candidate>> {
+        Ok(category::fuzzy::candidates(wanted, self.game_categories()?))
+    }
+
+    /// Gets summaries for all categories of the game with short name `game`.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the underlying SQL query fails.
+    pub fn categories_of(
+        &self,
+        game: model::short::Name,
+    ) -> Result<Vec<model::game::category::Target>> {
+        let conn = self.manager.connect()?;
+        let mut getter = category::Getter::new(&conn)?;
+        getter.game_category_info_for(game)
+    }
+
     /// Gets summaries for the runs attached to the game-category located by
     /// `loc`.
     ///
@@ -113,6 +300,30 @@ impl Db {
         Ok(runs.into_iter().map(|x| x.item).collect())
     }
 
+    /// Gets summaries for the runs attached to the game-category located by
+    /// `loc`, ordered by the date on which they were archived.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if any of the SQL queries relating to getting a run
+    /// fail.
+    pub fn runs_by_date<L: Locator>(&self, loc: &L) -> Result<Vec<history::run::Summary<GcID>>> {
+        let id = self.resolve_gcid(loc)?;
+        let runs = run::Getter::new(&self.manager.connect()?)?.runs_by_date(id)?;
+        Ok(runs.into_iter().map(|x| x.item).collect())
+    }
+
+    /// Gets the rounding policy of the game-category located by `loc`.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if the game-category doesn't exist, or if the underlying SQL query fails.
+    pub fn rounding<L: Locator>(&self, loc: &L) -> Result<model::timing::time::Rounding> {
+        let conn = self.manager.connect()?;
+        let mut getter = category::Getter::new(&conn)?;
+        getter.rounding(loc)
+    }
+
     fn resolve_gcid<L: Locator>(&self, loc: &L) -> Result<GcID> {
         // TODO(@MattWindsor91): this is horrible.
         if let Some(x) = loc.as_game_category_id() {
@@ -0,0 +1,88 @@
+//! Database functionality for backing up and restoring the database.
+//!
+//! Both directions go through SQLite's online backup API, so a backup taken
+//! while the server is running is still consistent.
+
+use std::{hash::Hasher, path::Path, time::Duration};
+
+use rusqlite::{backup::Backup, Connection, DatabaseName, OpenFlags};
+
+use super::{
+    error::{Error, Result},
+    init,
+};
+
+/// How many pages to copy per backup step.
+const PAGES_PER_STEP: std::os::raw::c_int = 100;
+
+/// Backs up the database at `conn` to a new database file at `path`.
+///
+/// # Errors
+///
+/// Propagates any errors from the underlying SQL database.
+pub(super) fn backup(conn: &Connection, path: impl AsRef<Path>) -> Result<()> {
+    Ok(conn.backup(DatabaseName::Main, path, None)?)
+}
+
+/// Replaces the database at `conn` with the backup at `path`.
+///
+/// Unless `force` is set, this refuses to replace a database that already has
+/// a schema.
+///
+/// # Errors
+///
+/// Fails if the backup's schema differs from the one this version of
+/// zombiesplit uses, if the restore would overwrite data without `force`, or if
+/// the underlying SQL queries fail.
+pub(super) fn restore(conn: &mut Connection, path: impl AsRef<Path>, force: bool) -> Result<()> {
+    let src = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    if schema_fingerprint(&src)? != expected_fingerprint()? {
+        return Err(Error::IncompatibleBackup);
+    }
+    if !force && has_schema(conn)? {
+        return Err(Error::RestoreWouldOverwrite);
+    }
+
+    Backup::new(&src, conn)?.run_to_completion(PAGES_PER_STEP, Duration::ZERO, None)?;
+    Ok(())
+}
+
+/// Gets whether the database at `conn` has anything in it.
+fn has_schema(conn: &Connection) -> Result<bool> {
+    let count: i64 = conn.query_row(SQL_COUNT_SCHEMA, [], |r| r.get(0))?;
+    Ok(0 < count)
+}
+
+/// Hashes the schema of the database at `conn`.
+///
+/// Two databases have the same fingerprint exactly when they have the same
+/// tables, views, and indices, defined the same way.
+fn schema_fingerprint(conn: &Connection) -> Result<u64> {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut query = conn.prepare(SQL_SCHEMA)?;
+    let mut rows = query.query([])?;
+    while let Some(r) = rows.next()? {
+        hasher.write(r.get::<_, String>("type")?.as_bytes());
+        hasher.write(r.get::<_, String>("name")?.as_bytes());
+        hasher.write(
+            r.get::<_, Option<String>>("sql")?
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+    }
+    Ok(hasher.finish())
+}
+
+/// Hashes the schema that this version of zombiesplit creates on initialisation.
+fn expected_fingerprint() -> Result<u64> {
+    let conn = Connection::open_in_memory()?;
+    init::on_db(&conn)?;
+    schema_fingerprint(&conn)
+}
+
+const SQL_COUNT_SCHEMA: &str = "SELECT COUNT(*) FROM sqlite_master;";
+
+const SQL_SCHEMA: &str = "
+SELECT type, name, sql
+  FROM sqlite_master
+ ORDER BY type ASC, name ASC;";
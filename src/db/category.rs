@@ -1,5 +1,6 @@
 //! Module for database activities related to storing and querying categories.
 
+pub mod fuzzy;
 pub mod get;
 pub mod id;
 
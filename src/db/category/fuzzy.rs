@@ -0,0 +1,130 @@
+//! Fuzzy matching of game-category short descriptors.
+//!
+//! Runs name their category by short descriptor, which has to match the database exactly.  When it
+//! doesn't, this module finds the existing categories it most likely meant, so that tools can
+//! suggest them.  Nothing here applies a suggestion; that is always up to the caller, who should
+//! ask the user first.
+
+use crate::model::game::category::{ShortDescriptor, Target};
+
+/// A category that might be the one a short descriptor meant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    /// The existing category.
+    pub target: Target,
+    /// How far the category's short descriptor is from the one wanted.
+    ///
+    /// This is the number of single-character edits (ignoring case) needed to get from one to
+    /// the other, summed across the game and category.  Zero is an exact match.
+    pub distance: usize,
+}
+
+/// Ranks `targets` by how closely they match `wanted`, closest first.
+///
+/// Only targets within [max_distance] of `wanted` are candidates; ties go to the earlier target
+/// in `targets`.
+///
+/// ```
+/// use zombiesplit::{
+///     db::category::fuzzy,
+///     model::game::category::{ShortDescriptor, Target},
+/// };
+///
+/// let target = |game: &'static str, category: &'static str| Target {
+///     game: game.to_owned(),
+///     category: category.to_owned(),
+///     short: ShortDescriptor::new(game, category),
+/// };
+/// let targets = vec![target("scd11", "btg-sonic"), target("s3k", "sonic")];
+///
+/// let found = fuzzy::candidates(&ShortDescriptor::new("scd11", "btg_sonic"), targets);
+/// assert_eq!(1, found.len());
+/// assert_eq!(ShortDescriptor::new("scd11", "btg-sonic"), found[0].target.short);
+/// assert_eq!(1, found[0].distance);
+/// ```
+#[must_use]
+pub fn candidates(
+    wanted: &ShortDescriptor,
+    targets: impl IntoIterator<Item = Target>,
+) -> Vec<Candidate> {
+    let limit = max_distance(wanted);
+    let mut result: Vec<Candidate> = targets
+        .into_iter()
+        .map(|target| Candidate {
+            distance: descriptor_distance(wanted, &target.short),
+            target,
+        })
+        .filter(|c| c.distance <= limit)
+        .collect();
+    // Stable, so ties keep their original order.
+    result.sort_by_key(|c| c.distance);
+    result
+}
+
+/// Gets the furthest a category can be from `wanted` and still be a candidate for it.
+///
+/// This grows with the length of the descriptor, so that longer names can absorb more typos,
+/// but never reaches the point where any two short names would match.
+#[must_use]
+pub fn max_distance(wanted: &ShortDescriptor) -> usize {
+    let len = wanted.game.to_string().chars().count() + wanted.category.to_string().chars().count();
+    (len / 4).max(1)
+}
+
+fn descriptor_distance(a: &ShortDescriptor, b: &ShortDescriptor) -> usize {
+    edit_distance(&a.game.to_string(), &b.game.to_string())
+        + edit_distance(&a.category.to_string(), &b.category.to_string())
+}
+
+/// Gets the Levenshtein distance between `a` and `b`, ignoring case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+
+    // Only the previous row of the distance matrix is ever needed.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edit_distance_basics() {
+        assert_eq!(0, edit_distance("sonic", "SONIC"));
+        assert_eq!(1, edit_distance("sonic", "sonc"));
+        assert_eq!(1, edit_distance("btg-sonic", "btg_sonic"));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+        assert_eq!(5, edit_distance("", "tails"));
+    }
+
+    /// Exact matches come first, and far-off categories aren't candidates at all.
+    #[test]
+    fn ranking() {
+        let target = |game: &'static str, category: &'static str| Target {
+            game: game.to_owned(),
+            category: category.to_owned(),
+            short: ShortDescriptor::new(game, category),
+        };
+        let targets = vec![
+            target("scd11", "btg-tails"),
+            target("scd11", "btg-sonic"),
+            target("s3k", "knuckles"),
+        ];
+
+        let found = candidates(&ShortDescriptor::new("scd11", "btg-sonic"), targets);
+        let shorts: Vec<_> = found.iter().map(|c| c.target.short.to_string()).collect();
+        assert_eq!(vec!["scd11/btg-sonic"], shorts);
+        assert_eq!(0, found[0].distance);
+    }
+}
@@ -9,19 +9,24 @@ use rusqlite::named_params;
 
 use crate::model::{
     game::{
-        category::{AttemptInfo, ShortDescriptor, Target},
+        category::{AttemptInfo, PreferredComparison, ShortDescriptor, SplitTimePolicy, Target},
         Split,
     },
-    session, short,
+    history, session, short,
+    timing::time::Rounding,
 };
 
 /// Object for getting category information from the database.
 pub struct Getter<'conn> {
     query_info_all: rusqlite::Statement<'conn>,
+    query_info_for_game: rusqlite::Statement<'conn>,
     query_attempt_info: rusqlite::Statement<'conn>,
     query_info_by_short: rusqlite::Statement<'conn>,
     query_info_by_id: rusqlite::Statement<'conn>,
     query_splits: rusqlite::Statement<'conn>,
+    query_time_policy: rusqlite::Statement<'conn>,
+    query_comparison: rusqlite::Statement<'conn>,
+    query_rounding: rusqlite::Statement<'conn>,
 }
 
 impl<'conn> AsMut<Getter<'conn>> for Getter<'conn> {
@@ -34,10 +39,14 @@ impl<'conn> Getter<'conn> {
     pub(crate) fn new(conn: &'conn rusqlite::Connection) -> Result<Self> {
         Ok(Self {
             query_info_all: conn.prepare(SQL_INFO_ALL)?,
+            query_info_for_game: conn.prepare(SQL_INFO_FOR_GAME)?,
             query_info_by_short: conn.prepare(SQL_INFO_BY_SHORT)?,
             query_info_by_id: conn.prepare(SQL_INFO_BY_ID)?,
             query_attempt_info: conn.prepare(SQL_ATTEMPT_INFO)?,
             query_splits: conn.prepare(SQL_SPLITS)?,
+            query_time_policy: conn.prepare(SQL_TIME_POLICY)?,
+            query_comparison: conn.prepare(SQL_COMPARISON)?,
+            query_rounding: conn.prepare(SQL_ROUNDING)?,
         })
     }
 
@@ -48,15 +57,18 @@ impl<'conn> Getter<'conn> {
     /// Propagates any errors from the database.
     pub fn all_game_category_info(&mut self) -> Result<Vec<Target>> {
         self.query_info_all
-            .query_and_then([], |row| {
-                let g_short: short::Name = row.get("gshort")?;
-                let c_short: short::Name = row.get("cshort")?;
-                Ok(Target {
-                    game: row.get("gname")?,
-                    category: row.get("cname")?,
-                    short: ShortDescriptor::new(g_short, c_short),
-                })
-            })?
+            .query_and_then([], target_from_row)?
+            .collect()
+    }
+
+    /// Gets information records for all categories of the game with short name `game`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the database.
+    pub fn game_category_info_for(&mut self, game: short::Name) -> Result<Vec<Target>> {
+        self.query_info_for_game
+            .query_and_then(named_params![":game": game], target_from_row)?
             .collect()
     }
 
@@ -114,9 +126,57 @@ impl<'conn> Getter<'conn> {
             category: locator.locate(self)?.info,
             info: self.attempt_info(locator)?,
             splits: self.splits(locator)?,
+            time_policy: self.time_policy(locator)?,
+            rounding: self.rounding(locator)?,
+            metadata: history::run::Metadata::default(),
         })
     }
 
+    /// Gets the split time policy for a game/category located by `locator`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the database.
+    pub fn time_policy<L: Locator>(&mut self, locator: &L) -> Result<SplitTimePolicy> {
+        let game_category = locator.locate_gcid(self)?;
+        Ok(self
+            .query_time_policy
+            .query_row(named_params![":game_category": game_category], |row| {
+                row.get("time_policy")
+            })?)
+    }
+
+    /// Gets the rounding policy for a game/category located by `locator`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the database.
+    pub fn rounding<L: Locator>(&mut self, locator: &L) -> Result<Rounding> {
+        let game_category = locator.locate_gcid(self)?;
+        Ok(self
+            .query_rounding
+            .query_row(named_params![":game_category": game_category], |row| {
+                row.get("rounding")
+            })?)
+    }
+
+    /// Gets the preferred comparison, if any, for a game/category located by `locator`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from the database.
+    pub fn preferred_comparison<L: Locator>(
+        &mut self,
+        locator: &L,
+    ) -> Result<Option<PreferredComparison>> {
+        let game_category = locator.locate_gcid(self)?;
+        Ok(self
+            .query_comparison
+            .query_row(named_params![":game_category": game_category], |row| {
+                row.get("comparison")
+            })?)
+    }
+
     /// Gets attempt information for a game/category located by `locator`.
     ///
     /// # Errors
@@ -151,12 +211,24 @@ impl<'conn> Getter<'conn> {
                 Ok(Split::new(
                     row.get::<_, short::Name>("sshort")?,
                     &row.get::<_, String>("sname")?,
-                ))
+                )
+                .with_marker(row.get::<_, Option<String>>("smarker")?)
+                .with_tolerance(row.get("stolerance")?))
             })?
             .collect()
     }
 }
 
+fn target_from_row(row: &rusqlite::Row) -> Result<Target> {
+    let g_short: short::Name = row.get("gshort")?;
+    let c_short: short::Name = row.get("cshort")?;
+    Ok(Target {
+        game: row.get("gname")?,
+        category: row.get("cname")?,
+        short: ShortDescriptor::new(g_short, c_short),
+    })
+}
+
 const SQL_INFO_ALL: &str = "
 SELECT game.short     AS gshort
      , game.name      AS gname
@@ -167,6 +239,17 @@ SELECT game.short     AS gshort
        INNER JOIN category      USING(category_id)
  ORDER BY gshort ASC, cshort ASC;";
 
+const SQL_INFO_FOR_GAME: &str = "
+SELECT game.short     AS gshort
+     , game.name      AS gname
+     , category.short AS cshort
+     , category.name  AS cname
+  FROM game
+       INNER JOIN game_category USING(game_id)
+       INNER JOIN category      USING(category_id)
+ WHERE game.short = :game
+ ORDER BY cshort ASC;";
+
 const SQL_INFO_BY_SHORT: &str = "
 SELECT game_category_id AS gcid
      , game.name        AS gname
@@ -193,9 +276,11 @@ SELECT COUNT(*)                     AS total,
  WHERE game_category_id = :game_category;";
 
 const SQL_SPLITS: &str = "
-SELECT split_id    AS sid
-     , split.short AS sshort
-     , split.name  AS sname
+SELECT split_id           AS sid
+     , split.short        AS sshort
+     , split.name         AS sname
+     , split.marker       AS smarker
+     , split.tolerance_ms AS stolerance
   FROM split
        INNER JOIN segment_split    USING(split_id)
        INNER JOIN category_segment USING(segment_id)
@@ -203,3 +288,21 @@ SELECT split_id    AS sid
  WHERE game_category_id = :game_category
  ORDER BY category_segment.position ASC
         , segment_split.position    ASC;";
+
+const SQL_TIME_POLICY: &str = "
+SELECT category.time_policy AS time_policy
+  FROM game_category
+       INNER JOIN category USING(category_id)
+ WHERE game_category_id = :game_category;";
+
+const SQL_COMPARISON: &str = "
+SELECT category.comparison AS comparison
+  FROM game_category
+       INNER JOIN category USING(category_id)
+ WHERE game_category_id = :game_category;";
+
+const SQL_ROUNDING: &str = "
+SELECT category.rounding AS rounding
+  FROM game_category
+       INNER JOIN category USING(category_id)
+ WHERE game_category_id = :game_category;";
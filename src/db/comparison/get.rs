@@ -63,6 +63,7 @@ impl<'conn> Getter<'conn> {
 
         let splits = cat_get.splits(&gcid)?;
         Ok(Comparison {
+            name: None,
             splits: self.splits(gcid, &splits, pb_full)?,
             run,
         })
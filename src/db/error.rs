@@ -29,6 +29,45 @@ pub enum Error {
     #[error("couldn't find split {short} requested by run")]
     MissingRunSplit { short: short::Name },
 
+    /// A run referenced splits that aren't part of its category.
+    #[error("run has splits not in its category: {}", display_shorts(.shorts))]
+    UnknownRunSplits { shorts: Vec<short::Name> },
+
+    /// A rename referred to a game not in the database.
+    #[error("couldn't find game {short}")]
+    MissingGame { short: short::Name },
+
+    /// A rename referred to a category not in the database.
+    #[error("couldn't find category {short} of game {game}")]
+    MissingCategory {
+        game: short::Name,
+        short: short::Name,
+    },
+
+    /// A rename targeted a name that is already in use.
+    #[error("the name {short} is already taken")]
+    NameTaken { short: short::Name },
+
+    /// A query referred to a run index with no run behind it.
+    #[error("couldn't find run {index}")]
+    MissingRun { index: usize },
+
+    /// A backup's schema doesn't match the one this version of zombiesplit uses.
+    #[error("the backup isn't compatible with this version of zombiesplit")]
+    IncompatibleBackup,
+
+    /// A restore would have overwritten an existing database.
+    #[error("the database already exists, and won't be overwritten")]
+    RestoreWouldOverwrite,
+
+    /// The database hasn't been initialised yet.
+    #[error("the database hasn't been initialised")]
+    Uninitialised,
+
+    /// The database's schema is newer than this version of zombiesplit understands.
+    #[error("the database is at schema version {current}, but this version of zombiesplit only knows up to {latest}")]
+    SchemaTooNew { current: usize, latest: usize },
+
     #[error("bad run timestamp: {0}")]
     BadRunTimestamp(i64),
 
@@ -36,5 +75,13 @@ pub enum Error {
     Lock,
 }
 
+fn display_shorts(shorts: &[short::Name]) -> String {
+    shorts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Shorthand for a result over [Error].
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,145 @@
+//! Database integrity checks.
+//!
+//! SQLite doesn't enforce the schema's foreign keys unless asked to, so failed
+//! inserts or hand edits can leave rows pointing at rows that no longer exist.
+//! This module finds such orphans and, on request, deletes them.
+
+use std::{collections::BTreeMap, fmt, ops::Deref};
+
+use rusqlite::Connection;
+
+use super::error::Result;
+
+/// A kind of inconsistency that can creep into the database.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Problem {
+    /// A run whose game-category is missing.
+    RunWithoutCategory,
+    /// A run split whose run is missing.
+    RunSplitWithoutRun,
+    /// A run split whose split is missing.
+    RunSplitWithoutSplit,
+    /// A split time whose run split is missing.
+    TimeWithoutRunSplit,
+    /// A piece of run metadata whose run is missing.
+    MetadataWithoutRun,
+}
+
+impl Problem {
+    /// Every problem, in the order in which repairs fix them.
+    ///
+    /// Deleting an orphan can orphan the rows that refer to it, so problems
+    /// higher up the schema come first.
+    pub const ALL: &'static [Problem] = &[
+        Problem::RunWithoutCategory,
+        Problem::RunSplitWithoutRun,
+        Problem::RunSplitWithoutSplit,
+        Problem::TimeWithoutRunSplit,
+        Problem::MetadataWithoutRun,
+    ];
+
+    /// Gets the table holding rows with this problem, and the condition
+    /// picking them out.
+    const fn orphans(self) -> (&'static str, &'static str) {
+        match self {
+            Problem::RunWithoutCategory => (
+                "run",
+                "game_category_id IS NULL
+                 OR game_category_id NOT IN (SELECT game_category_id FROM game_category)",
+            ),
+            Problem::RunSplitWithoutRun => ("run_split", "run_id NOT IN (SELECT run_id FROM run)"),
+            Problem::RunSplitWithoutSplit => {
+                ("run_split", "split_id NOT IN (SELECT split_id FROM split)")
+            }
+            Problem::TimeWithoutRunSplit => (
+                "run_split_time",
+                "run_split_id NOT IN (SELECT run_split_id FROM run_split)",
+            ),
+            Problem::MetadataWithoutRun => {
+                ("run_metadata", "run_id NOT IN (SELECT run_id FROM run)")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Problem::RunWithoutCategory => "runs without a category",
+            Problem::RunSplitWithoutRun => "run splits without a run",
+            Problem::RunSplitWithoutSplit => "run splits without a split",
+            Problem::TimeWithoutRunSplit => "split times without a run split",
+            Problem::MetadataWithoutRun => "run metadata without a run",
+        })
+    }
+}
+
+/// The number of rows found (or deleted) for each problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report(pub BTreeMap<Problem, usize>);
+
+impl Report {
+    /// Gets the number of rows with `problem`.
+    #[must_use]
+    pub fn count(&self, problem: Problem) -> usize {
+        self.0.get(&problem).copied().unwrap_or_default()
+    }
+
+    /// Gets the number of rows with any problem.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+
+    /// Gets whether there were no problems.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+/// Counts the rows with each problem in the database at `conn`.
+///
+/// A row with more than one problem counts towards each of them.
+///
+/// # Errors
+///
+/// Fails if the underlying SQL queries fail.
+pub(super) fn check<C: Deref<Target = Connection>>(conn: C) -> Result<Report> {
+    let mut report = Report::default();
+    for problem in Problem::ALL {
+        let (table, condition) = problem.orphans();
+        let sql = format!("SELECT COUNT(*) FROM {table} WHERE {condition};");
+        let count: i64 = conn.query_row(&sql, [], |r| r.get(0))?;
+        report
+            .0
+            .insert(*problem, usize::try_from(count).unwrap_or_default());
+    }
+    Ok(report)
+}
+
+/// Deletes the rows with each problem in the database at `conn`, returning
+/// how many were deleted for each.
+///
+/// Deleting an orphan can orphan the rows that refer to it; these are deleted
+/// too, and count towards their own problem.  The repair is all-or-nothing.
+///
+/// # Errors
+///
+/// Fails if the underlying SQL queries fail; the database is unchanged in that
+/// case.
+pub(super) fn repair(conn: &mut Connection) -> Result<Report> {
+    let tx = conn.transaction()?;
+    let mut report = Report::default();
+    for problem in Problem::ALL {
+        let (table, condition) = problem.orphans();
+        let deleted = tx.execute(&format!("DELETE FROM {table} WHERE {condition};"), [])?;
+        if 0 < deleted {
+            log::info!("deleted {deleted} {problem}");
+        }
+        report.0.insert(*problem, deleted);
+    }
+    tx.commit()?;
+    Ok(report)
+}
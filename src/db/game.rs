@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use rusqlite::{named_params, Transaction};
+use rusqlite::{named_params, OptionalExtension, Transaction};
 
 use super::error::{Error, Result};
 use crate::model::{game, short};
@@ -22,14 +22,51 @@ pub(super) struct Inserter<'conn, 'tx> {
 }
 
 const SQL_GAME: &str = "INSERT INTO game (short, name) VALUES (:short, :name);";
-const SQL_CATEGORY: &str = "INSERT INTO category (short, name) VALUES (:short, :name);";
+const SQL_CATEGORY: &str =
+    "INSERT INTO category (short, name, time_policy, comparison, rounding) VALUES (:short, :name, :time_policy, :comparison, :rounding);";
 const SQL_SEGMENT: &str = "INSERT INTO segment (short, name) VALUES (:short, :name);";
-const SQL_SPLIT: &str = "INSERT INTO split (short, name) VALUES (:short, :name);";
+const SQL_SPLIT: &str = "INSERT INTO split (short, name, marker, tolerance_ms) VALUES (:short, :name, :marker, :tolerance_ms);";
 const SQL_GAME_CATEGORY: &str =
     "INSERT INTO game_category (game_id, category_id) VALUES (:game_id, :category_id);";
 const SQL_CATEGORY_SEGMENT: &str = "INSERT INTO category_segment (category_id, segment_id, position) VALUES (:category_id, :segment_id, :position);";
 const SQL_SEGMENT_SPLIT: &str = "INSERT INTO segment_split (segment_id, split_id, position) VALUES (:segment_id, :split_id, :position);";
 
+const SQL_GAME_ID: &str = "SELECT game_id FROM game WHERE short = :short;";
+const SQL_UPDATE_GAME: &str = "UPDATE game SET name = :name WHERE game_id = :game_id;";
+const SQL_UPDATE_CATEGORY: &str =
+    "UPDATE category SET name = :name, time_policy = :time_policy, comparison = :comparison, rounding = :rounding WHERE category_id = :category_id;";
+const SQL_UPDATE_SEGMENT: &str = "UPDATE segment SET name = :name WHERE segment_id = :segment_id;";
+const SQL_UPDATE_SPLIT: &str =
+    "UPDATE split SET name = :name, marker = :marker, tolerance_ms = :tolerance_ms WHERE split_id = :split_id;";
+const SQL_UNLINK_CATEGORY_SEGMENTS: &str =
+    "DELETE FROM category_segment WHERE category_id = :category_id;";
+const SQL_UNLINK_SEGMENT_SPLITS: &str = "DELETE FROM segment_split WHERE segment_id = :segment_id;";
+
+/// Finds the short name and ID of every category of a game.
+const SQL_GAME_CATEGORIES: &str = "
+SELECT category.short AS short
+     , category_id    AS id
+  FROM category
+       INNER JOIN game_category USING (category_id)
+ WHERE game_id = :game_id;";
+/// Finds the short name and ID of every segment used by a game's categories.
+const SQL_GAME_SEGMENTS: &str = "
+SELECT DISTINCT segment.short AS short
+              , segment_id    AS id
+  FROM segment
+       INNER JOIN category_segment USING (segment_id)
+       INNER JOIN game_category    USING (category_id)
+ WHERE game_id = :game_id;";
+/// Finds the short name and ID of every split used by a game's categories.
+const SQL_GAME_SPLITS: &str = "
+SELECT DISTINCT split.short AS short
+              , split_id    AS id
+  FROM split
+       INNER JOIN segment_split    USING (split_id)
+       INNER JOIN category_segment USING (segment_id)
+       INNER JOIN game_category    USING (category_id)
+ WHERE game_id = :game_id;";
+
 #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
 enum Query {
     Game,
@@ -78,6 +115,117 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         Ok(())
     }
 
+    /// Adds the game `game` to the database under shortname `short` or, if there is already a
+    /// game with that shortname, brings it up to date with `game`.
+    ///
+    /// Updating matches splits, segments, and categories by short name, keeping their database
+    /// IDs so that existing runs stay attached to them; it updates their names and orderings, and
+    /// adds anything new.  Nothing is removed, as runs may still refer to it.
+    ///
+    /// Returns `true` if the game was newly added.
+    ///
+    /// # Errors
+    ///
+    /// Raises an error if any of the SQL queries relating to inserting or
+    /// updating a game fail.
+    pub fn upsert_game(&mut self, short: &str, game: &game::Config) -> Result<bool> {
+        let existing: Option<i64> = self
+            .tx
+            .query_row(SQL_GAME_ID, named_params![":short": short], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        if let Some(game_id) = existing {
+            self.update_game(short, game_id, game)?;
+            Ok(false)
+        } else {
+            self.add_game(short, game)?;
+            Ok(true)
+        }
+    }
+
+    fn update_game(&mut self, short: &str, game_id: i64, game: &game::Config) -> Result<()> {
+        log::info!("updating game {short} (ID {game_id})");
+        self.game_id = game_id;
+        self.tx.execute(
+            SQL_UPDATE_GAME,
+            named_params![":game_id": game_id, ":name": game.name],
+        )?;
+
+        self.split_ids = self.existing_ids(SQL_GAME_SPLITS)?;
+        self.segment_ids = self.existing_ids(SQL_GAME_SEGMENTS)?;
+        let category_ids = self.existing_ids(SQL_GAME_CATEGORIES)?;
+
+        for (short, split) in &game.splits {
+            if let Some(split_id) = self.split_ids.get(short) {
+                self.tx.execute(
+                    SQL_UPDATE_SPLIT,
+                    named_params![
+                        ":split_id": split_id,
+                        ":name": split.name,
+                        ":marker": split.marker,
+                        ":tolerance_ms": split.tolerance
+                    ],
+                )?;
+            } else {
+                self.add_split(*short, split)?;
+            }
+        }
+
+        for (short, segment) in &game.segments {
+            let segment_id = if let Some(segment_id) = self.segment_ids.get(short).copied() {
+                self.tx.execute(
+                    SQL_UPDATE_SEGMENT,
+                    named_params![":segment_id": segment_id, ":name": segment.name],
+                )?;
+                self.tx.execute(
+                    SQL_UNLINK_SEGMENT_SPLITS,
+                    named_params![":segment_id": segment_id],
+                )?;
+                segment_id
+            } else {
+                self.add_segment_main(*short, segment)?
+            };
+            self.add_splits_to_segment(segment_id, segment)?;
+        }
+
+        for (short, category) in &game.categories {
+            let category_id = if let Some(category_id) = category_ids.get(short).copied() {
+                self.tx.execute(
+                    SQL_UPDATE_CATEGORY,
+                    named_params![
+                        ":category_id": category_id,
+                        ":name": category.name,
+                        ":time_policy": category.time_policy,
+                        ":comparison": category.comparison,
+                        ":rounding": category.rounding
+                    ],
+                )?;
+                self.tx.execute(
+                    SQL_UNLINK_CATEGORY_SEGMENTS,
+                    named_params![":category_id": category_id],
+                )?;
+                category_id
+            } else {
+                let category_id = self.add_category_main(*short, category)?;
+                self.add_category_to_game(category_id)?;
+                category_id
+            };
+            self.add_segments_to_category(category_id, category)?;
+        }
+        Ok(())
+    }
+
+    /// Runs `sql`, a query over the current game, to map short names to database IDs.
+    fn existing_ids(&self, sql: &str) -> Result<short::Map<i64>> {
+        self.tx
+            .prepare(sql)?
+            .query_and_then(named_params![":game_id": self.game_id], |row| {
+                Ok((row.get("short")?, row.get("id")?))
+            })?
+            .collect()
+    }
+
     /// Adds the game `game` to the database, assigning it shortname `short`.
     ///
     /// # Errors
@@ -118,8 +266,12 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
     fn add_split(&mut self, short: short::Name, split: &game::config::Split) -> Result<()> {
         log::info!("adding split {short} ('{}')", split.name);
 
-        self.query(Query::Split)
-            .execute(named_params![":short": short, ":name": split.name])?;
+        self.query(Query::Split).execute(named_params![
+            ":short": short,
+            ":name": split.name,
+            ":marker": split.marker,
+            ":tolerance_ms": split.tolerance
+        ])?;
 
         let split_id = self.tx.last_insert_rowid();
         log::info!("split {short} -> ID {split_id}");
@@ -212,8 +364,13 @@ impl<'conn, 'tx> Inserter<'conn, 'tx> {
         category: &game::config::Category,
     ) -> Result<i64> {
         log::info!("adding category {} for game ID {}", short, self.game_id);
-        self.query(Query::Category)
-            .execute(named_params![":short": short, ":name": category.name])?;
+        self.query(Query::Category).execute(named_params![
+            ":short": short,
+            ":name": category.name,
+            ":time_policy": category.time_policy,
+            ":comparison": category.comparison,
+            ":rounding": category.rounding
+        ])?;
 
         let categoryid = self.tx.last_insert_rowid();
         log::info!("category {short} -> ID {categoryid}");
@@ -1,19 +1,22 @@
 //! SQL for initialising the database.
 
-use super::error::Result;
+use super::{error::Result, migrate};
 use std::ops::Deref;
 
-/// Initialises the database at `conn`.
+/// Initialises the database at `conn`, bringing it up to the latest schema version.
 ///
 /// # Errors
 ///
 /// Propagates any errors from the underlying SQL database.
 pub fn on_db<C: Deref<Target = rusqlite::Connection>>(conn: C) -> Result<()> {
     conn.execute_batch(SCHEMA)?;
+    migrate::run(conn)?;
     Ok(())
 }
 
-/// The zombiesplit database schema.
+/// The zombiesplit database schema, as it stood before schema versioning.
+///
+/// Don't change this; add a migration in [migrate] instead.
 const SCHEMA: &str = "
 BEGIN;
 
@@ -1,11 +1,11 @@
 //! High-level database inspection queries.
 
 use super::{
-    super::model::{history, session, timing},
+    super::model::{history, session, short, timing},
     category::{self, id::InfoWithID},
     comparison, run,
     util::WithID,
-    Result,
+    Error, Result,
 };
 
 /// Inspects various aspects of the database for a given game-category ID.
@@ -43,9 +43,143 @@ impl<'db> timing::comparison::provider::Provider for Inspector<'db> {
                 .map_err(anyhow::Error::from)?,
         ))
     }
+
+    /// The revision changes whenever any run in the database changes.
+    fn revision(
+        &mut self,
+    ) -> std::result::Result<Option<u64>, timing::comparison::provider::Error> {
+        Ok(Some(self.run.revision().map_err(anyhow::Error::from)?))
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Personal Best".to_owned())
+    }
+}
+
+impl<'db> timing::comparison::balanced::Source for Inspector<'db> {
+    fn segment_history(
+        &mut self,
+    ) -> std::result::Result<
+        timing::comparison::balanced::History,
+        timing::comparison::provider::Error,
+    > {
+        Ok(self.segment_history().map_err(anyhow::Error::from)?)
+    }
+}
+
+impl<'db> timing::comparison::latest::Source for Inspector<'db> {
+    fn latest_run(
+        &mut self,
+    ) -> std::result::Result<Option<history::timing::Totals>, timing::comparison::provider::Error>
+    {
+        Ok(self.latest_run().map_err(anyhow::Error::from)?)
+    }
+}
+
+impl<'db> timing::comparison::weighted::Source for Inspector<'db> {
+    fn dated_history(
+        &mut self,
+    ) -> std::result::Result<
+        timing::comparison::weighted::History,
+        timing::comparison::provider::Error,
+    > {
+        Ok(self.dated_history().map_err(anyhow::Error::from)?)
+    }
 }
 
 impl<'db> Inspector<'db> {
+    /// Gets the split totals of the most recently dated run in this game-category, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns any database errors occurring during the query.
+    pub fn latest_run(&mut self) -> Result<Option<history::timing::Totals>> {
+        self.run
+            .latest_run(self.info.id)?
+            .map(|run| self.run.split_totals_for(run.id))
+            .transpose()
+    }
+
+    /// Gets, for each split in this game-category, its time in every run that timed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns any database errors occurring during the listing.
+    pub fn segment_history(&mut self) -> Result<timing::comparison::balanced::History> {
+        let mut history = timing::comparison::balanced::History::default();
+        for run in self.run.runs_for(self.info.id)? {
+            for (short, time) in self.run.split_totals_for(run.id)?.totals {
+                history.entry(short).or_default().push(time);
+            }
+        }
+        Ok(history)
+    }
+
+    /// Gets the split totals of every run in this game-category, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns any database errors occurring during the listing.
+    pub fn dated_history(&mut self) -> Result<timing::comparison::weighted::History> {
+        self.run
+            .runs_by_date(self.info.id)?
+            .into_iter()
+            .map(|run| self.run.split_totals_for(run.id))
+            .collect()
+    }
+
+    /// Gets the best segment time ever recorded for each split in this game-category.
+    ///
+    /// # Errors
+    ///
+    /// Returns any database errors occurring during the query.
+    pub fn best_segments(&mut self) -> Result<short::Map<timing::time::Time>> {
+        self.comparison.split_pbs(self.info.id)
+    }
+
+    /// Gets aggregate statistics over every run of this game-category.
+    ///
+    /// # Errors
+    ///
+    /// Returns any database errors occurring during the listing.
+    pub fn stats(&mut self) -> Result<history::Stats> {
+        let runs: Vec<_> = self
+            .run
+            .runs_for(self.info.id)?
+            .into_iter()
+            .map(|x| x.item)
+            .collect();
+        let history = self.segment_history()?;
+        let attempt = self.cat.run(&self.info)?;
+        let order = attempt.splits.iter().map(|s| s.info.short);
+        Ok(history::Stats::new(&runs, &history, order))
+    }
+
+    /// Compares the runs at indices `a` and `b` (ordered by timestamp) split by split.
+    ///
+    /// Indices start at zero.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no run at either index, or with any database errors occurring during the
+    /// listing.
+    pub fn diff(&mut self, a: usize, b: usize) -> Result<history::Diff> {
+        let a = self.split_totals_at(a)?;
+        let b = self.split_totals_at(b)?;
+        let attempt = self.cat.run(&self.info)?;
+        let order = attempt.splits.iter().map(|s| s.info.short);
+        Ok(history::Diff::new(&a, &b, order))
+    }
+
+    /// Gets the split totals of the run at `index` (ordered by timestamp).
+    fn split_totals_at(&mut self, index: usize) -> Result<history::timing::Totals> {
+        let run = self
+            .run
+            .run_at(self.info.id, index)?
+            .ok_or(Error::MissingRun { index })?;
+        self.run.split_totals_for(run.id)
+    }
+
     /// Initialises an attempt session for the game/category referred to by
     /// `desc`, and with the given observer and sink.
     ///
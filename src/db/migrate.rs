@@ -0,0 +1,228 @@
+//! Schema migrations.
+//!
+//! The schema in [super::init] is the schema as it stood before zombiesplit
+//! tracked schema versions.  Every change since then is a [Migration] in
+//! [MIGRATIONS]; a database is at version `n` once it has had the first `n`
+//! migrations applied.  Initialising a database applies every migration, so
+//! new and migrated databases end up with the same schema.
+
+use std::ops::Deref;
+
+use rusqlite::{Connection, OptionalExtension};
+
+use super::error::{Error, Result};
+
+/// A single, ordered change to the database schema.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Migration {
+    /// A short, human-readable description of what the migration does.
+    pub description: &'static str,
+    /// The SQL that performs the migration.
+    sql: &'static str,
+}
+
+/// Every migration, in the order in which they apply.
+///
+/// Only ever append to this list: existing databases record how far along it
+/// they are.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "add split time policies to categories",
+        sql: "
+ALTER TABLE category
+  ADD COLUMN time_policy TEXT NOT NULL DEFAULT 'accumulate';  -- what to do when pushing to a split with times",
+    },
+    Migration {
+        description: "add markers to splits",
+        sql: "
+ALTER TABLE split
+  ADD COLUMN marker TEXT;  -- optional short marker, such as a boss indicator",
+    },
+    Migration {
+        description: "record time spent paused in runs",
+        sql: "
+ALTER TABLE run
+  ADD COLUMN paused_ms INTEGER NOT NULL DEFAULT 0;  -- real time spent paused, in milliseconds",
+    },
+    Migration {
+        description: "record skipped splits in runs",
+        sql: "
+ALTER TABLE run_split
+  ADD COLUMN is_skipped INTEGER NOT NULL DEFAULT 0 CHECK(is_skipped = 0 OR is_skipped = 1);  -- 0 = not skipped, 1 = skipped",
+    },
+    Migration {
+        description: "add run metadata",
+        sql: "
+-- Logs arbitrary key/value metadata attached to a run.
+CREATE TABLE
+    run_metadata
+        ( run_metadata_id INTEGER PRIMARY KEY
+        , run_id          INTEGER NOT NULL REFERENCES run
+        , key             TEXT    NOT NULL
+        , value           TEXT    NOT NULL
+        , UNIQUE(run_id, key)                 -- each run can have each key at most once
+        );",
+    },
+    Migration {
+        description: "add preferred comparisons to categories",
+        sql: "
+ALTER TABLE category
+  ADD COLUMN comparison TEXT;  -- comparison to run against by default, if any",
+    },
+    Migration {
+        description: "add rounding policies to categories",
+        sql: "
+ALTER TABLE category
+  ADD COLUMN rounding TEXT NOT NULL DEFAULT 'truncate';  -- how to round times displayed at lower precision",
+    },
+    Migration {
+        description: "record adjustments to split times in runs",
+        sql: "
+-- Adjustments are stored as split times, so that split totals include them, but flagged so that
+-- they stay distinguishable from the times that were actually entered.
+ALTER TABLE run_split_time
+  ADD COLUMN is_adjustment INTEGER NOT NULL DEFAULT 0 CHECK(is_adjustment = 0 OR is_adjustment = 1);  -- 0 = entered time, 1 = adjustment",
+    },
+    Migration {
+        description: "add close-call tolerances to splits",
+        sql: "
+ALTER TABLE split
+  ADD COLUMN tolerance_ms INTEGER;  -- optional override of the close-call tolerance, in milliseconds",
+    },
+    Migration {
+        description: "count changes to run data",
+        sql: "
+-- Bumped, by the triggers below, in the same transaction as every change to a run's data, so
+-- that readers can tell cheaply whether anything has changed since they last looked.
+CREATE TABLE
+    run_revision
+        ( run_revision_id  INTEGER PRIMARY KEY CHECK(run_revision_id = 0)  -- there is only ever one row
+        , revision         INTEGER NOT NULL
+        );
+INSERT INTO run_revision (run_revision_id, revision) VALUES (0, 0);
+
+CREATE TRIGGER run_revision_run_insert AFTER INSERT ON run
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_update AFTER UPDATE ON run
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_delete AFTER DELETE ON run
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_insert AFTER INSERT ON run_split
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_update AFTER UPDATE ON run_split
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_delete AFTER DELETE ON run_split
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_time_insert AFTER INSERT ON run_split_time
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_time_update AFTER UPDATE ON run_split_time
+BEGIN UPDATE run_revision SET revision = revision + 1; END;
+CREATE TRIGGER run_revision_run_split_time_delete AFTER DELETE ON run_split_time
+BEGIN UPDATE run_revision SET revision = revision + 1; END;",
+    },
+    Migration {
+        description: "leave skipped splits out of split PBs",
+        sql: "
+-- Skipped splits usually have no times, but splits merged with the split after them keep theirs
+-- and are recorded as skipped; their totals span more than one segment, so can't be PBs.
+DROP VIEW split_pb;
+CREATE VIEW split_pb (game_category_id, split_id, total) AS
+    SELECT game_category_id, split_id, MIN(total) AS total
+      FROM run_split_total
+           INNER JOIN run_split USING (run_split_id)
+           INNER JOIN segment_split USING (split_id)
+           INNER JOIN category_segment AS cs USING (segment_id)
+           INNER JOIN game_category AS gc USING (category_id)
+           -- This bit is necessary to make the game-category pulled in above correspond to the run.
+           INNER JOIN run USING (run_id, game_category_id)
+     WHERE NOT is_skipped
+     GROUP BY game_category_id, split_id;",
+    },
+];
+
+/// Where a database stands against the known migrations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Status {
+    /// The database's schema version.
+    pub current: usize,
+    /// The latest schema version this version of zombiesplit knows about.
+    pub latest: usize,
+}
+
+impl Status {
+    /// Gets the migrations that the database has yet to have applied.
+    #[must_use]
+    pub fn pending(&self) -> &'static [Migration] {
+        MIGRATIONS.get(self.current..).unwrap_or_default()
+    }
+
+    /// Gets whether the database is at the latest schema version.
+    #[must_use]
+    pub fn is_up_to_date(&self) -> bool {
+        self.latest <= self.current
+    }
+}
+
+/// Gets the migration status of the database at `conn`.
+///
+/// # Errors
+///
+/// Fails if the database hasn't been initialised, if its schema is newer than
+/// this version of zombiesplit knows about, or if the underlying SQL queries
+/// fail.
+pub(super) fn status<C: Deref<Target = Connection>>(conn: C) -> Result<Status> {
+    let count: i64 = conn.query_row(SQL_COUNT_SCHEMA, [], |r| r.get(0))?;
+    if count == 0 {
+        return Err(Error::Uninitialised);
+    }
+    conn.execute_batch(SQL_CREATE_VERSION)?;
+
+    let current: Option<i64> = conn
+        .query_row(SQL_GET_VERSION, [], |r| r.get(0))
+        .optional()?;
+    let current = usize::try_from(current.unwrap_or_default()).unwrap_or_default();
+    let latest = MIGRATIONS.len();
+    if latest < current {
+        return Err(Error::SchemaTooNew { current, latest });
+    }
+    Ok(Status { current, latest })
+}
+
+/// Applies every pending migration to the database at `conn`, returning how
+/// many there were.
+///
+/// Each migration runs in its own transaction, alongside the update to the
+/// schema version, so a failing migration leaves the database at the version
+/// before it.
+///
+/// # Errors
+///
+/// Fails if [status] fails, or if any migration fails.
+pub(super) fn run<C: Deref<Target = Connection>>(conn: C) -> Result<usize> {
+    let status = status(&*conn)?;
+    for (version, migration) in (status.current + 1..).zip(status.pending()) {
+        log::info!(
+            "migrating database to version {version}: {}",
+            migration.description
+        );
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(SQL_SET_VERSION, [version])?;
+        tx.commit()?;
+    }
+    Ok(status.pending().len())
+}
+
+const SQL_COUNT_SCHEMA: &str = "SELECT COUNT(*) FROM sqlite_master;";
+
+const SQL_CREATE_VERSION: &str = "
+CREATE TABLE IF NOT EXISTS
+    schema_version
+        ( schema_version_id  INTEGER PRIMARY KEY CHECK(schema_version_id = 0)  -- there is only ever one row
+        , version            INTEGER NOT NULL                                   -- number of migrations applied
+        );";
+
+const SQL_GET_VERSION: &str = "SELECT version FROM schema_version;";
+
+const SQL_SET_VERSION: &str =
+    "INSERT OR REPLACE INTO schema_version (schema_version_id, version) VALUES (0, ?);";
@@ -0,0 +1,85 @@
+//! Database functionality for renaming games and categories.
+//!
+//! Runs refer to their game and category by ID, so renaming either carries
+//! every run along with it.
+
+use rusqlite::{named_params, OptionalExtension, Transaction};
+
+use super::error::{Error, Result};
+use crate::model::short;
+
+const SQL_GAME_ID: &str = "SELECT game_id FROM game WHERE short = :short;";
+const SQL_RENAME_GAME: &str = "UPDATE game SET short = :new WHERE game_id = :game_id;";
+const SQL_CATEGORY_ID: &str = "
+SELECT category_id
+  FROM category
+       INNER JOIN game_category USING (category_id)
+ WHERE game_id = :game_id AND category.short = :short;";
+const SQL_RENAME_CATEGORY: &str =
+    "UPDATE category SET short = :new WHERE category_id = :category_id;";
+
+/// Renames the game with short name `old` to `new`.
+///
+/// # Errors
+///
+/// Fails if there is no game `old`, if there is already a game `new`, or if
+/// the underlying SQL queries fail.
+pub(super) fn game(tx: &Transaction, old: short::Name, new: short::Name) -> Result<()> {
+    let game_id = find_game_id(tx, old)?.ok_or(Error::MissingGame { short: old })?;
+    if find_game_id(tx, new)?.is_some() {
+        return Err(Error::NameTaken { short: new });
+    }
+
+    log::info!("renaming game {old} (ID {game_id}) to {new}");
+    tx.execute(
+        SQL_RENAME_GAME,
+        named_params![":new": new, ":game_id": game_id],
+    )?;
+    Ok(())
+}
+
+/// Renames the category with short name `old`, in the game with short name
+/// `game`, to `new`.
+///
+/// # Errors
+///
+/// Fails if there is no such game or category, if the game already has a
+/// category `new`, or if the underlying SQL queries fail.
+pub(super) fn category(
+    tx: &Transaction,
+    game: short::Name,
+    old: short::Name,
+    new: short::Name,
+) -> Result<()> {
+    let game_id = find_game_id(tx, game)?.ok_or(Error::MissingGame { short: game })?;
+    let category_id =
+        find_category_id(tx, game_id, old)?.ok_or(Error::MissingCategory { game, short: old })?;
+    if find_category_id(tx, game_id, new)?.is_some() {
+        return Err(Error::NameTaken { short: new });
+    }
+
+    log::info!("renaming category {game}/{old} (ID {category_id}) to {new}");
+    tx.execute(
+        SQL_RENAME_CATEGORY,
+        named_params![":new": new, ":category_id": category_id],
+    )?;
+    Ok(())
+}
+
+fn find_game_id(tx: &Transaction, short: short::Name) -> Result<Option<i64>> {
+    Ok(tx
+        .query_row(SQL_GAME_ID, named_params![":short": short], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+fn find_category_id(tx: &Transaction, game_id: i64, short: short::Name) -> Result<Option<i64>> {
+    Ok(tx
+        .query_row(
+            SQL_CATEGORY_ID,
+            named_params![":game_id": game_id, ":short": short],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
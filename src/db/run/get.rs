@@ -17,8 +17,20 @@ pub struct Getter<'conn> {
     query_run_at_index: Statement<'conn>,
     /// Query used for summarising all runs logged on a game-category.
     query_all_runs: Statement<'conn>,
+    /// Query used for summarising all runs logged on a game-category, in date order.
+    query_runs_by_date: Statement<'conn>,
+    /// Query used for summarising the most recently dated run logged on a game-category.
+    query_latest_run: Statement<'conn>,
     /// Query used for finding all split totals for a run.
     query_splits_for_run: Statement<'conn>,
+    /// Query used for finding every split time, and skip flag, for a run.
+    query_split_times_for_run: Statement<'conn>,
+    /// Query used for finding how long a run was paused.
+    query_paused_for_run: Statement<'conn>,
+    /// Query used for finding the metadata attached to a run.
+    query_metadata_for_run: Statement<'conn>,
+    /// Query used for summarising how many runs a game-category has, and the newest one.
+    query_revision: Statement<'conn>,
 }
 
 impl<'conn> Getter<'conn> {
@@ -31,7 +43,13 @@ impl<'conn> Getter<'conn> {
         Ok(Self {
             query_run_at_index: conn.prepare(SQL_RUN_AT_INDEX)?,
             query_all_runs: conn.prepare(SQL_ALL_RUNS)?,
+            query_runs_by_date: conn.prepare(SQL_RUNS_BY_DATE)?,
+            query_latest_run: conn.prepare(SQL_LATEST_RUN)?,
             query_splits_for_run: conn.prepare(SQL_SPLITS_FOR_RUN)?,
+            query_split_times_for_run: conn.prepare(SQL_SPLIT_TIMES_FOR_RUN)?,
+            query_paused_for_run: conn.prepare(SQL_PAUSED_FOR_RUN)?,
+            query_metadata_for_run: conn.prepare(SQL_METADATA_FOR_RUN)?,
+            query_revision: conn.prepare(SQL_REVISION)?,
         })
     }
 
@@ -45,12 +63,14 @@ impl<'conn> Getter<'conn> {
         id: GcID,
         index: usize,
     ) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
-        self.query_run_at_index
+        let run = self
+            .query_run_at_index
             .query_and_then(named_params![":game_category": id, ":index": index], |r| {
                 WithID::from_row(id, r)
             })?
             .next()
-            .transpose()
+            .transpose()?;
+        run.map(|r| self.add_metadata(r)).transpose()
     }
 
     /// Gets summaries for each run on a given game-category ID.
@@ -59,11 +79,58 @@ impl<'conn> Getter<'conn> {
     ///
     /// Errors if the database query fails.
     pub fn runs_for(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
-        self.query_all_runs
+        let runs: Vec<_> = self
+            .query_all_runs
             .query_and_then(named_params![":game_category": id], |r| {
                 WithID::from_row(id, r)
             })?
-            .collect()
+            .collect::<Result<_>>()?;
+        runs.into_iter().map(|r| self.add_metadata(r)).collect()
+    }
+
+    /// Gets summaries for each run on a given game-category ID, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database query fails.
+    pub fn runs_by_date(&mut self, id: GcID) -> Result<Vec<WithID<history::run::Summary<GcID>>>> {
+        let runs: Vec<_> = self
+            .query_runs_by_date
+            .query_and_then(named_params![":game_category": id], |r| {
+                WithID::from_row(id, r)
+            })?
+            .collect::<Result<_>>()?;
+        runs.into_iter().map(|r| self.add_metadata(r)).collect()
+    }
+
+    /// Gets a summary of the most recently dated run on a given game-category ID, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database query fails.
+    pub fn latest_run(&mut self, id: GcID) -> Result<Option<WithID<history::run::Summary<GcID>>>> {
+        let run = self
+            .query_latest_run
+            .query_and_then(named_params![":game_category": id], |r| {
+                WithID::from_row(id, r)
+            })?
+            .next()
+            .transpose()?;
+        run.map(|r| self.add_metadata(r)).transpose()
+    }
+
+    /// Gets a revision token for the runs in the database.
+    ///
+    /// The token changes whenever any run is added, removed, or changed, even if a later change
+    /// puts things back as they were.  It covers every game-category, so it can also change when
+    /// a different game-category's runs do.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database query fails.
+    pub fn revision(&mut self) -> Result<u64> {
+        let revision: i64 = self.query_revision.query_row([], |r| r.get("revision"))?;
+        Ok(u64::try_from(revision).unwrap_or_default())
     }
 
     /// Gets split totals for the run with the given ID.
@@ -83,6 +150,68 @@ impl<'conn> Getter<'conn> {
         Ok(history::timing::Totals { totals })
     }
 
+    /// Gets full timing for the run with the given ID.
+    ///
+    /// This includes every time logged for each split, any adjustments to
+    /// those times, which splits were skipped, and how long the run was paused.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database query fails.
+    pub fn timing_for(&mut self, id: i64) -> Result<history::timing::Full> {
+        let mut timing = history::timing::Full {
+            times: short::Map::new(),
+            skipped: short::Set::new(),
+            paused: self
+                .query_paused_for_run
+                .query_row(named_params![":run": id], |r| r.get("paused_ms"))?,
+            adjustments: short::Map::new(),
+        };
+
+        let mut rows = self
+            .query_split_times_for_run
+            .query(named_params![":run": id])?;
+        while let Some(r) = rows.next()? {
+            let short: short::Name = r.get("short")?;
+            // Skipped splits may have no times at all.
+            if let Some(time) = r.get::<_, Option<time::Time>>("time_ms")? {
+                if r.get("is_adjustment")? {
+                    timing.adjustments.insert(short, time);
+                } else {
+                    timing.times.entry(short).or_default().push(time);
+                }
+            }
+            if r.get("is_skipped")? {
+                timing.skipped.insert(short);
+            }
+        }
+        Ok(timing)
+    }
+
+    /// Gets the metadata attached to the run with the given ID.
+    ///
+    /// Runs without metadata get an empty map.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the database query fails.
+    pub fn metadata_for(&mut self, id: i64) -> Result<history::run::Metadata> {
+        self.query_metadata_for_run
+            .query_and_then(named_params![":run": id], |r| {
+                Ok((r.get("key")?, r.get("value")?))
+            })?
+            .collect()
+    }
+
+    /// Fills in the metadata of a run summary straight out of a database row.
+    fn add_metadata(
+        &mut self,
+        mut run: WithID<history::run::Summary<GcID>>,
+    ) -> Result<WithID<history::run::Summary<GcID>>> {
+        run.item.metadata = self.metadata_for(run.id)?;
+        Ok(run)
+    }
+
     /// Adds split totals to an existing run.
     ///
     /// # Errors
@@ -123,6 +252,8 @@ impl WithID<history::run::Summary<GcID>> {
                     total: r.get("total")?,
                     rank: r.get("rank")?,
                 },
+                // Metadata lives in a separate table; the getter fills it in afterwards.
+                metadata: history::run::Metadata::new(),
             },
         })
     }
@@ -167,6 +298,60 @@ GROUP BY run_id
 ORDER BY rank ASC NULLS LAST, run.timestamp ASC
 ;";
 
+const SQL_RUNS_BY_DATE: &str = "
+SELECT run_id
+     , is_completed
+     , run.timestamp AS date
+     , SUM(time_ms)  AS total
+     , (CASE
+        WHEN is_completed = 1
+        THEN (RANK() OVER (PARTITION BY game_category_id, is_completed ORDER BY SUM(time_ms)))
+        ELSE NULL
+        END
+       ) AS rank
+  FROM run
+       INNER JOIN run_split      USING (run_id)
+       INNER JOIN run_split_time USING (run_split_id)
+ WHERE game_category_id = :game_category
+ GROUP BY run_id
+ ORDER BY run.timestamp ASC, run_id ASC;";
+
+const SQL_LATEST_RUN: &str = "
+SELECT run_id
+     , is_completed
+     , run.timestamp AS date
+     , SUM(time_ms)  AS total
+     -- Ranking would need every run in the game-category, and nothing needs it here.
+     , NULL          AS rank
+  FROM run
+       INNER JOIN run_split      USING (run_id)
+       INNER JOIN run_split_time USING (run_split_id)
+ WHERE game_category_id = :game_category
+ GROUP BY run_id
+ ORDER BY run.timestamp DESC, run_id DESC
+ LIMIT 1;";
+
+const SQL_REVISION: &str = "SELECT revision FROM run_revision WHERE run_revision_id = 0;";
+
+const SQL_SPLIT_TIMES_FOR_RUN: &str = "
+SELECT s.short AS short, is_skipped, time_ms, COALESCE(is_adjustment, 0) AS is_adjustment
+  FROM run_split
+       INNER JOIN split          AS s USING (split_id)
+       LEFT  JOIN run_split_time      USING (run_split_id)
+ WHERE run_id = :run
+ ORDER BY run_split_id ASC, position ASC;";
+
+const SQL_PAUSED_FOR_RUN: &str = "
+SELECT paused_ms
+  FROM run
+ WHERE run_id = :run;";
+
+const SQL_METADATA_FOR_RUN: &str = "
+SELECT key, value
+  FROM run_metadata
+ WHERE run_id = :run
+ ORDER BY key ASC;";
+
 const SQL_SPLITS_FOR_RUN: &str = "
 SELECT s.short AS short, total
   FROM run_split_total
@@ -17,6 +17,7 @@ pub struct Inserter<'conn> {
     query_add_run: Statement<'conn>,
     query_add_split: Statement<'conn>,
     query_add_split_time: Statement<'conn>,
+    query_add_metadata: Statement<'conn>,
     query_split_shortmap: Statement<'conn>,
 }
 
@@ -32,6 +33,7 @@ impl<'conn> Inserter<'conn> {
             query_add_run: conn.prepare(SQL_ADD_RUN)?,
             query_add_split: conn.prepare(SQL_ADD_SPLIT)?,
             query_add_split_time: conn.prepare(SQL_ADD_SPLIT_TIME)?,
+            query_add_metadata: conn.prepare(SQL_ADD_METADATA)?,
             query_split_shortmap: conn.prepare(SQL_SPLIT_SHORTMAP)?,
         })
     }
@@ -43,30 +45,44 @@ impl<'conn> Inserter<'conn> {
     ///
     /// # Errors
     ///
-    /// Propagates any errors from the database.
+    /// Fails with [Error::UnknownRunSplits] if the run has times for splits that aren't in its
+    /// category; in this case, nothing is inserted.  Otherwise, propagates any errors from the
+    /// database.
     pub fn add(&mut self, run: &history::run::FullyTimed<GcID>) -> Result<()> {
         info!(
             "adding run from {} to game-category ID {}",
             run.date, run.category_locator.0
         );
 
-        let run_id = self.add_main(run)?;
         let split_map = self.split_shortmap(run.category_locator)?;
+        check_splits(&run.timing, &split_map)?;
+
+        let run_id = self.add_main(run)?;
         self.add_splits(run_id, &run.timing, &split_map)?;
+        self.add_metadata(run_id, &run.metadata)?;
 
         Ok(())
     }
 
-    fn add_main<T>(&mut self, run: &history::Run<GcID, T>) -> Result<i64> {
+    fn add_main(&mut self, run: &history::run::FullyTimed<GcID>) -> Result<i64> {
         self.query_add_run.execute(named_params![
             ":is_completed": run.was_completed,
             ":timestamp": run.date.timestamp(),
-            ":game_category": run.category_locator
+            ":game_category": run.category_locator,
+            ":paused_ms": run.timing.paused.into_millis()
         ])?;
 
         Ok(self.conn.last_insert_rowid())
     }
 
+    fn add_metadata(&mut self, run_id: i64, metadata: &history::run::Metadata) -> Result<()> {
+        for (key, value) in metadata {
+            self.query_add_metadata
+                .execute(named_params![":run_id": run_id, ":key": key, ":value": value])?;
+        }
+        Ok(())
+    }
+
     fn split_shortmap(&mut self, id: GcID) -> Result<short::Map<i64>> {
         self.query_split_shortmap
             .query_and_then(named_params![":game_category": id], |row| {
@@ -81,44 +97,92 @@ impl<'conn> Inserter<'conn> {
         timing: &history::timing::Full,
         split_map: &short::Map<i64>,
     ) -> Result<()> {
-        for (short, times) in &timing.times {
-            // No point storing an empty split.
-            if times.is_empty() {
+        for short in split_shorts(timing) {
+            let times = timing.times.get(&short).map_or(&[][..], Vec::as_slice);
+            let adjustment = timing.adjustments.get(&short).copied();
+            let is_skipped = timing.is_skipped(short);
+            // No point storing an empty split, unless we need to remember that it was skipped.
+            if times.is_empty() && adjustment.is_none() && !is_skipped {
                 continue;
             }
 
             let split_id = split_map
-                .get(short)
-                .ok_or(Error::MissingRunSplit { short: *short })?;
-            self.query_add_split
-                .execute(named_params![":run_id": run_id, ":split_id": split_id])?;
+                .get(&short)
+                .ok_or(Error::MissingRunSplit { short })?;
+            self.query_add_split.execute(named_params![
+                ":run_id": run_id,
+                ":split_id": split_id,
+                ":is_skipped": is_skipped
+            ])?;
             let run_split_id = self.conn.last_insert_rowid();
-            self.add_split_times(run_split_id, times)?;
+            self.add_split_times(run_split_id, times, adjustment)?;
         }
         Ok(())
     }
 
-    fn add_split_times(&mut self, run_split_id: i64, times: &[time::Time]) -> Result<()> {
-        for (position, time) in times.iter().enumerate() {
-            self.query_add_split_time.execute(
-                named_params![":run_split_id": run_split_id, ":position": position, ":time_ms": time.into_millis()]
-            )?;
+    /// Adds `times` to the run split `run_split_id`, followed by `adjustment` if there is one.
+    fn add_split_times(
+        &mut self,
+        run_split_id: i64,
+        times: &[time::Time],
+        adjustment: Option<time::Time>,
+    ) -> Result<()> {
+        let times = times
+            .iter()
+            .map(|time| (*time, false))
+            .chain(adjustment.map(|time| (time, true)));
+        for (position, (time, is_adjustment)) in times.enumerate() {
+            self.query_add_split_time.execute(named_params![
+                ":run_split_id": run_split_id,
+                ":position": position,
+                ":time_ms": time.into_millis(),
+                ":is_adjustment": is_adjustment
+            ])?;
         }
         Ok(())
     }
 }
 
+/// Gets every split mentioned in `timing`, whether it has times, has an adjustment, or was skipped.
+fn split_shorts(timing: &history::timing::Full) -> short::Set {
+    timing
+        .times
+        .keys()
+        .chain(timing.adjustments.keys())
+        .chain(timing.skipped.iter())
+        .copied()
+        .collect()
+}
+
+/// Checks that every split in `timing` is in `split_map`.
+fn check_splits(timing: &history::timing::Full, split_map: &short::Map<i64>) -> Result<()> {
+    let mut shorts: Vec<short::Name> = split_shorts(timing)
+        .into_iter()
+        .filter(|short| !split_map.contains_key(short))
+        .collect();
+    if shorts.is_empty() {
+        Ok(())
+    } else {
+        shorts.sort();
+        Err(Error::UnknownRunSplits { shorts })
+    }
+}
+
 const SQL_ADD_RUN: &str = "
-INSERT INTO run (is_completed, timestamp, game_category_id)
-VALUES (:is_completed, :timestamp, :game_category);";
+INSERT INTO run (is_completed, timestamp, game_category_id, paused_ms)
+VALUES (:is_completed, :timestamp, :game_category, :paused_ms);";
 
 const SQL_ADD_SPLIT: &str = "
-INSERT INTO run_split (run_id, split_id)
-VALUES (:run_id, :split_id);";
+INSERT INTO run_split (run_id, split_id, is_skipped)
+VALUES (:run_id, :split_id, :is_skipped);";
 
 const SQL_ADD_SPLIT_TIME: &str = "
-INSERT INTO run_split_time (run_split_id, position, time_ms)
-VALUES (:run_split_id, :position, :time_ms);";
+INSERT INTO run_split_time (run_split_id, position, time_ms, is_adjustment)
+VALUES (:run_split_id, :position, :time_ms, :is_adjustment);";
+
+const SQL_ADD_METADATA: &str = "
+INSERT INTO run_metadata (run_id, key, value)
+VALUES (:run_id, :key, :value);";
 
 // TODO(@MattWindsor91): similar to, but not quite, the one in category.
 const SQL_SPLIT_SHORTMAP: &str = "
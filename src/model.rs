@@ -1,6 +1,8 @@
 //! Models used in zombiesplit.
+pub use error::{Error, Result};
 pub use load::Loadable;
 
+pub mod error;
 pub mod game;
 pub mod history;
 pub mod load;
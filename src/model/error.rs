@@ -0,0 +1,31 @@
+//! Errors raised by the zombiesplit model.
+
+use thiserror::Error;
+
+use super::{game::category::ShortDescriptor, session, short};
+
+/// Enumeration of errors that can occur while working with the model.
+///
+/// These errors can be sent to observers (and, through them, clients), so they carry enough
+/// information for a client to explain what went wrong.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Something referred to a split that doesn't exist.
+    #[error("unknown split {0}")]
+    UnknownSplit(short::Name),
+    /// Something expected a run of one category, but got a run of another.
+    #[error("expected a run of {expected}, but got one of {got}")]
+    CategoryMismatch {
+        /// The category we expected.
+        expected: ShortDescriptor,
+        /// The category we got.
+        got: ShortDescriptor,
+    },
+    /// An action on a session failed.
+    #[error(transparent)]
+    Session(#[from] session::Error),
+}
+
+/// Shorthand for results over model errors.
+pub type Result<T> = std::result::Result<T, Error>;
@@ -4,12 +4,18 @@ use std::{
     str::FromStr,
 };
 
-use super::super::short;
+use super::super::{load::schema, short};
+use rusqlite::{
+    types::{FromSql, FromSqlError},
+    ToSql,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
 /// A reference to the category of a game using a pair of short names.
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
 pub struct ShortDescriptor {
     /// The shortname of the game.
     pub game: short::Name,
@@ -55,11 +61,15 @@ impl FromStr for ShortDescriptor {
     ///
     /// "scd11".parse::<ShortDescriptor>().expect_err("can't parse without one slash");
     /// "scd11/btg/sonic".parse::<ShortDescriptor>().expect_err("can't parse with three slashes");
+    /// "scd11/BTG Sonic".parse::<ShortDescriptor>().expect_err("can't parse bad short names");
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let splits: Vec<&str> = s.split('/').collect();
         if splits.len() == 2 {
-            Ok(Self::new(splits[0], splits[1]))
+            Ok(Self {
+                game: splits[0].parse()?,
+                category: splits[1].parse()?,
+            })
         } else {
             Err(ShortDescriptorError::WrongSlashCount)
         }
@@ -72,10 +82,13 @@ pub enum ShortDescriptorError {
     /// Incorrect number of slashes in a short descriptor.
     #[error("short descriptors need exactly one slash")]
     WrongSlashCount,
+    /// One of the short names in the descriptor was invalid.
+    #[error("invalid short name in descriptor")]
+    BadShort(#[from] short::Error),
 }
 
 /// Full, displayable metadata about a target (game/category pair).
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Target {
     /// The name of the game.
     pub game: String,
@@ -123,3 +136,170 @@ impl Display for AttemptInfo {
         write!(f, "{} ({})", self.total, self.completed)
     }
 }
+
+/// Policy for what happens when a time is pushed to a split that already has times.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub enum SplitTimePolicy {
+    /// Append the new time to the split's existing times.
+    #[default]
+    Accumulate,
+    /// Replace the split's existing times with the new time.
+    Replace,
+    /// Reject the new time, leaving the split's existing times alone.
+    ///
+    /// This is useful for categories where each split gets exactly one timestamp.
+    RejectExtra,
+}
+
+impl Display for SplitTimePolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Accumulate => "accumulate",
+            Self::Replace => "replace",
+            Self::RejectExtra => "reject-extra",
+        })
+    }
+}
+
+impl FromStr for SplitTimePolicy {
+    type Err = SplitTimePolicyError;
+
+    /// Parses a split time policy from its kebab-case name.
+    ///
+    /// ```
+    /// use zombiesplit::model::game::category::SplitTimePolicy;
+    ///
+    /// assert_eq!(SplitTimePolicy::RejectExtra, "reject-extra".parse().unwrap());
+    /// "reject".parse::<SplitTimePolicy>().expect_err("not a policy");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "accumulate" => Ok(Self::Accumulate),
+            "replace" => Ok(Self::Replace),
+            "reject-extra" => Ok(Self::RejectExtra),
+            _ => Err(SplitTimePolicyError(s.to_owned())),
+        }
+    }
+}
+
+impl ToSql for SplitTimePolicy {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::Owned(
+            rusqlite::types::Value::Text(self.to_string()),
+        ))
+    }
+}
+
+impl FromSql for SplitTimePolicy {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl JsonSchema for SplitTimePolicy {
+    fn schema_name() -> String {
+        "SplitTimePolicy".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schema::string_enum([Self::Accumulate, Self::Replace, Self::RejectExtra])
+    }
+}
+
+/// Error returned when parsing an unknown split time policy.
+#[derive(Debug, Error)]
+#[error("unknown split time policy: {0}")]
+pub struct SplitTimePolicyError(String);
+
+/// The comparison a category prefers to run against, unless told otherwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub enum PreferredComparison {
+    /// Don't compare against anything.
+    None,
+    /// Compare against the PB run in the database.
+    Database,
+    /// Compare against the PB run in the database, balanced against the run history.
+    Balanced,
+    /// Compare against the most recently dated run in the database.
+    Latest,
+    /// Compare against the run history in the database, weighted towards recent runs.
+    Weighted,
+}
+
+impl Display for PreferredComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Database => "database",
+            Self::Balanced => "balanced",
+            Self::Latest => "latest",
+            Self::Weighted => "weighted",
+        })
+    }
+}
+
+impl FromStr for PreferredComparison {
+    type Err = PreferredComparisonError;
+
+    /// Parses a preferred comparison from its kebab-case name.
+    ///
+    /// ```
+    /// use zombiesplit::model::game::category::PreferredComparison;
+    ///
+    /// assert_eq!(PreferredComparison::Balanced, "balanced".parse().unwrap());
+    /// "average".parse::<PreferredComparison>().expect_err("not a comparison");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "database" => Ok(Self::Database),
+            "balanced" => Ok(Self::Balanced),
+            "latest" => Ok(Self::Latest),
+            "weighted" => Ok(Self::Weighted),
+            _ => Err(PreferredComparisonError(s.to_owned())),
+        }
+    }
+}
+
+impl ToSql for PreferredComparison {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::Owned(
+            rusqlite::types::Value::Text(self.to_string()),
+        ))
+    }
+}
+
+impl FromSql for PreferredComparison {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl JsonSchema for PreferredComparison {
+    fn schema_name() -> String {
+        "PreferredComparison".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schema::string_enum([
+            Self::None,
+            Self::Database,
+            Self::Balanced,
+            Self::Latest,
+            Self::Weighted,
+        ])
+    }
+}
+
+/// Error returned when parsing an unknown preferred comparison.
+#[derive(Debug, Error)]
+#[error("unknown comparison: {0}")]
+pub struct PreferredComparisonError(String);
@@ -5,21 +5,29 @@ These models represent the way in which zombiesplit is fed new games to track
 (not to be confused with the models that represent in-database game data).
 */
 
+pub mod layout;
+
 use std::{
     fmt::{self, Display},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
-use crate::model::timing::time;
+use crate::model::{
+    load::{self, Loadable},
+    timing::time,
+};
 
 use super::super::short;
+pub use layout::Layout;
 
 /// Configuration for a game.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct Config {
     /// The name of the game.
     pub name: String,
@@ -32,6 +40,36 @@ pub struct Config {
 }
 
 impl Config {
+    /// Loads a game from the TOML file at `path`, resolving any split layouts its categories
+    /// reference.
+    ///
+    /// Layout paths are resolved relative to the directory containing `path`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the game, or any of its layouts, can't be read or parsed; or if the layouts are
+    /// inconsistent with each other or with the game.
+    pub fn load(path: impl AsRef<Path>) -> load::Result<Self> {
+        let path = path.as_ref();
+        let mut game = Self::from_toml_file(path)?;
+        game.resolve_layouts(path)?;
+        Ok(game)
+    }
+
+    /// Replaces every category's layout reference with the contents of the referenced layout.
+    fn resolve_layouts(&mut self, path: &Path) -> load::Result<()> {
+        for category in self.categories.values_mut() {
+            if let Some(file) = category.splits.take() {
+                let file = layout::relative_to(path, &file);
+                let layout = Layout::load(&file)?;
+                category.segments = layout.order;
+                layout::merge(&mut self.segments, layout.segments, &file)?;
+                layout::merge(&mut self.splits, layout.splits, &file)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Wrapper for `categories.get`.
     ///
     /// # Errors
@@ -46,13 +84,27 @@ impl Config {
 }
 
 /// A run category.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct Category {
     /// The display name of the category.
     pub name: String,
     /// The list of segments that make up the category.
     #[serde(default)]
     pub segments: Vec<short::Name>,
+    /// Path to a split layout file from which to take the category's segments and splits.
+    ///
+    /// This is resolved (and cleared) when loading the game with [Config::load].
+    #[serde(default)]
+    pub splits: Option<PathBuf>,
+    /// What to do when pushing a time to a split that already has times.
+    #[serde(default)]
+    pub time_policy: super::category::SplitTimePolicy,
+    /// The comparison to run against by default, if not the one the server is configured with.
+    #[serde(default)]
+    pub comparison: Option<super::category::PreferredComparison>,
+    /// How to round this category's times when displaying them at less than full precision.
+    #[serde(default)]
+    pub rounding: time::Rounding,
 }
 
 impl Category {
@@ -77,7 +129,7 @@ impl Category {
 }
 
 /// A configured split segment.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
 pub struct Segment {
     /// The name of the segment.
     pub name: String,
@@ -108,17 +160,23 @@ impl Segment {
 }
 
 /// A configured split.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
 pub struct Split {
     /// The split name.
     pub name: String,
+    /// An optional short marker for the split (for instance, a boss indicator).
+    #[serde(default)]
+    pub marker: Option<String>,
+    /// An optional tolerance for close calls on this split, overriding the server's.
+    #[serde(default)]
+    pub tolerance: Option<time::Time>,
     /// The set of records configured for this split.
     #[serde(default)]
     pub records: short::Map<Record>,
 }
 
 /// A configured record.
-#[derive(SerializeDisplay, DeserializeFromStr, Debug)]
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, PartialEq, Eq)]
 pub struct Record {
     /// The time.
     pub time: time::human::Time,
@@ -130,6 +188,16 @@ impl Display for Record {
     }
 }
 
+impl JsonSchema for Record {
+    fn schema_name() -> String {
+        "Record".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        gen.subschema_for::<time::human::Time>()
+    }
+}
+
 impl FromStr for Record {
     type Err = time::Error; // for now
 
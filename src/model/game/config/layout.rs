@@ -0,0 +1,175 @@
+/*!
+External split layouts.
+
+A category can take its segments and splits from a separate layout file, rather than from the game
+file itself, by giving the path to that file in its `splits` option.  This is useful for games
+with several routes that don't share splits.
+
+Layout files look like this:
+
+```toml
+# Another layout to inherit segments and splits from (optional).
+extends = "base.toml"
+# The segments making up the category, in order.
+order = ["pp", "cc"]
+
+[segments.pp]
+    name = "Palmtree Panic"
+    splits = ["pp1", "pp2", "pp3"]
+
+[splits.pp1]
+    name = "Palmtree Panic 1"
+```
+
+Paths are resolved relative to the file mentioning them.
+*/
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    super::super::{
+        load::{self, Loadable},
+        short,
+    },
+    Segment, Split,
+};
+
+/// A split layout, as loaded from a layout file.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Layout {
+    /// Path to a layout from which this layout inherits segments and splits.
+    #[serde(default)]
+    pub extends: Option<PathBuf>,
+    /// The segments making up the category, in order.
+    ///
+    /// If this is empty, the order comes from the extended layout.
+    #[serde(default)]
+    pub order: Vec<short::Name>,
+    /// Map of split segments defined by the layout.
+    #[serde(default)]
+    pub segments: short::Map<Segment>,
+    /// Map of split configurations defined by the layout.
+    #[serde(default)]
+    pub splits: short::Map<Split>,
+}
+
+impl Layout {
+    /// Loads the layout at `path`, resolving any layouts it extends.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `path`, or any layout it extends, can't be read or parsed; if the layouts extend
+    /// each other in a cycle; or if they define the same segment or split differently.
+    pub fn load(path: impl AsRef<Path>) -> load::Result<Self> {
+        Self::load_visiting(path.as_ref(), &mut Vec::new())
+    }
+
+    fn load_visiting(path: &Path, visiting: &mut Vec<PathBuf>) -> load::Result<Self> {
+        let path = path
+            .canonicalize()
+            .map_err(|source| load::Error::MissingLayout {
+                path: path.to_owned(),
+                source,
+            })?;
+        if visiting.contains(&path) {
+            return Err(load::Error::CircularLayout { path });
+        }
+
+        let mut layout = Self::from_toml_file(&path)?;
+        if let Some(parent) = layout.extends.take() {
+            visiting.push(path.clone());
+            let parent = Self::load_visiting(&relative_to(&path, &parent), visiting)?;
+            visiting.pop();
+            layout.inherit(parent, &path)?;
+        }
+        Ok(layout)
+    }
+
+    /// Merges segments, splits, and (if we have none) the order from `parent` into this layout.
+    fn inherit(&mut self, parent: Self, path: &Path) -> load::Result<()> {
+        if self.order.is_empty() {
+            self.order = parent.order;
+        }
+        merge(&mut self.segments, parent.segments, path)?;
+        merge(&mut self.splits, parent.splits, path)
+    }
+}
+
+/// Resolves `path` relative to the directory containing `file`.
+pub(super) fn relative_to(file: &Path, path: &Path) -> PathBuf {
+    file.parent()
+        .map_or_else(|| path.to_owned(), |dir| dir.join(path))
+}
+
+/// Merges `from` into `into`, failing if they define the same item differently.
+///
+/// `path` names the layout responsible, for error reporting.
+pub(super) fn merge<T: PartialEq>(
+    into: &mut short::Map<T>,
+    from: short::Map<T>,
+    path: &Path,
+) -> load::Result<()> {
+    for (short, item) in from {
+        match into.get(&short) {
+            Some(existing) if *existing != item => {
+                return Err(load::Error::LayoutConflict {
+                    path: path.to_owned(),
+                    short,
+                })
+            }
+            Some(_) => {}
+            None => {
+                into.insert(short, item);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).expect("couldn't write layout");
+        path
+    }
+
+    #[test]
+    fn load_extended_layout() {
+        let dir = tempfile::tempdir().expect("couldn't make temp dir");
+        write(
+            dir.path(),
+            "base.toml",
+            r#"
+order = ["pp"]
+[segments.pp]
+    name = "Palmtree Panic"
+    splits = ["pp1"]
+[splits.pp1]
+    name = "Palmtree Panic 1"
+"#,
+        );
+        let path = write(dir.path(), "route.toml", r#"extends = "base.toml""#);
+
+        let layout = Layout::load(path).expect("layout should load");
+        assert_eq!(vec![short::Name::from("pp")], layout.order);
+        assert!(layout.splits.contains_key(&short::Name::from("pp1")));
+    }
+
+    #[test]
+    fn load_circular_layout() {
+        let dir = tempfile::tempdir().expect("couldn't make temp dir");
+        write(dir.path(), "a.toml", r#"extends = "b.toml""#);
+        let path = write(dir.path(), "b.toml", r#"extends = "a.toml""#);
+
+        let err = Layout::load(path).expect_err("circular layouts shouldn't load");
+        assert!(
+            matches!(err, load::Error::CircularLayout { .. }),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Models about splits in the context of a game configuration.
 
-use crate::model::short;
+use crate::model::{short, timing::time};
 
 /// Information about a segment.
 ///
@@ -14,13 +14,18 @@ pub struct Split {
     pub name: String,
     /// The nickname of this segment.
     pub nickname: Option<String>,
+    /// An optional short marker (for instance, a boss indicator) shown alongside the name.
+    pub marker: Option<String>,
+    /// How far this segment's time can stray from its comparison and still count as even, if
+    /// this overrides the session-wide tolerance.
+    pub tolerance: Option<time::Time>,
     // TODO(@MattWindsor91): segments
 }
 
 impl Split {
     /// Constructs a new segment.
     ///
-    /// The split will initially have an empty nickname.
+    /// The split will initially have an empty nickname, no marker, and no tolerance override.
     ///
     /// ```
     /// use zombiesplit::model::game::split;
@@ -34,6 +39,36 @@ impl Split {
             short: short.into(),
             name: name.to_string(),
             nickname: None,
+            marker: None,
+            tolerance: None,
         }
     }
+
+    /// Replaces this split's marker with `marker`.
+    ///
+    /// ```
+    /// use zombiesplit::model::game::split;
+    ///
+    /// let split = split::Split::new("mmz", "Metal Sonic").with_marker(Some("B"));
+    /// assert_eq!(Some("B"), split.marker.as_deref());
+    /// ```
+    #[must_use]
+    pub fn with_marker(mut self, marker: Option<impl ToString>) -> Self {
+        self.marker = marker.map(|x| x.to_string());
+        self
+    }
+
+    /// Replaces this split's tolerance override with `tolerance`.
+    ///
+    /// ```
+    /// use zombiesplit::model::{game::split, timing::time::Time};
+    ///
+    /// let split = split::Split::new("mmz", "Metal Sonic").with_tolerance(Some(Time::from_millis(500)));
+    /// assert_eq!(Some(Time::from_millis(500)), split.tolerance);
+    /// ```
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Option<time::Time>) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
 }
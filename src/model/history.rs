@@ -5,7 +5,11 @@ These models are useful for transferring run information into and out of
 flat files, as well as storing finished runs into the database.
 */
 
+pub mod diff;
 pub mod run;
+pub mod stats;
 pub mod timing;
 
+pub use diff::Diff;
 pub use run::Run;
+pub use stats::Stats;
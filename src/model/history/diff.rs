@@ -0,0 +1,119 @@
+//! Split-by-split comparisons between two historic runs.
+
+use super::{
+    super::{
+        short,
+        timing::{aggregate, time},
+    },
+    timing,
+};
+
+/// A split-by-split comparison between two runs, 'A' and 'B', of the same category.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// The comparison for each split timed in either run, in split order.
+    ///
+    /// Splits that aren't in the category's order (for instance, because the category has since
+    /// changed) come last, in name order.
+    pub splits: Vec<(short::Name, Split)>,
+}
+
+/// The comparison for one split between two runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Split {
+    /// The aggregate times for this split in run A, if it timed the split.
+    pub a: Option<aggregate::Set>,
+    /// The aggregate times for this split in run B, if it timed the split.
+    pub b: Option<aggregate::Set>,
+}
+
+impl Split {
+    /// Gets how much longer run A took on this split than run B, if both runs timed it.
+    ///
+    /// Negative deltas mean that run A gained time on run B.
+    #[must_use]
+    pub fn split_delta(&self) -> Option<time::Time> {
+        Some(self.a?.split - self.b?.split)
+    }
+
+    /// Gets how far run A was behind run B as of the end of this split, if both runs timed it.
+    ///
+    /// Negative deltas mean that run A was ahead of run B.
+    #[must_use]
+    pub fn cumulative_delta(&self) -> Option<time::Time> {
+        Some(self.a?.cumulative - self.b?.cumulative)
+    }
+}
+
+impl Diff {
+    /// Compares the split totals of run `a` against those of run `b`.
+    ///
+    /// `order` gives the order of splits in the category.  Cumulative times in each run only
+    /// count the splits that run timed.
+    ///
+    /// ```
+    /// use zombiesplit::model::{history::{diff::Diff, timing::Totals}, short, timing::time::Time};
+    ///
+    /// let s = |secs| Time::from_millis(secs * 1000);
+    /// let (x, y, z) = (short::Name::from("x"), short::Name::from("y"), short::Name::from("z"));
+    ///
+    /// let a = Totals { totals: [(x, s(10)), (y, s(20))].into_iter().collect() };
+    /// let b = Totals { totals: [(x, s(12)), (z, s(5))].into_iter().collect() };
+    ///
+    /// let diff = Diff::new(&a, &b, [x, y, z]);
+    /// assert_eq!(3, diff.splits.len());
+    /// assert_eq!(Some(s(-2)), diff.splits[0].1.split_delta());
+    /// // Only run A timed `y`, and only run B timed `z`.
+    /// assert!(diff.splits[1].1.cumulative_delta().is_none());
+    /// assert_eq!(Some(s(17)), diff.splits[2].1.b.map(|b| b.cumulative));
+    /// ```
+    #[must_use]
+    pub fn new(
+        a: &timing::Totals,
+        b: &timing::Totals,
+        order: impl IntoIterator<Item = short::Name>,
+    ) -> Self {
+        let mut order: Vec<short::Name> = order.into_iter().collect();
+        let mut extra: Vec<short::Name> = a
+            .totals
+            .keys()
+            .chain(b.totals.keys())
+            .filter(|s| !order.contains(s))
+            .copied()
+            .collect();
+        extra.sort_by_cached_key(ToString::to_string);
+        extra.dedup();
+        order.extend(extra);
+
+        let a = aggregates(a, &order);
+        let b = aggregates(b, &order);
+        let splits = order
+            .into_iter()
+            .filter_map(|short| {
+                let split = Split {
+                    a: a.get(&short).copied(),
+                    b: b.get(&short).copied(),
+                };
+                if split.a.is_none() && split.b.is_none() {
+                    None
+                } else {
+                    Some((short, split))
+                }
+            })
+            .collect();
+        Self { splits }
+    }
+}
+
+/// Works out the aggregate times of each split timed in `run`, following `order`.
+fn aggregates(run: &timing::Totals, order: &[short::Name]) -> short::Map<aggregate::Set> {
+    let mut cumulative = time::Time::default();
+    order
+        .iter()
+        .filter_map(|short| {
+            let split = *run.totals.get(short)?;
+            cumulative += split;
+            Some((*short, aggregate::Set { split, cumulative }))
+        })
+        .collect()
+}
@@ -1,11 +1,15 @@
 //!  Models related to a finished ('historic') run.
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Arbitrary key/value metadata attached to a run, such as the patch version or controller used.
+pub type Metadata = std::collections::BTreeMap<String, String>;
+
 /// A summary of a finished run.
 ///
 /// Runs are parametric over category locators and time calculations.
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
 pub struct Run<L, T> {
     /// The category_locator used to locate the game and category.
     #[serde(flatten)]
@@ -17,6 +21,37 @@ pub struct Run<L, T> {
     pub was_completed: bool,
     /// The date at which this run was archived.
     pub date: DateTime<Utc>,
+    /// Metadata attached to the run.
+    ///
+    /// Runs recorded before metadata was tracked have none.
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
+}
+
+impl<L, T> Run<L, T> {
+    /// Gets whether this run has the metadata `key` set to `value`.
+    ///
+    /// ```
+    /// use zombiesplit::model::history::run::Summary;
+    /// use zombiesplit::model::history::timing;
+    ///
+    /// let mut run = Summary {
+    ///     category_locator: (),
+    ///     timing: timing::Summary { total: Default::default(), rank: None },
+    ///     was_completed: true,
+    ///     date: chrono::Utc::now(),
+    ///     metadata: Default::default(),
+    /// };
+    /// run.metadata.insert("controller".to_owned(), "pad".to_owned());
+    ///
+    /// assert!(run.has_metadata("controller", "pad"));
+    /// assert!(!run.has_metadata("controller", "keyboard"));
+    /// assert!(!run.has_metadata("patch", "1.0"));
+    /// ```
+    #[must_use]
+    pub fn has_metadata(&self, key: &str, value: &str) -> bool {
+        self.metadata.get(key).map_or(false, |v| v == value)
+    }
 }
 
 impl<L, T: Clone> Run<L, T> {
@@ -27,6 +62,7 @@ impl<L, T: Clone> Run<L, T> {
             was_completed: self.was_completed,
             date: self.date,
             timing: self.timing.clone(),
+            metadata: self.metadata.clone(),
         }
     }
 
@@ -38,6 +74,7 @@ impl<L, T: Clone> Run<L, T> {
             was_completed: self.was_completed,
             date: self.date,
             timing: f(self.timing),
+            metadata: self.metadata,
         }
     }
 
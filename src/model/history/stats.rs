@@ -0,0 +1,131 @@
+//! Aggregate statistics over the run history of a category.
+
+use super::{
+    super::{short, timing::time},
+    run,
+};
+
+/// Statistics over every run recorded for a category.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of runs recorded, whether completed or not.
+    pub attempts: usize,
+    /// The number of completed runs recorded.
+    pub completed: usize,
+    /// The best total of any completed run.
+    pub pb: Option<time::Time>,
+    /// The sum of the best time for each split, across all runs.
+    pub sum_of_best: Option<time::Time>,
+    /// The mean total of the completed runs.
+    pub average: Option<time::Time>,
+    /// The median total of the completed runs.
+    pub median: Option<time::Time>,
+    /// Statistics for each split with at least one time, in split order.
+    pub splits: Vec<(short::Name, Split)>,
+}
+
+/// Statistics for one split, across every run that timed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Split {
+    /// The best time for this split.
+    pub best: time::Time,
+    /// The worst time for this split.
+    pub worst: time::Time,
+    /// The mean time for this split.
+    pub average: time::Time,
+}
+
+impl Stats {
+    /// Calculates statistics over `runs`.
+    ///
+    /// `history` contains every time recorded for each split across the runs, and `order` gives
+    /// the order of splits in the category.  Splits in `history` but not in `order` are ignored.
+    ///
+    /// ```
+    /// use zombiesplit::model::{history::stats::Stats, short, timing::time::Time};
+    ///
+    /// let s = |secs| Time::from_millis(secs * 1000);
+    /// let mut history = short::Map::new();
+    /// history.insert(short::Name::from("a"), vec![s(10), s(14)]);
+    /// history.insert(short::Name::from("b"), vec![s(20)]);
+    ///
+    /// let stats = Stats::new::<()>(&[], &history, ["a", "b"].map(short::Name::from));
+    /// assert_eq!(Some(s(30)), stats.sum_of_best);
+    /// assert_eq!(s(12), stats.splits[0].1.average);
+    /// // There are no completed runs.
+    /// assert!(stats.pb.is_none());
+    /// ```
+    #[must_use]
+    pub fn new<L>(
+        runs: &[run::Summary<L>],
+        history: &short::Map<Vec<time::Time>>,
+        order: impl IntoIterator<Item = short::Name>,
+    ) -> Self {
+        let mut totals: Vec<time::Time> = runs
+            .iter()
+            .filter(|r| r.was_completed)
+            .map(|r| r.timing.total)
+            .collect();
+        totals.sort_unstable();
+
+        let splits: Vec<(short::Name, Split)> = order
+            .into_iter()
+            .filter_map(|short| Some((short, Split::new(history.get(&short)?)?)))
+            .collect();
+
+        Self {
+            attempts: runs.len(),
+            completed: totals.len(),
+            pb: totals.first().copied(),
+            sum_of_best: (!splits.is_empty()).then(|| splits.iter().map(|(_, s)| s.best).sum()),
+            average: mean(&totals),
+            median: median(&totals),
+            splits,
+        }
+    }
+}
+
+impl Split {
+    /// Calculates statistics over the split times `times`, or `None` if there are none.
+    #[must_use]
+    pub fn new(times: &[time::Time]) -> Option<Self> {
+        Some(Self {
+            best: times.iter().min().copied()?,
+            worst: times.iter().max().copied()?,
+            average: mean(times)?,
+        })
+    }
+}
+
+/// Gets the mean of `times`, rounded towards zero, or `None` if there are no times.
+fn mean(times: &[time::Time]) -> Option<time::Time> {
+    let count = i64::try_from(times.len()).ok().filter(|n| *n != 0)?;
+    let sum: i64 = times.iter().map(|t| i64::from(t.into_millis())).sum();
+    i32::try_from(sum / count).ok().map(time::Time::from_millis)
+}
+
+/// Gets the median of the sorted `times`, or `None` if there are no times.
+fn median(times: &[time::Time]) -> Option<time::Time> {
+    let mid = times.len() / 2;
+    if times.len() % 2 == 0 {
+        mean(times.get(mid.checked_sub(1)?..=mid)?)
+    } else {
+        times.get(mid).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn s(secs: i32) -> time::Time {
+        time::Time::from_millis(secs * 1000)
+    }
+
+    #[test]
+    fn median_odd_and_even() {
+        assert_eq!(None, median(&[]));
+        assert_eq!(Some(s(2)), median(&[s(1), s(2), s(9)]));
+        assert_eq!(Some(s(3)), median(&[s(1), s(2), s(4), s(9)]));
+    }
+}
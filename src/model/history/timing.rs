@@ -7,6 +7,9 @@ different historic time models used.
 */
 use std::fmt::Formatter;
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
 use super::super::{short, timing::time};
 
 pub trait Timing {
@@ -16,15 +19,100 @@ pub trait Timing {
 
 /// Full timing information for a run.
 ///
-/// This includes every logged time for every split in the run.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// This includes every logged time for every split in the run, any
+/// adjustments made to those times, which splits were skipped, and how long
+/// the run spent paused.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Full {
+    /// The times logged for each split, in order of entry.
     pub times: short::Map<Vec<time::Time>>,
+    /// The splits that were skipped.
+    ///
+    /// Runs recorded before skipping was tracked have no skipped splits.
+    #[serde(default)]
+    pub skipped: short::Set,
+    /// The amount of real time for which the run was paused.
+    ///
+    /// This doesn't count towards any split, and is zero for runs recorded
+    /// before pausing was tracked.
+    #[serde(default)]
+    pub paused: time::Time,
+    /// The signed adjustments made to each split's logged times.
+    ///
+    /// Adjustments count towards split totals, but are kept apart from the
+    /// logged times.  Splits without adjustments don't appear here.
+    #[serde(default, skip_serializing_if = "short::Map::is_empty")]
+    pub adjustments: short::Map<time::Time>,
+}
+
+impl Full {
+    /// Gets whether the split `short` was skipped.
+    #[must_use]
+    pub fn is_skipped(&self, short: short::Name) -> bool {
+        self.skipped.contains(&short)
+    }
+
+    /// Gets the total time of the split `short`, including any adjustment.
+    ///
+    /// ```
+    /// use zombiesplit::model::{history::timing::Full, short, timing::time::Time};
+    ///
+    /// let a = short::Name::from("a");
+    /// let timing = Full {
+    ///     times: [(a, vec![Time::from_millis(1000), Time::from_millis(500)])].into_iter().collect(),
+    ///     skipped: short::Set::new(),
+    ///     paused: Time::default(),
+    ///     adjustments: [(a, Time::from_millis(-200))].into_iter().collect(),
+    /// };
+    /// assert_eq!(Time::from_millis(1300), timing.split_total(a));
+    /// ```
+    #[must_use]
+    pub fn split_total(&self, short: short::Name) -> time::Time {
+        let times: time::Time = self.times.get(&short).into_iter().flatten().copied().sum();
+        times + self.adjustments.get(&short).copied().unwrap_or_default()
+    }
+
+    /// Gets the splits whose total time is negative, in name order.
+    ///
+    /// The cumulative time of the run goes backwards at each of these splits, which almost always
+    /// means a time or adjustment in a run file was mistyped as negative, or a negative
+    /// adjustment was larger than intended.  Such runs would confuse pace calculations and
+    /// comparisons.
+    ///
+    /// ```
+    /// use zombiesplit::model::{history::timing::Full, short, timing::time::Time};
+    ///
+    /// let mut timing = Full {
+    ///     times: short::Map::new(),
+    ///     skipped: short::Set::new(),
+    ///     paused: Time::default(),
+    ///     adjustments: short::Map::new(),
+    /// };
+    /// timing.times.insert(short::Name::from("a"), vec![Time::from_millis(1000)]);
+    /// assert!(timing.backwards_splits().is_empty());
+    ///
+    /// timing.times.insert(short::Name::from("b"), vec![Time::from_millis(500), Time::from_millis(-800)]);
+    /// assert_eq!(vec![short::Name::from("b")], timing.backwards_splits());
+    /// ```
+    #[must_use]
+    pub fn backwards_splits(&self) -> Vec<short::Name> {
+        let mut shorts: Vec<short::Name> = self
+            .times
+            .keys()
+            .chain(self.adjustments.keys())
+            .filter(|short| self.split_total(**short) < time::Time::default())
+            .copied()
+            .collect();
+        shorts.sort_by_cached_key(ToString::to_string);
+        shorts.dedup();
+        shorts
+    }
 }
 
 impl Timing for Full {
     fn total(&self) -> time::Time {
-        self.times.values().flatten().copied().sum()
+        let times: time::Time = self.times.values().flatten().copied().sum();
+        times + self.adjustments.values().copied().sum()
     }
 }
 
@@ -142,3 +230,35 @@ impl Timing for ForLevel {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that a run file with an oversized negative adjustment goes backwards.
+    #[test]
+    fn backwards_from_toml() {
+        let timing: Full = toml::from_str(
+            r#"
+            [times]
+            a = ["10s"]
+            b = ["5s"]
+            c = ["-1s"]
+
+            [adjustments]
+            a = "-2s"
+            b = "-7s500"
+            "#,
+        )
+        .expect("should be valid");
+
+        assert_eq!(
+            Some(&time::Time::from_millis(-7_500)),
+            timing.adjustments.get(&short::Name::from("b"))
+        );
+        assert_eq!(
+            vec![short::Name::from("b"), short::Name::from("c")],
+            timing.backwards_splits()
+        );
+    }
+}
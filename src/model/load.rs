@@ -1,13 +1,24 @@
 /*!
 Top-level code relating to loading models from files.
 
-Models are invariably stored in TOML files in zombiesplit, so this mostly wraps
-around the serde/toml code.
+Models are almost always stored in TOML files in zombiesplit, so this mostly wraps
+around the serde/toml code.  The exception is [csv], which loads a game's splits from
+a spreadsheet export.
+
+[schema] describes the TOML formats as JSON schemas, for use by other tools.
 */
 
-use std::{io::Read, path::Path};
+pub mod csv;
+pub mod schema;
+
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
+use super::short;
+
 pub trait Loadable: Sized {
     /// Loads this model from a TOML file.
     ///
@@ -34,6 +45,25 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Error parsing game config from TOML")]
     Toml(#[from] toml::de::Error),
+    #[error("couldn't find split layout {path:?}")]
+    MissingLayout {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("split layout {path:?} extends itself")]
+    CircularLayout { path: PathBuf },
+    #[error("split layout {path:?} redefines {short} differently")]
+    LayoutConflict { path: PathBuf, short: short::Name },
+    #[error("expected a CSV header of short,name[,group], got {0:?}")]
+    CsvHeader(String),
+    #[error("CSV line {line} has the wrong number of fields")]
+    CsvRow { line: usize },
+    #[error("bad short name on CSV line {line}")]
+    CsvShort { line: usize, source: short::Error },
+    #[error("split {short} appears more than once in the CSV")]
+    CsvDuplicateSplit { short: short::Name },
+    #[error("the splits of group {group} aren't on consecutive CSV lines")]
+    CsvSplitGroup { group: short::Name },
 }
 /// Shorthand for a model load error.
 pub type Result<T> = std::result::Result<T, Error>;
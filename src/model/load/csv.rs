@@ -0,0 +1,238 @@
+/*! Loading a game's splits from CSV.
+
+This is a lightweight alternative to a TOML game file, for runners who keep their routes in a
+spreadsheet.  The CSV describes the splits of a single category, one per row, in order:
+
+```text
+short,name,group
+pp1,Palmtree Panic 1,pp
+pp2,Palmtree Panic 2,pp
+cc1,Collision Chaos 1,cc
+```
+
+The header row is required.  The `group` column is optional; if present, it gives the short name
+of the segment containing each split, and each segment's splits must be in consecutive rows.
+Without it, every split goes into a single segment named after the category.  Fields can be
+quoted with `"` if they contain commas.
+
+Everything else about the game (its name, and the category's short and display names) comes from
+a [Names].
+*/
+
+use std::path::Path;
+
+use super::{
+    super::{
+        game::{
+            category,
+            config::{Category, Segment, Split},
+            Config,
+        },
+        short,
+        timing::time,
+    },
+    Error, Result,
+};
+
+/// The names needed to build a game around a CSV category.
+#[derive(Clone, Debug)]
+pub struct Names {
+    /// The display name of the game.
+    pub game: String,
+    /// The short name of the category.
+    pub category: short::Name,
+    /// The display name of the category.
+    pub category_name: String,
+}
+
+/// Loads a game with one category, named by `names`, from the CSV file at `path`.
+///
+/// # Errors
+///
+/// Fails if the file can't be read, or if [parse] fails on its contents.
+pub fn load(path: impl AsRef<Path>, names: &Names) -> Result<Config> {
+    parse(&std::fs::read_to_string(path)?, names)
+}
+
+/// Parses a game with one category, named by `names`, from the CSV `contents`.
+///
+/// ```
+/// use zombiesplit::model::{load::csv, short};
+///
+/// let names = csv::Names {
+///     game: "Sonic CD".to_owned(),
+///     category: short::Name::from("any"),
+///     category_name: "Any%".to_owned(),
+/// };
+/// let game = csv::parse("short,name,group\npp1,Palmtree Panic 1,pp\ncc1,\"Collision, Chaos\",cc\n", &names)
+///     .unwrap();
+///
+/// let category = game.category("any").unwrap();
+/// assert_eq!(vec![short::Name::from("pp"), short::Name::from("cc")], category.segments);
+/// assert_eq!("Collision, Chaos", game.splits[&short::Name::from("cc1")].name);
+/// ```
+///
+/// # Errors
+///
+/// Fails if the header is missing or malformed; if any row has the wrong number of fields or a
+/// bad short name; if a split appears twice; or if a group's splits aren't consecutive.
+pub fn parse(contents: &str, names: &Names) -> Result<Config> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+
+    let header = lines.next().map_or_else(Vec::new, |(_, l)| fields(l));
+    let has_group = match header.as_slice() {
+        [s, n] if s == "short" && n == "name" => false,
+        [s, n, g] if s == "short" && n == "name" && g == "group" => true,
+        _ => return Err(Error::CsvHeader(header.join(","))),
+    };
+
+    let mut game = Config {
+        name: names.game.clone(),
+        segments: short::Map::new(),
+        splits: short::Map::new(),
+        categories: short::Map::new(),
+    };
+    let mut order: Vec<short::Name> = Vec::new();
+
+    for (index, line) in lines {
+        let row = fields(line);
+        if row.len() != header.len() {
+            return Err(Error::CsvRow { line: index + 1 });
+        }
+        let short = parse_short(&row[0], index)?;
+        let group = if has_group {
+            parse_short(&row[2], index)?
+        } else {
+            names.category
+        };
+
+        if game.splits.contains_key(&short) {
+            return Err(Error::CsvDuplicateSplit { short });
+        }
+        game.splits.insert(
+            short,
+            Split {
+                name: row[1].clone(),
+                marker: None,
+                tolerance: None,
+                records: short::Map::new(),
+            },
+        );
+
+        if order.last() != Some(&group) {
+            if order.contains(&group) {
+                return Err(Error::CsvSplitGroup { group });
+            }
+            order.push(group);
+        }
+        game.segments
+            .entry(group)
+            .or_insert_with(|| Segment {
+                name: if has_group {
+                    group.to_string()
+                } else {
+                    names.category_name.clone()
+                },
+                splits: Vec::new(),
+            })
+            .splits
+            .push(short);
+    }
+
+    game.categories.insert(
+        names.category,
+        Category {
+            name: names.category_name.clone(),
+            segments: order,
+            splits: None,
+            time_policy: category::SplitTimePolicy::default(),
+            comparison: None,
+            rounding: time::Rounding::default(),
+        },
+    );
+    Ok(game)
+}
+
+/// Parses the short name `field` on the zero-based line `index`.
+fn parse_short(field: &str, index: usize) -> Result<short::Name> {
+    field.parse().map_err(|source| Error::CsvShort {
+        line: index + 1,
+        source,
+    })
+}
+
+/// Splits a CSV line into its fields, unquoting any quoted fields.
+fn fields(line: &str) -> Vec<String> {
+    let mut result = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                result
+                    .last_mut()
+                    .expect("always at least one field")
+                    .push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => result.push(String::new()),
+            c => result
+                .last_mut()
+                .expect("always at least one field")
+                .push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names() -> Names {
+        Names {
+            game: "Game".to_owned(),
+            category: short::Name::from("cat"),
+            category_name: "Category".to_owned(),
+        }
+    }
+
+    #[test]
+    fn parse_without_groups() {
+        let game = parse("short,name\na,A\nb,B\n", &names()).unwrap();
+        let segment = &game.segments[&short::Name::from("cat")];
+        assert_eq!("Category", segment.name);
+        assert_eq!(
+            vec![short::Name::from("a"), short::Name::from("b")],
+            segment.splits
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_header() {
+        assert!(matches!(
+            parse("name,short\nA,a\n", &names()),
+            Err(Error::CsvHeader(_))
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_duplicate_split() {
+        assert!(matches!(
+            parse("short,name\na,A\na,Again\n", &names()),
+            Err(Error::CsvDuplicateSplit { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_split_group() {
+        assert!(matches!(
+            parse("short,name,group\na,A,x\nb,B,y\nc,C,x\n", &names()),
+            Err(Error::CsvSplitGroup { .. })
+        ));
+    }
+}
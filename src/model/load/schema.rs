@@ -0,0 +1,148 @@
+/*!
+JSON schemas for the TOML formats that zombiesplit loads.
+
+The schemas derive from the same types that zombiesplit deserialises game and run files into, so
+they can't drift from what zombiesplit actually accepts.  Editors and other tools can use them to
+validate and autocomplete those files outside zombiesplit.
+
+Most types derive their schemas; types that serialise through [Display](std::fmt::Display) and
+[FromStr] (such as short names and times) implement them by hand, using the helpers here.
+*/
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use schemars::{
+    schema::{InstanceType, Metadata, RootSchema, Schema, SchemaObject, StringValidation},
+    schema_for,
+};
+use thiserror::Error;
+
+use super::super::{game, history};
+
+/// The file formats for which zombiesplit can give a schema.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Game files, as loaded by [game::Config::load].
+    Game,
+    /// Run files, as loaded into [history::run::FullyTimed].
+    Run,
+}
+
+impl Kind {
+    /// Generates the schema for this kind of file.
+    #[must_use]
+    pub fn schema(self) -> RootSchema {
+        match self {
+            Self::Game => schema_for!(game::Config),
+            Self::Run => schema_for!(history::run::FullyTimed<game::category::ShortDescriptor>),
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Game => "game",
+            Self::Run => "run",
+        })
+    }
+}
+
+impl FromStr for Kind {
+    type Err = KindError;
+
+    /// Parses a schema kind from its name.
+    ///
+    /// ```
+    /// use zombiesplit::model::load::schema::Kind;
+    ///
+    /// assert_eq!(Kind::Run, "run".parse().unwrap());
+    /// "layout".parse::<Kind>().expect_err("no schema for layouts");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "game" => Ok(Self::Game),
+            "run" => Ok(Self::Run),
+            _ => Err(KindError(s.to_owned())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown schema kind.
+#[derive(Debug, Error)]
+#[error("unknown schema kind (expected 'game' or 'run'): {0}")]
+pub struct KindError(String);
+
+/// Makes a schema for strings described by `description`, optionally matching `pattern`.
+pub(crate) fn string(description: &str, pattern: Option<&str>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_owned()),
+            ..Metadata::default()
+        })),
+        string: pattern.map(|p| {
+            Box::new(StringValidation {
+                pattern: Some(p.to_owned()),
+                ..StringValidation::default()
+            })
+        }),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+/// Makes a schema for strings that must be the display form of one of `values`.
+///
+/// Taking the values themselves, rather than their names, keeps the schema in step with how the
+/// values actually serialise.
+pub(crate) fn string_enum<T: Display>(values: impl IntoIterator<Item = T>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(
+            values
+                .into_iter()
+                .map(|v| serde_json::Value::String(v.to_string()))
+                .collect(),
+        ),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Gets the names of the top-level properties of the schema for `kind`.
+    fn properties(kind: Kind) -> Vec<String> {
+        let schema = kind.schema();
+        let mut names: Vec<String> = schema
+            .schema
+            .object
+            .map(|o| o.properties.into_keys().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn game_schema_has_top_level_fields() {
+        assert_eq!(
+            vec!["categories", "name", "segments", "splits"],
+            properties(Kind::Game)
+        );
+    }
+
+    /// Run files flatten their category locator and timing into the top level.
+    #[test]
+    fn run_schema_flattens_locator_and_timing() {
+        let props = properties(Kind::Run);
+        for expected in ["category", "date", "game", "times", "was_completed"] {
+            assert!(props.iter().any(|p| p == expected), "missing {expected}");
+        }
+    }
+}
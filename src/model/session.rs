@@ -10,21 +10,35 @@ This module contains the bulk of the model surface of the zombiesplit server, co
 */
 pub mod action;
 pub mod attempt;
+pub mod checkpoint;
+pub mod clock;
+pub mod error;
 pub mod event;
+pub mod ghost;
 pub mod sink;
 pub mod split;
 pub mod state;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 use event::{split::Observer as SO, time::Observer as TO};
 
-use super::timing::{aggregate, comparison, comparison::provider, time, Comparison};
+use super::{
+    short,
+    timing::{aggregate, comparison, comparison::provider, time, Comparison},
+};
 
 pub use action::Action;
 pub use attempt::Attempt;
+pub use clock::Clock;
+pub use error::Error;
 pub use event::{observer::Observer, Event};
 pub use sink::Sink;
 pub use split::Split;
-pub use state::State;
+pub use state::{EntryMode, OnLastSplit, State};
+
+/// Metadata key flagging saved practice runs; its value names the practised splits.
+pub const PRACTICE_KEY: &str = "practice";
 
 /// A session over run attempts.
 ///
@@ -39,7 +53,17 @@ pub struct Session<'cmp, 'obs, O> {
     /// The observer attached to the session (which, itself, may be observable).
     observer: &'obs O,
     /// The function for timestamping outgoing runs.
+    ///
+    /// This is wall-clock time, and is only used for dating runs; see `clock` for timing them.
     timestamper: fn() -> chrono::DateTime<chrono::Utc>,
+    /// The clock for measuring how long the current run has been going.
+    clock: Box<dyn Clock>,
+    /// The time on `clock` at which the current run started, if it has.
+    started: Option<std::time::Duration>,
+    /// Whether the run was projected to be ahead when we last observed its notes.
+    ///
+    /// This lets us tell observers about pace crossings only when they happen.
+    projected_ahead: std::cell::Cell<Option<bool>>,
 
     //
     // Integrations with the historical model
@@ -48,26 +72,73 @@ pub struct Session<'cmp, 'obs, O> {
     // TODO(@MattWindsor91): refactor those into a separate struct?
     /// The sink attached to the session, for emitting saved runs.
     sink: Box<dyn Sink>,
+    /// The checkpoint attached to the session, for snapshotting the run in progress.
+    checkpoint: Box<dyn checkpoint::Checkpoint>,
+    /// The directory into which snapshots of the run are exported, if exporting is allowed.
+    export_dir: Option<std::path::PathBuf>,
+    /// Asked before clearing every split's times; the clear only goes ahead if this returns true.
+    confirm_clear: fn(&State) -> bool,
     /// The comparison provider.
     comparator: Box<dyn comparison::Provider + 'cmp>,
+    /// The providers of overlay comparisons, in the order they were added.
+    overlay_providers: Vec<Box<dyn comparison::Provider + 'cmp>>,
+    /// The provider of the comparison replayed as a ghost, if any.
+    ghost_provider: Option<Box<dyn comparison::Provider + 'cmp>>,
+    /// The past run being replayed alongside the current run, if any.
+    ghost: Option<ghost::Ghost>,
 }
 
 impl<'cmp, 'obs, O: Observer> action::Handler for Session<'cmp, 'obs, O> {
-    // This error may change later.
-    type Error = std::convert::Infallible;
+    type Error = super::Error;
 
     fn dump(&mut self) -> Result<State, Self::Error> {
         Ok(self.state.clone())
     }
 
-    fn handle(&mut self, action: Action) -> Result<(), Self::Error> {
-        match action {
-            Action::NewRun(dest) => self.reset(dest),
+    fn handle(&mut self, action: Action) -> Result<action::Outcome, Self::Error> {
+        // Finished runs can't be edited until reset.
+        let is_edit = action.is_edit();
+        if self.state.finished && is_edit {
+            return Ok(action::Outcome::Unchanged);
+        }
+        let changed = match action {
+            Action::NewRun(dest) => {
+                self.reset(dest);
+                true
+            }
             Action::Pop(s, action::Pop::One) => self.pop_from(s),
             Action::Pop(s, action::Pop::All) => self.clear_at(s),
-            Action::Push(s, t) => self.push_to(s, t),
+            Action::ClearAll => self.clear_all(),
+            Action::Push(s, t) => self.try_push_to(s, t)?,
+            Action::PushTotal(s, t) => self.try_push_total_to(s, t)?,
+            Action::Adjust(s, t) => self.adjust(s, t),
+            Action::SetCurrent(s) => self.set_current(s),
+            Action::Split(t) => self.split(t)?,
+            Action::SplitReset => self.split_reset(),
+            Action::PushNewSplit(s, t) => {
+                self.push_new_split(s, t)?;
+                true
+            }
+            Action::Finish => self.finish()?,
+            Action::Edit(s) => self.set_editing(s),
+            Action::MergeSplits(s, name) => self.merge_splits(s, name)?,
+            Action::SetMeta(key, value) => self.set_meta(key, value),
+            Action::Practice(practice) => self.set_practice(practice)?,
+            Action::ExportSnapshot(name) => {
+                self.export_snapshot(&name)?;
+                true
+            }
         };
-        Ok(())
+        if changed && is_edit {
+            if self.started.is_none() {
+                self.started = Some(self.clock.now());
+            }
+            // Finishing the run, as an edit can on the last split, saves it instead.
+            if !self.state.finished {
+                self.save_checkpoint();
+            }
+        }
+        Ok(action::Outcome::from_changed(changed))
     }
 }
 
@@ -79,13 +150,98 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
             state: State::new(run, Comparison::default()),
             observer,
             sink: Box::new(sink::Null),
+            checkpoint: Box::new(checkpoint::Null),
+            export_dir: None,
             timestamper: chrono::Utc::now,
+            clock: Box::new(clock::Monotonic::default()),
+            started: None,
+            projected_ahead: std::cell::Cell::default(),
+            confirm_clear: |_| true,
             comparator: Box::new(provider::Null),
+            overlay_providers: Vec::new(),
+            ghost_provider: None,
+            ghost: None,
         }
     }
 
     // TODO(@MattWindsor91): replace these 'set_' functions with a builder.
 
+    /// Replaces the clock the session uses to time runs.
+    ///
+    /// Useful for stubbing out elapsed time when testing; see [clock::Manual].
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+        self.started = None;
+        self.reset_ghost();
+    }
+
+    /// Gets how much real time has elapsed since the current run started, if it has.
+    ///
+    /// A run starts the first time an action edits it.  Elapsed time comes from the session's
+    /// [Clock], so it isn't affected by changes to the wall clock mid-run.
+    #[must_use]
+    pub fn elapsed(&self) -> Option<time::Time> {
+        self.started
+            .map(|start| clock::to_time(self.clock.now().saturating_sub(start)))
+    }
+
+    /// Replays the run behind the comparison from `p` as a ghost alongside the current run.
+    ///
+    /// The ghost moves as the current run's elapsed time passes each of the comparison's
+    /// cumulative times, and starts again whenever the run does; see [ghost] for details.  Ghosts
+    /// only move when the session [ticks](Self::tick).
+    ///
+    /// Triggers an immediate fetch of the ghost, which is fetched again alongside the comparison
+    /// whenever the run is reset.  If `p` has no comparison, there is no ghost.
+    pub fn set_ghost_provider(&mut self, p: Box<dyn provider::Provider + 'cmp>) {
+        self.ghost_provider = Some(p);
+        self.refresh_ghost();
+    }
+
+    /// Moves time-driven parts of the session, such as the ghost, on to the current elapsed time.
+    ///
+    /// Servers should call this regularly, and often enough for ghosts to look smooth; nothing
+    /// happens before the run starts.
+    pub fn tick(&mut self) {
+        let elapsed = self.elapsed();
+        let live_progress = self
+            .state
+            .attempt
+            .splits
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.num_times() != 0)
+            .last()
+            .map_or(0, |(position, _)| position + 1);
+        let (ghost, elapsed) = match (self.ghost.as_mut(), elapsed) {
+            (Some(ghost), Some(elapsed)) => (ghost, elapsed),
+            _ => return,
+        };
+
+        let was_finished = ghost.is_finished();
+        for (short, cumulative) in ghost.advance(elapsed) {
+            self.observer
+                .observe(Event::Ghost(event::Ghost::Passed(short, cumulative)));
+        }
+        if !was_finished && ghost.is_finished() {
+            if let Some(total) = ghost.total() {
+                self.observer
+                    .observe(Event::Ghost(event::Ghost::Finished(total)));
+            }
+        }
+        if let Some(ahead) = ghost.update_lead(live_progress) {
+            self.observer
+                .observe(Event::Ghost(event::Ghost::Lead { ahead }));
+        }
+    }
+
+    /// Moves any ghost back to the start of its run.
+    fn reset_ghost(&mut self) {
+        if let Some(ghost) = &mut self.ghost {
+            ghost.reset();
+        }
+    }
+
     /// Replaces the session's timestamper with a different function.
     ///
     /// Useful for stubbing out time when testing.
@@ -93,6 +249,55 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         self.timestamper = ts;
     }
 
+    /// Sets the function asked to confirm [Action::ClearAll] before the session clears the run.
+    ///
+    /// The function sees the state as it is before clearing.  By default, clears always go
+    /// ahead; a declined clear changes nothing.
+    pub fn set_clear_confirmation(&mut self, confirm: fn(&State) -> bool) {
+        self.confirm_clear = confirm;
+    }
+
+    /// Sets how the session interprets pushed times.
+    pub fn set_entry_mode(&mut self, mode: EntryMode) {
+        self.state.entry_mode = mode;
+    }
+
+    /// Sets what happens when the last split of a run gets a time.
+    ///
+    /// This doesn't apply while practising, or to pushes that add new splits (as in freeruns),
+    /// since such runs have no known last split.
+    pub fn set_on_last_split(&mut self, on_last_split: OnLastSplit) {
+        self.state.on_last_split = on_last_split;
+    }
+
+    /// Sets how far a split's time can stray from its comparison and still count as even.
+    ///
+    /// Splits with their own tolerance keep it.  Paces already reported don't change until their
+    /// splits next change.
+    pub fn set_tolerance(&mut self, tolerance: time::Time) {
+        self.state.tolerance = tolerance;
+    }
+
+    /// Sets the buckets into which splits are classified by pace.
+    ///
+    /// The buckets should already have been validated (see
+    /// [comparison::pace::bucket::Buckets::validate]); splits that no bucket matches just don't
+    /// get a bucket.  Buckets already reported don't change until their splits next change.
+    pub fn set_pace_buckets(&mut self, buckets: comparison::pace::bucket::Buckets) {
+        self.state.pace_buckets = buckets;
+    }
+
+    /// Sets the best segment time ever recorded for each split, and tells the observers about them.
+    ///
+    /// These usually come from the run history at the start of the session; they don't change
+    /// during runs.
+    pub fn set_best_segments(&mut self, best_segments: short::Map<time::Time>) {
+        self.state.best_segments = best_segments;
+        for split in self.state.attempt.splits.iter() {
+            self.observe_best_segment(split.info.short);
+        }
+    }
+
     /// Replaces the session's comparison provider with a different one.
     ///
     /// By default, the session doesn't have comparisons set up, so this will
@@ -104,6 +309,17 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         self.refresh_comparison();
     }
 
+    /// Adds a provider of an overlay comparison, shown alongside the main comparison.
+    ///
+    /// The overlay takes its name from the provider or, failing that, from its comparison; if
+    /// neither has one, it gets a numbered name.  Overlays with the same name replace each other.
+    ///
+    /// Triggers an immediate refresh of every overlay.
+    pub fn add_overlay_provider(&mut self, p: Box<dyn provider::Provider + 'cmp>) {
+        self.overlay_providers.push(p);
+        self.refresh_overlays();
+    }
+
     /// Replaces the session's run sink with a different one.
     ///
     /// By default, the session doesn't have comparisons set up, so this will
@@ -112,6 +328,88 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         self.sink = s;
     }
 
+    /// Replaces the session's checkpoint with a different one.
+    ///
+    /// By default, the session doesn't checkpoint runs in progress.
+    pub fn set_checkpoint(&mut self, c: Box<dyn checkpoint::Checkpoint>) {
+        self.checkpoint = c;
+    }
+
+    /// Sets the directory into which snapshots of the run are exported.
+    ///
+    /// By default, the session has no such directory, and can't export snapshots.
+    pub fn set_export_dir(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.export_dir = Some(dir.into());
+    }
+
+    /// Resumes the checkpointed run `run`, replacing the times of the current attempt.
+    ///
+    /// Observers receive a reset, followed by every resumed time.
+    ///
+    /// # Errors
+    ///
+    /// Fails, leaving the session alone, if `run` is a run of a different category to the
+    /// session's, or has times for a split that the session doesn't have.
+    pub fn resume(&mut self, run: &checkpoint::Run) -> super::Result<()> {
+        let expected = self.state.attempt.category.short;
+        if run.category_locator != expected {
+            return Err(super::Error::CategoryMismatch {
+                expected,
+                got: run.category_locator,
+            });
+        }
+        let splits = &self.state.attempt.splits;
+        if let Some(short) = run
+            .timing
+            .times
+            .keys()
+            .chain(run.timing.adjustments.keys())
+            .find(|short| splits.position(**short).is_none())
+        {
+            return Err(super::Error::UnknownSplit(*short));
+        }
+
+        self.state.reset(action::OldDestination::Discard);
+        self.state.restore(&run.timing);
+        self.state.attempt.metadata = run.metadata.clone();
+        // We can't know how long ago the checkpointed run started.
+        self.started = None;
+        self.projected_ahead.set(None);
+        self.reset_ghost();
+        self.observe_reset();
+        for split in self.state.attempt.splits.iter() {
+            for time in split.all_times() {
+                self.observer
+                    .observe_time(split.info.short, time, event::Time::Pushed);
+            }
+        }
+        for (key, value) in &self.state.attempt.metadata {
+            self.observer
+                .observe(Event::Metadata(key.clone(), Some(value.clone())));
+        }
+        self.observe_notes();
+        Ok(())
+    }
+
+    /// Prepares the session for the process to exit.
+    ///
+    /// If an unfinished run has times, this checkpoints it one last time, so that it can be
+    /// resumed later; finished runs are already in the history.  Any existing checkpoint is left
+    /// alone if there is nothing to save, so a crashed run that wasn't resumed survives too.
+    ///
+    /// The session stays usable afterwards, and shutting down more than once is harmless.
+    /// Sessions without a checkpoint (see [Self::set_checkpoint]) lose unfinished runs.
+    pub fn shutdown(&mut self) {
+        if self.state.finished {
+            return;
+        }
+        if let Some(run) = self.state.attempt.as_historic((self.timestamper)()) {
+            if let Err(e) = self.checkpoint.save(&run) {
+                log::error!("couldn't checkpoint run on shutdown: {e}");
+            }
+        }
+    }
+
     /// Asks the comparison provider for an updated comparison.
     ///
     /// This should occur when the run is reset, in case the outgoing run has
@@ -119,7 +417,10 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
     fn refresh_comparison(&mut self) {
         // TODO(@MattWindsor91): abort on error?
         match self.comparator.comparison() {
-            Ok(Some(c)) => {
+            Ok(Some(mut c)) => {
+                if let Some(name) = self.comparator.name() {
+                    c.name = Some(name);
+                }
                 self.state.comparison = c;
                 self.observe_comparison();
             }
@@ -128,6 +429,51 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
                 log::error!("couldn't get comparison: {e}");
             }
         }
+        self.refresh_ghost();
+    }
+
+    /// Asks the ghost provider, if any, for an updated ghost.
+    ///
+    /// The ghost is only replaced if the fetch succeeds, so a failed fetch keeps the old ghost.
+    fn refresh_ghost(&mut self) {
+        let result = match &mut self.ghost_provider {
+            Some(p) => p.comparison(),
+            None => return,
+        };
+        match result {
+            Ok(c) => {
+                let order = self.state.attempt.splits.iter().map(|s| s.info.short);
+                self.ghost = c.map(|c| ghost::Ghost::new(&c, order));
+            }
+            Err(e) => {
+                log::error!("couldn't get ghost comparison: {e}");
+            }
+        }
+    }
+
+    /// Asks each overlay provider for an updated comparison.
+    ///
+    /// Like [Self::refresh_comparison], this should occur when the run is reset.
+    fn refresh_overlays(&mut self) {
+        let mut overlays = std::collections::BTreeMap::new();
+        for (i, p) in self.overlay_providers.iter_mut().enumerate() {
+            match p.comparison() {
+                Ok(Some(mut c)) => {
+                    let name = p
+                        .name()
+                        .or_else(|| c.name.take())
+                        .unwrap_or_else(|| format!("Overlay {}", i + 1));
+                    c.name = Some(name.clone());
+                    overlays.insert(name, c);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("couldn't get overlay comparison: {e}");
+                }
+            }
+        }
+        self.state.overlays = overlays;
+        self.observe_overlays();
     }
 
     /// Observes a reset.
@@ -140,6 +486,37 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
     /// server's state changes.  Bugs have happened in the past where this is not true.
     fn observe_reset(&self) {
         self.observer.observe(Event::Reset(self.state.attempt.info));
+        self.observer.observe(Event::Live(self.state.live()));
+        self.observe_target();
+        self.observe_current();
+    }
+
+    /// Observes the current split, if there is one.
+    fn observe_current(&self) {
+        if let Some(short) = self.state.current_split() {
+            self.observe_current_split(short);
+        }
+    }
+
+    /// Observes `short` becoming the current split.
+    fn observe_current_split(&self, short: short::Name) {
+        self.observer.observe(Event::CurrentSplit(short));
+    }
+
+    /// Observes the comparison's segment time for the split being run, if it has one.
+    fn observe_target(&self) {
+        self.observer.observe(Event::Target(self.state.target()));
+    }
+
+    /// Observes the best and worst splits of the run, if there is a comparison to judge them by.
+    fn observe_highlights(&self) {
+        if let Some((best, worst)) = self.state.best_and_worst_splits() {
+            use event::split::{Highlight, Split};
+            self.observer
+                .observe_split(best, Split::Highlight(Highlight::Best));
+            self.observer
+                .observe_split(worst, Split::Highlight(Highlight::Worst));
+        }
     }
 
     /// Observes notes for each split, notifying all observers.
@@ -151,6 +528,14 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
             if let Some(d) = note.delta {
                 self.observer
                     .observe_split(*short, event::split::Split::Delta(d));
+                self.observer
+                    .observe_split(*short, event::split::Split::Trend(note.trend));
+                self.observer
+                    .observe_split(*short, event::split::Split::SegmentPace(note.segment_pace));
+            }
+            if let Some(bucket) = self.state.pace_bucket(*short) {
+                self.observer
+                    .observe_split(*short, event::split::Split::PaceBucket(bucket.name.clone()));
             }
             self.observer.observe_aggregate_set(
                 *short,
@@ -163,6 +548,28 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
         let time = self.state.total.map(|x| x.time);
         self.observer
             .observe(Event::Total(event::Total::Attempt(delta), time));
+        self.observer.observe(Event::Live(self.state.live()));
+        self.observe_target();
+
+        self.observe_pace_cross();
+        self.observe_overlay_deltas();
+    }
+
+    /// Observes the projected total crossing the comparison total, if it has just done so.
+    ///
+    /// Runs with no projection (for instance, fresh runs) don't count as being on either side,
+    /// so the first projection doesn't count as a crossing.
+    fn observe_pace_cross(&self) {
+        let now = self.state.is_projected_ahead();
+        if now.is_none() {
+            return;
+        }
+        let before = self.projected_ahead.replace(now);
+        if let (Some(before), Some(ahead)) = (before, now) {
+            if before != ahead {
+                self.observer.observe(Event::PaceCross { ahead });
+            }
+        }
     }
 
     /// Observes the contents of a comparison.
@@ -170,8 +577,11 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
     /// This lets the user interface know, for each splits, which times we are
     /// running against.
     fn observe_comparison(&self) {
+        self.observer
+            .observe(Event::ComparisonName(self.state.comparison.name.clone()));
         self.observe_comparison_run();
         self.observe_comparison_splits();
+        self.observe_target();
     }
 
     /// Observes comparison data for the run as a whole.
@@ -190,53 +600,486 @@ impl<'cmp, 'obs, O: Observer> Session<'cmp, 'obs, O> {
                 self.observer
                     .observe_aggregate_set(short, *s, aggregate::Source::Comparison);
             }
+            if let Some(t) = self.state.comparison.possible_timesave(short) {
+                self.observer
+                    .observe_split(short, event::split::Split::PossibleTimesave(t));
+            }
+            self.observe_best_segment(short);
+        }
+    }
+
+    /// Observes the contents of every overlay comparison, followed by the run's deltas against
+    /// them.
+    fn observe_overlays(&self) {
+        for (name, overlay) in &self.state.overlays {
+            self.observer.observe(Event::Overlay(
+                name.clone(),
+                event::Overlay::Total(overlay.run.total_in_pb_run),
+            ));
+            for split in self.state.attempt.splits.iter() {
+                let short = split.info.short;
+                if let Some(s) = overlay.aggregate_for(short) {
+                    self.observer.observe(Event::Overlay(
+                        name.clone(),
+                        event::Overlay::Aggregate(short, *s),
+                    ));
+                }
+            }
+        }
+        self.observe_overlay_deltas();
+    }
+
+    /// Observes the deltas of each split against every overlay comparison.
+    fn observe_overlay_deltas(&self) {
+        for (name, overlay) in &self.state.overlays {
+            for (short, delta) in self.state.overlay_deltas(overlay) {
+                self.observer.observe(Event::Overlay(
+                    name.clone(),
+                    event::Overlay::Delta(short, delta),
+                ));
+            }
+        }
+    }
+
+    /// Observes the best segment time for the split `short`, if it has one.
+    fn observe_best_segment(&self, short: short::Name) {
+        if let Some(t) = self.state.best_segments.get(&short) {
+            self.observer
+                .observe_split(short, event::split::Split::BestSegment(*t));
         }
     }
 
     fn reset(&mut self, dest: action::OldDestination) {
+        // Finished runs have already had their highlights observed.
+        if !self.state.finished {
+            self.observe_highlights();
+        }
         self.handle_last_attempt(dest);
+        // A finished run counts as a saved attempt, whatever we were asked to do with it.
+        let dest = if self.state.finished {
+            action::OldDestination::Save
+        } else {
+            dest
+        };
         self.state.reset(dest);
+        self.clear_checkpoint();
+        self.started = None;
+        self.projected_ahead.set(None);
+        self.reset_ghost();
         // Important that this happens AFTER the session is reset, so the new attempt info is sent.
         self.observe_reset();
         self.refresh_comparison();
+        self.refresh_overlays();
     }
 
     fn handle_last_attempt(&mut self, dest: action::OldDestination) {
+        // Finished runs have already been saved.
+        if self.state.finished {
+            return;
+        }
         match dest {
             action::OldDestination::Save => self.send_run_to_sink(),
             action::OldDestination::Discard => (),
         }
     }
 
+    /// Writes a snapshot of the run so far to the comparison file `name` in the export directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no export directory, `name` isn't a plain file name, or the file can't
+    /// be written; the observers also receive an error event.
+    fn export_snapshot(&self, name: &str) -> error::Result<()> {
+        let result = self.export_path(name).and_then(|path| {
+            checkpoint::write_toml(&path, &self.state.snapshot()).map_err(|e| Error::Export {
+                path,
+                reason: e.to_string(),
+            })
+        });
+        self.observe_error(result)
+    }
+
+    /// Resolves the export file `name` inside the export directory.
+    ///
+    /// Only plain file names resolve, so exports can't reach outside the directory.
+    fn export_path(&self, name: &str) -> error::Result<std::path::PathBuf> {
+        use std::path::Component;
+
+        let dir = self.export_dir.as_ref().ok_or(Error::NoExportDir)?;
+        let mut components = std::path::Path::new(name).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(file)), None) => Ok(dir.join(file)),
+            _ => Err(Error::ExportName(name.to_owned())),
+        }
+    }
+
+    /// Snapshots the run in progress to the checkpoint.
+    fn save_checkpoint(&mut self) {
+        let result = match self.state.attempt.as_historic((self.timestamper)()) {
+            Some(run) => self.checkpoint.save(&run),
+            None => self.checkpoint.clear(),
+        };
+        if let Err(e) = result {
+            log::warn!("couldn't checkpoint run: {e}");
+        }
+    }
+
+    /// Names the splits practised in `practice`, for flagging saved practice runs.
+    fn practice_label(&self, practice: action::Practice) -> String {
+        let short = |i| {
+            self.state
+                .attempt
+                .splits
+                .get(i)
+                .map_or_else(String::new, |s| s.info.short.to_string())
+        };
+        format!("{}-{}", short(practice.start), short(practice.end))
+    }
+
+    /// Removes the checkpoint, as the run in progress is no longer in progress.
+    fn clear_checkpoint(&mut self) {
+        if let Err(e) = self.checkpoint.clear() {
+            log::warn!("couldn't clear checkpoint: {e}");
+        }
+    }
+
     fn send_run_to_sink(&mut self) {
-        if let Some(r) = self.state.attempt.as_historic((self.timestamper)()) {
+        let practice = self.state.practice;
+        // Practice runs only make it into the history if asked for.
+        if practice.map_or(false, |p| !p.save) {
+            return;
+        }
+        if let Some(mut r) = self.state.attempt.as_historic((self.timestamper)()) {
+            if let Some(p) = practice {
+                r.metadata
+                    .insert(PRACTICE_KEY.to_owned(), self.practice_label(p));
+            }
             if let Err(e) = self.sink.accept(r) {
                 log::warn!("couldn't save run: {e}");
             }
         }
     }
 
-    fn clear_at(&mut self, split: impl split::Locator) {
+    /// Clears all times from a split located by `split`, returning whether there were any.
+    fn clear_at(&mut self, split: impl split::Locator) -> bool {
         if let Some(short) = self.state.clear_at(split) {
             self.observer
                 .observe_split(short, event::split::Split::Popped(action::Pop::All));
             self.observe_notes();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Clears every split's times, if confirmed, returning whether anything was cleared.
+    ///
+    /// Observers see each cleared split popped, then the run's current split moving back to the
+    /// start and its notes blanking out.
+    fn clear_all(&mut self) -> bool {
+        if self.state.finished || !(self.confirm_clear)(&self.state) {
+            return false;
+        }
+        let cleared = self.state.clear_all();
+        if cleared.is_empty() {
+            return false;
+        }
+        for short in cleared {
+            self.observer
+                .observe_split(short, event::split::Split::Popped(action::Pop::All));
+        }
+        self.set_current(self.state.practice.map_or(0, |p| p.start));
+        self.started = None;
+        self.projected_ahead.set(None);
+        self.reset_ghost();
+        self.clear_checkpoint();
+        self.observe_notes();
+        true
+    }
+
+    /// Adds `by` to the adjustment of a split located by `split`, returning whether it changed.
+    fn adjust(&mut self, split: impl split::Locator, by: time::Time) -> bool {
+        if let Some((short, adjustment)) = self.state.adjust(split, by) {
+            self.observer
+                .observe_split(short, event::split::Split::Adjustment(adjustment));
+            self.observe_notes();
+            true
+        } else {
+            false
         }
     }
 
     /// Pushes a time to a split located by `split`.
-    pub fn push_to(&mut self, split: impl split::Locator, time: time::Time) {
-        if let Some(short) = self.state.push_to(split, time) {
-            self.observer.observe_time(short, time, event::Time::Pushed);
+    ///
+    /// The effect of pushing to a split that already has times depends on the category's
+    /// [SplitTimePolicy](super::game::category::SplitTimePolicy).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the push is rejected; the observers also receive an error event.
+    pub fn push_to(
+        &mut self,
+        split: impl split::Locator,
+        time: time::Time,
+    ) -> error::Result<action::Outcome> {
+        self.try_push_to(split, time)
+            .map(action::Outcome::from_changed)
+    }
+
+    /// Pushes a time to a split located by `split`, returning whether the push happened.
+    fn try_push_to(&mut self, split: impl split::Locator, time: time::Time) -> error::Result<bool> {
+        let result = self.state.push_to(split, time);
+        let push = self.observe_error(result)?;
+        self.observe_push_to_end(push)
+    }
+
+    /// Pushes a cumulative time to a split located by `split`, returning whether the push
+    /// happened.
+    fn try_push_total_to(
+        &mut self,
+        split: impl split::Locator,
+        time: time::Time,
+    ) -> error::Result<bool> {
+        let result = self.state.push_total_to(split, time);
+        let push = self.observe_error(result)?;
+        self.observe_push_to_end(push)
+    }
+
+    /// Observes the result of pushing to a split, as [Self::observe_push], then reacts as
+    /// configured if the push was to the last split.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the run should have finished, but couldn't.
+    fn observe_push_to_end(&mut self, push: Option<state::Push>) -> error::Result<bool> {
+        let is_last = push.map_or(false, |p| self.is_last_split(p.short));
+        let pushed = self.observe_push(push);
+        if is_last && self.state.practice.is_none() {
+            match self.state.on_last_split {
+                OnLastSplit::Stay => (),
+                OnLastSplit::AutoFinish => {
+                    self.finish()?;
+                }
+                OnLastSplit::Prompt => self.observer.observe(Event::FinishPrompt),
+            }
+        }
+        Ok(pushed)
+    }
+
+    /// Gets whether `short` names the last split of the attempt.
+    fn is_last_split(&self, short: short::Name) -> bool {
+        self.state
+            .attempt
+            .splits
+            .iter()
+            .last()
+            .map(|s| s.info.short)
+            == Some(short)
+    }
+
+    /// Appends a new split named `short` and pushes `time` to it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split can't be added; the observers also receive an error event.
+    pub fn push_new_split(&mut self, short: short::Name, time: time::Time) -> error::Result<()> {
+        let result = self.state.push_new_split(short, time);
+        let (index, push) = self.observe_error(result)?;
+        if let Some(split) = self.state.attempt.splits.get(index) {
+            self.observer
+                .observe(Event::AddSplit(index, split.info.clone()));
+        }
+        self.observe_push(push);
+        Ok(())
+    }
+
+    /// Merges the split at position `index` with the split after it, naming the result according
+    /// to `name`.
+    ///
+    /// Returns whether there was a split at `index` to merge.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split at `index` is the last split; the observers also receive an error event.
+    pub fn merge_splits(&mut self, index: usize, name: action::MergeName) -> error::Result<bool> {
+        let current = self.state.current_split();
+        let editing = self.editing_split();
+        let result = self.state.merge_splits(index, name);
+        if self.observe_error(result)?.is_none() {
+            return Ok(false);
+        }
+        if let Some(split) = self.state.attempt.splits.get(index) {
+            self.observer
+                .observe(Event::MergeSplits(index, split.info.clone()));
+        }
+        self.observe_notes();
+        if let Some(short) = self.state.current_split().filter(|s| Some(*s) != current) {
+            self.observe_current_split(short);
+        }
+        let now_editing = self.editing_split();
+        if now_editing != editing {
+            self.observer.observe(Event::Editing(now_editing));
+        }
+        Ok(true)
+    }
+
+    /// Sets the run metadata `key` to `value`, removing it if `value` is empty.
+    ///
+    /// Returns whether the metadata changed.
+    pub fn set_meta(&mut self, key: String, value: String) -> bool {
+        if !self.state.set_meta(&key, &value) {
+            return false;
+        }
+        let value = if value.is_empty() { None } else { Some(value) };
+        self.observer.observe(Event::Metadata(key, value));
+        true
+    }
+
+    /// Gets the short name of the split open in an editor, if any.
+    fn editing_split(&self) -> Option<short::Name> {
+        self.state
+            .editing
+            .and_then(|i| self.state.attempt.splits.get(i))
+            .map(|s| s.info.short)
+    }
+
+    /// Tells the observers about the error in `result`, if there is one, then passes it through.
+    fn observe_error<T>(&self, result: error::Result<T>) -> error::Result<T> {
+        if let Err(e) = &result {
+            self.observer.observe(Event::Error(e.clone().into()));
+        }
+        result
+    }
+
+    /// Observes the result of a push to a split, returning whether the push happened.
+    fn observe_push(&self, push: Option<state::Push>) -> bool {
+        if let Some(push) = push {
+            if push.replaced {
+                self.observer
+                    .observe_split(push.short, event::split::Split::Popped(action::Pop::All));
+            }
+            self.observer
+                .observe_time(push.short, push.time, event::Time::Pushed);
+            if let Some(short) = push.new_current {
+                self.observe_current_split(short);
+            }
             self.observe_notes();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Pushes a time to the current split, then advances to the next split.
+    ///
+    /// Returns whether the push happened.
+    fn split(&mut self, time: time::Time) -> error::Result<bool> {
+        let current = self.state.current;
+        let pushed = self.try_push_to(current, time)?;
+        if pushed {
+            match self.state.practice {
+                Some(p) if p.end <= current => self.loop_practice(p),
+                _ => {
+                    self.set_current(current + 1);
+                }
+            }
+        }
+        Ok(pushed)
+    }
+
+    /// Starts practising the range of splits in `practice` or, if `None`, stops practising,
+    /// discarding the run in progress.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the range is invalid; the observers also receive an error event.
+    fn set_practice(&mut self, practice: Option<action::Practice>) -> error::Result<bool> {
+        let result = self.state.set_practice(practice);
+        self.observe_error(result)?;
+        self.observer.observe(Event::Practice(practice));
+        self.reset(action::OldDestination::Discard);
+        Ok(true)
+    }
+
+    /// Finishes one loop through the practice range `practice`, recording any new best segments
+    /// and returning to the start of the range.
+    fn loop_practice(&mut self, practice: action::Practice) {
+        for (short, time) in self.state.record_practice_bests() {
+            self.observer
+                .observe_split(short, event::split::Split::BestSegment(time));
+        }
+        self.reset(if practice.save {
+            action::OldDestination::Save
+        } else {
+            action::OldDestination::Discard
+        });
+    }
+
+    /// Undoes the most recent split on the current split, returning whether anything changed.
+    fn split_reset(&mut self) -> bool {
+        let current = self.state.current;
+        let is_empty = self
+            .state
+            .attempt
+            .splits
+            .get(current)
+            .map_or(true, |s| s.num_times() == 0);
+        let moved = is_empty && 0 < current && self.set_current(current - 1);
+        let popped = self.pop_from(self.state.current);
+        moved || popped
+    }
+
+    /// Finishes the run, saving it to the sink and locking it from edits until reset.
+    ///
+    /// Returns whether the run was newly finished.  If the run can't be finished, the observers
+    /// also receive an error event.
+    fn finish(&mut self) -> error::Result<bool> {
+        let result = self.state.finish();
+        let time = self.observe_error(result)?;
+        if let Some(time) = time {
+            self.send_run_to_sink();
+            self.clear_checkpoint();
+            self.observer.observe(Event::Finish(time));
+            self.observe_highlights();
+        }
+        Ok(time.is_some())
+    }
+
+    /// Makes the split at position `index` the current split.
+    ///
+    /// Observers are told about the change only if the current split actually changed; this
+    /// returns whether it did.
+    pub fn set_current(&mut self, index: usize) -> bool {
+        if let Some(short) = self.state.set_current(index) {
+            self.observe_current_split(short);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Marks the split at position `index` as open in an editor, or the editor as closed if `None`.
+    ///
+    /// Observers are told about the change only if the editing state actually changed; this
+    /// returns whether it did.
+    fn set_editing(&mut self, index: Option<usize>) -> bool {
+        if let Some(short) = self.state.set_editing(index) {
+            self.observer.observe(Event::Editing(short));
+            true
+        } else {
+            false
         }
     }
 
-    fn pop_from(&mut self, split: impl split::Locator) {
+    /// Pops the most recent time from a split located by `split`, returning whether there was one.
+    fn pop_from(&mut self, split: impl split::Locator) -> bool {
         if let Some(short) = self.state.pop_from(split) {
             self.observer
                 .observe_split(short, event::split::Split::Popped(action::Pop::One));
             self.observe_notes();
+            true
+        } else {
+            false
         }
     }
 }
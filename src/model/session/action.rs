@@ -1,19 +1,225 @@
 /*!
 An event interface for manipulating a current attempt.
+
+Most actions address splits by position.  External triggers (autosplitters, streamdeck buttons,
+and so on) can instead use [Action::Split] and [Action::SplitReset], which act on the session's
+current split; this way, the trigger doesn't need to keep track of split positions itself.
+Servers accept these from external tools over their trigger endpoint (see
+[crate::net::server::trigger]).
 */
 
-use super::super::timing::time;
+use super::super::{short, timing::time};
 
 /// An event that manipulates the current session.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Action {
     /// Start a new run.
     NewRun(OldDestination),
     /// Pushes a time to the split at the given position.
     Push(usize, time::Time),
+    /// Pushes a time to the split at the given position, reading it as the run's cumulative time
+    /// at the end of that split, whatever the session's entry mode.
+    ///
+    /// This is for one-off entries read off an in-game timer that shows the total so far.  The
+    /// time can't be before the run's cumulative time up to the split.
+    PushTotal(usize, time::Time),
+    /// Adds a signed adjustment, such as a penalty or a correction, to the split at the given
+    /// position.
+    ///
+    /// The adjustment counts towards the split's total and all aggregates, but is kept apart
+    /// from the split's pushed times, so the history shows both.  Adjustments accumulate, and
+    /// clearing the split removes them.
+    Adjust(usize, time::Time),
     /// Pops one or more times from the split at the given position.
     Pop(usize, Pop),
+    /// Clears the times and adjustments of every split, starting the attempt over.
+    ///
+    /// Unlike [Action::NewRun], this doesn't save the run or count it as an attempt, and the
+    /// run keeps its metadata.  Finished runs can't be cleared; they need a new run.  Sessions can
+    /// ask for confirmation first (see [super::Session::set_clear_confirmation]).
+    ClearAll,
+    /// Makes the split at the given position the current split.
+    SetCurrent(usize),
+    /// Pushes a time to the current split, then advances to the next split.
+    ///
+    /// If the push is rejected, the current split doesn't change.
+    Split(time::Time),
+    /// Undoes the most recent [Action::Split].
+    ///
+    /// If the current split has no times, this first moves back to the previous split; it then
+    /// pops the most recent time from the current split.
+    SplitReset,
+    /// Appends a new split with the given short name, then pushes a time to it.
+    ///
+    /// This is mainly useful for freerun attempts (see [super::Attempt::freerun]), where the
+    /// runner names splits as they go.
+    PushNewSplit(short::Name, time::Time),
+    /// Finishes the current run, saving it and locking it from further edits until a reset.
+    ///
+    /// This is only possible if the last split has a time.
+    Finish,
+    /// Tells the session that a client has opened an editor on the split at the given position
+    /// or, if the position is `None`, closed its editor.
+    ///
+    /// This doesn't change any times; it lets other clients show which split is being edited.
+    Edit(Option<usize>),
+    /// Merges the split at the given position with the split after it.
+    ///
+    /// The merged split keeps the first split's short name and takes the times of both splits,
+    /// in order; its display name comes from the [MergeName].  The last split can't be merged,
+    /// as there is nothing after it.
+    MergeSplits(usize, MergeName),
+    /// Sets the run metadata with the given key to the given value.
+    ///
+    /// An empty value removes the key.  Metadata is saved along with the run, and carries over
+    /// to the next run on a reset.
+    SetMeta(String, String),
+    /// Starts practising the given range of splits or, if `None`, goes back to full runs.
+    ///
+    /// Either way, the run in progress is discarded.  While practising, splitting past the end
+    /// of the range resets the run back to the start of the range.
+    Practice(Option<Practice>),
+    /// Writes a snapshot of the run so far to the comparison file with the given name, in the
+    /// session's export directory.
+    ///
+    /// The name must be a plain file name, not a path, so that exports can't escape the export
+    /// directory; there is nowhere to export to if the session has no such directory.
+    ///
+    /// The run isn't reset or saved to the history, and the file can be loaded as a comparison
+    /// later (see [super::State::snapshot]).  This works on finished runs too.
+    ExportSnapshot(String),
+}
+
+impl Action {
+    /// Gets whether this action edits the times of the current run.
+    ///
+    /// Editing actions are ignored once the run is finished.
+    #[must_use]
+    pub const fn is_edit(&self) -> bool {
+        matches!(
+            self,
+            Self::Push(..)
+                | Self::PushTotal(..)
+                | Self::Adjust(..)
+                | Self::Pop(..)
+                | Self::Split(_)
+                | Self::SplitReset
+                | Self::PushNewSplit(..)
+                | Self::MergeSplits(..)
+                | Self::SetMeta(..)
+        )
+    }
+}
+
+/// What happened to a session when it handled an action.
+///
+/// Clients can use this to give feedback on actions that did nothing, such as "nothing to undo".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Outcome {
+    /// The action changed the session.
+    Applied,
+    /// The action had nothing to act on, so the session didn't change.
+    ///
+    /// This happens, for instance, when popping from an empty split, or when moving to the split
+    /// that is already current.
+    Unchanged,
+}
+
+impl Outcome {
+    /// Gets the outcome of an action that did, or did not, change the session.
+    ///
+    /// ```
+    /// use zombiesplit::model::session::action::Outcome;
+    ///
+    /// assert_eq!(Outcome::Applied, Outcome::from_changed(true));
+    /// assert_eq!(Outcome::Unchanged, Outcome::from_changed(false));
+    /// ```
+    #[must_use]
+    pub const fn from_changed(changed: bool) -> Self {
+        if changed {
+            Self::Applied
+        } else {
+            Self::Unchanged
+        }
+    }
+
+    /// Gets whether the action changed the session.
+    #[must_use]
+    pub const fn is_applied(self) -> bool {
+        matches!(self, Self::Applied)
+    }
+}
+
+/// A range of splits to loop over in practice (see [Action::Practice]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Practice {
+    /// Position of the first split in the range.
+    pub start: usize,
+    /// Position of the last split in the range, inclusive.
+    pub end: usize,
+    /// Whether to save each loop through the range to the run history.
+    ///
+    /// Saved practice runs have `practice` metadata naming the range, so they can be told apart
+    /// from full runs.
+    pub save: bool,
+}
+
+impl Practice {
+    /// Gets whether the split at position `index` is in this range.
+    ///
+    /// ```
+    /// use zombiesplit::model::session::action::Practice;
+    ///
+    /// let practice = Practice { start: 1, end: 2, save: false };
+    /// assert!(!practice.contains(0));
+    /// assert!(practice.contains(1));
+    /// assert!(practice.contains(2));
+    /// assert!(!practice.contains(3));
+    /// ```
+    #[must_use]
+    pub const fn contains(&self, index: usize) -> bool {
+        self.start <= index && index <= self.end
+    }
+}
+
+/// How to name the split resulting from a [Action::MergeSplits].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MergeName {
+    /// Keep the name of the first split.
+    First,
+    /// Take the name of the second split.
+    Second,
+    /// Join both names together, separated by a slash.
+    Both,
+}
+
+impl Default for MergeName {
+    fn default() -> Self {
+        Self::Both
+    }
+}
+
+impl MergeName {
+    /// Combines the display names `first` and `second` according to this policy.
+    ///
+    /// ```
+    /// use zombiesplit::model::session::action::MergeName;
+    ///
+    /// assert_eq!("Act 1", MergeName::First.combine("Act 1", "Act 2"));
+    /// assert_eq!("Act 2", MergeName::Second.combine("Act 1", "Act 2"));
+    /// assert_eq!("Act 1 / Act 2", MergeName::Both.combine("Act 1", "Act 2"));
+    /// ```
+    #[must_use]
+    pub fn combine(self, first: &str, second: &str) -> String {
+        match self {
+            Self::First => first.to_owned(),
+            Self::Second => second.to_owned(),
+            Self::Both => format!("{first} / {second}"),
+        }
+    }
 }
 
 /// What should we do with an old attempt when we start a new one?
@@ -53,12 +259,11 @@ pub trait Handler {
     /// Fails if we can't, for whatever reason, get a dump from the handler.
     fn dump(&mut self) -> Result<super::State, Self::Error>;
 
-    // TODO(@MattWindsor91): errors?
-
-    /// Performs the action `a`.
+    /// Performs the action `a`, returning whether it changed anything.
     ///
     /// # Errors
     ///
-    /// Fails if we can't, for whatever reason, perform the action.
-    fn handle(&mut self, a: Action) -> Result<(), Self::Error>;
+    /// Fails if we can't, for whatever reason, perform the action; for instance, if the action
+    /// would break a rule of the run's category.
+    fn handle(&mut self, a: Action) -> Result<Outcome, Self::Error>;
 }
@@ -4,6 +4,7 @@ use crate::model::{
     game::{category, config},
     history,
     session::split,
+    timing::time,
 };
 use chrono::{DateTime, Utc};
 
@@ -22,6 +23,14 @@ pub struct Attempt {
     pub info: category::AttemptInfo,
     /// The split data for this run.
     pub splits: split::Set,
+    /// What to do when pushing a time to a split that already has times.
+    pub time_policy: category::SplitTimePolicy,
+    /// How to round this run's times when displaying them at less than full precision.
+    pub rounding: time::Rounding,
+    /// Arbitrary key/value metadata attached to this run.
+    ///
+    /// Metadata carries over between resets, and is saved along with the run.
+    pub metadata: history::run::Metadata,
 }
 
 impl Attempt {
@@ -52,9 +61,27 @@ impl Attempt {
             // TODO: indeterminate attempt information
             info: category::AttemptInfo::default(),
             splits,
+            time_policy: category.time_policy,
+            rounding: category.rounding,
+            metadata: history::run::Metadata::new(),
         })
     }
 
+    /// Constructs a freerun attempt against `category`, which starts with no splits at all.
+    ///
+    /// Splits can then be added as the run goes on, using [super::Action::PushNewSplit].
+    #[must_use]
+    pub fn freerun(category: category::Target) -> Self {
+        Self {
+            category,
+            info: category::AttemptInfo::default(),
+            splits: std::iter::empty::<split::Split>().collect(),
+            time_policy: category::SplitTimePolicy::default(),
+            rounding: time::Rounding::default(),
+            metadata: history::run::Metadata::new(),
+        }
+    }
+
     /// Resets this run and all splits inside it, incrementing the attempt if necessary.
     pub fn reset(&mut self, dest: super::action::OldDestination) {
         if matches!(dest, super::action::OldDestination::Save) {
@@ -93,6 +120,22 @@ impl Attempt {
                 .iter()
                 .map(|s| (s.info.short, s.all_times()))
                 .collect(),
+            // Merged splits keep their times, so the run total stays right, but count as skipped
+            // so that their times don't count as segment times.
+            // TODO(@MattWindsor91): record real skips once sessions can skip splits and pause.
+            skipped: self
+                .splits
+                .iter()
+                .filter(|s| s.merged)
+                .map(|s| s.info.short)
+                .collect(),
+            paused: time::Time::default(),
+            adjustments: self
+                .splits
+                .iter()
+                .filter(|s| s.adjustment != time::Time::default())
+                .map(|s| (s.info.short, s.adjustment))
+                .collect(),
         }
     }
 
@@ -120,6 +163,7 @@ impl Attempt {
             was_completed,
             date,
             timing: self.timing_as_historic(),
+            metadata: self.metadata.clone(),
         }
     }
 }
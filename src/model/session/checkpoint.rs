@@ -0,0 +1,170 @@
+/*! Checkpoints for in-progress runs.
+
+To survive crashes during long runs, a session can snapshot its in-progress run to a [Checkpoint]
+whenever the run's times change.  Unlike a [Sink](super::Sink), a checkpoint only ever holds the
+current run: it is overwritten on each change, and cleared when the run is reset or finished.
+
+If a checkpoint survives (because the session didn't shut down cleanly), the run it holds can be
+resumed with [Session::resume](super::Session::resume).
+*/
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::super::{game::category::ShortDescriptor, load::Loadable};
+
+/// Type of runs held in a [Checkpoint].
+pub type Run = super::sink::Run;
+
+/// Trait for things that can hold a checkpoint of the current run.
+pub trait Checkpoint {
+    /// Replaces the checkpoint with `run`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the underlying storage mechanism can't store this run.
+    fn save(&mut self, run: &Run) -> Result<()>;
+
+    /// Removes any checkpointed run.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the underlying storage mechanism can't remove the run.
+    fn clear(&mut self) -> Result<()>;
+}
+
+/// Null implementation of a checkpoint, which doesn't save anything.
+#[derive(Default)]
+pub struct Null;
+
+impl Checkpoint for Null {
+    fn save(&mut self, _run: &Run) -> Result<()> {
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A checkpoint stored as a TOML file, in the same format that `zsdb add-run` reads.
+pub struct File {
+    /// The path to the checkpoint file.
+    path: PathBuf,
+}
+
+impl File {
+    /// Constructs a file checkpoint for runs of `target`, inside the directory `dir`.
+    #[must_use]
+    pub fn new(dir: impl AsRef<Path>, target: &ShortDescriptor) -> Self {
+        Self {
+            path: dir
+                .as_ref()
+                .join(format!("{}.{}.toml", target.game, target.category)),
+        }
+    }
+
+    /// Gets the path to the checkpoint file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the checkpointed run, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the checkpoint file exists, but can't be read or parsed.
+    pub fn load(&self) -> Result<Option<Run>> {
+        if self.path.exists() {
+            Ok(Some(Run::from_toml_file(&self.path)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl Checkpoint for File {
+    fn save(&mut self, run: &Run) -> Result<()> {
+        write_toml(&self.path, run)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Writes `value` to the TOML file at `path`, replacing anything already there.
+///
+/// This writes to a temporary file first, then renames it over `path`, so that a crash mid-write
+/// doesn't corrupt the old file.
+///
+/// # Errors
+///
+/// Fails if `value` can't be serialised as TOML, or the file can't be written.
+pub fn write_toml(path: &Path, value: &impl serde::Serialize) -> Result<()> {
+    // Going through a TOML value puts the fields in an order TOML can serialise.
+    let contents = toml::to_string(&toml::Value::try_from(value)?)?;
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
+
+/// Enumeration of errors that can occur while checkpointing.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("i/o error accessing checkpoint")]
+    Io(#[from] std::io::Error),
+    #[error("couldn't serialise checkpoint")]
+    Serialise(#[from] toml::ser::Error),
+    #[error("couldn't load checkpoint")]
+    Load(#[from] super::super::load::Error),
+}
+
+/// Shorthand for results over checkpoint errors.
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{history, timing::time};
+
+    #[test]
+    fn file_round_trip() {
+        let dir = tempfile::tempdir().expect("couldn't make temp dir");
+        let target = ShortDescriptor::new("scd11", "btg-sonic");
+        let mut checkpoint = File::new(dir.path(), &target);
+
+        let timing = history::timing::Full {
+            times: [("pp1".into(), vec![time::Time::from_millis(25_000)])]
+                .into_iter()
+                .collect(),
+            skipped: ["pp2".into()].into_iter().collect(),
+            paused: time::Time::from_millis(5_000),
+            adjustments: [("pp1".into(), time::Time::from_millis(-1_000))]
+                .into_iter()
+                .collect(),
+        };
+        let run = Run {
+            category_locator: target,
+            timing,
+            was_completed: false,
+            date: chrono::Utc::now(),
+            metadata: history::run::Metadata::new(),
+        };
+
+        checkpoint.save(&run).expect("checkpoint should save");
+        let loaded = checkpoint.load().expect("checkpoint should load");
+        assert_eq!(Some(run.timing), loaded.map(|r| r.timing));
+
+        checkpoint.clear().expect("checkpoint should clear");
+        assert!(checkpoint.load().expect("checkpoint should load").is_none());
+        checkpoint.clear().expect("clearing twice should be fine");
+    }
+}
@@ -0,0 +1,78 @@
+/*! Clocks for measuring elapsed real time.
+
+Sessions measure how long a run has been going with a [Clock], rather than with the wall clock
+used to date runs.  The wall clock can jump (for instance, when NTP corrects it, or when daylight
+saving time starts), which would corrupt a timer that was running at the time; a [Monotonic]
+clock never goes backwards.
+
+[Manual] clocks only move when told to, which makes them useful for testing.
+*/
+
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use super::super::timing::time;
+
+/// Trait of clocks that measure elapsed time.
+pub trait Clock {
+    /// Gets the time elapsed since some fixed, but arbitrary, point.
+    ///
+    /// Successive calls should never go backwards.
+    fn now(&self) -> Duration;
+}
+
+/// A clock backed by the operating system's monotonic clock.
+#[derive(Clone, Copy, Debug)]
+pub struct Monotonic {
+    /// The point from which this clock measures.
+    epoch: std::time::Instant,
+}
+
+impl Default for Monotonic {
+    fn default() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+impl Clock for Monotonic {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
+
+/// A clock that only moves when advanced by hand.
+///
+/// Clones of a manual clock share the same time, so a test can keep one clone and hand another to
+/// a session.
+///
+/// ```
+/// use std::time::Duration;
+/// use zombiesplit::model::session::clock::{Clock, Manual};
+///
+/// let clock = Manual::default();
+/// let other = clock.clone();
+/// clock.advance(Duration::from_millis(1500));
+/// assert_eq!(Duration::from_millis(1500), other.now());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Manual(Rc<Cell<Duration>>);
+
+impl Manual {
+    /// Moves this clock, and all of its clones, forwards by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for Manual {
+    fn now(&self) -> Duration {
+        self.0.get()
+    }
+}
+
+/// Converts the duration `since` into a time, saturating at the longest representable time.
+#[must_use]
+pub fn to_time(since: Duration) -> time::Time {
+    time::Time::from_millis(i32::try_from(since.as_millis()).unwrap_or(i32::MAX))
+}
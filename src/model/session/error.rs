@@ -0,0 +1,49 @@
+//! Errors raised while handling actions on a session.
+
+use thiserror::Error;
+
+use super::super::{short, timing::time};
+
+/// Enumeration of errors that can occur while acting on a session.
+///
+/// These errors are usually reported to observers, rather than aborting the session.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Tried to push a time to a split that already has one, under a policy that rejects this.
+    #[error("split {0} already has a time")]
+    ExtraTime(short::Name),
+    /// Tried to add a split with the same short name as an existing split.
+    #[error("there is already a split named {0}")]
+    DuplicateSplit(short::Name),
+    /// Tried to push a cumulative time that is before the run's existing cumulative time.
+    #[error("cumulative time for split {split} is before the previous cumulative time {previous}")]
+    CumulativeBehind {
+        split: short::Name,
+        previous: time::Time,
+    },
+    /// Tried to merge the last split with the split after it.
+    #[error("can't merge split {0}, as it is the last split")]
+    NothingToMerge(short::Name),
+    /// Tried to practise a range of splits that is empty or runs past the last split.
+    #[error("can't practise splits {start} to {end}")]
+    BadPracticeRange { start: usize, end: usize },
+    /// Tried to finish a run whose last split has no time.
+    #[error("can't finish a run until its last split has a time")]
+    Unfinished,
+    /// Tried to export a snapshot of the run, but the session has no export directory.
+    #[error("can't export snapshots, as no export directory is set")]
+    NoExportDir,
+    /// Tried to export a snapshot of the run under a name that isn't a plain file name.
+    #[error("can't export snapshot to {0:?}, as it isn't a plain file name")]
+    ExportName(String),
+    /// Tried to export a snapshot of the run, but couldn't write it.
+    #[error("couldn't export snapshot to {path:?}: {reason}")]
+    Export {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+}
+
+/// Shorthand for results over session errors.
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,16 +1,22 @@
 //! Observer pattern wiring for attempt sessions.
 
 pub mod debug;
+pub mod file;
+pub mod ghost;
 pub mod mux;
 pub mod observer;
+pub mod overlay;
 pub mod split;
 pub mod time;
 
-use super::super::{game::category, short, timing};
+use super::super::{game, game::category, short, timing};
 
 pub use debug::Debug;
+pub use file::File;
+pub use ghost::Ghost;
 pub use mux::Mux;
 pub use observer::{Observable, Observer};
+pub use overlay::Overlay;
 pub use split::Split;
 pub use time::Time;
 
@@ -24,6 +30,58 @@ pub enum Event {
     Reset(category::AttemptInfo),
     /// Observes an event on a split.
     Split(short::Name, Split),
+    /// Observes a new split being added at the given position.
+    AddSplit(usize, game::Split),
+    /// Observes the split at the given position absorbing the split after it.
+    ///
+    /// The merged split's new information is attached.  Observers should move the absorbed
+    /// split's times onto the merged split, then remove the absorbed split, shifting every later
+    /// split back by one position.
+    MergeSplits(usize, game::Split),
+    /// Observes a change in the current split, with the short name of the new current split.
+    CurrentSplit(short::Name),
+    /// Observes the time to aim for on the split being run: the comparison's segment time for it.
+    ///
+    /// This is `None` if there is no split left to run, or the comparison has no segment time for
+    /// it, in which case clients shouldn't show a target.  It follows resets, changes of times,
+    /// and changes of comparison; see [super::State::target].
+    Target(Option<timing::time::Time>),
+    /// Observes the run's projected total crossing its comparison total.
+    ///
+    /// `ahead` is true if the run has just moved ahead of the comparison, and false if it has just
+    /// fallen behind.
+    PaceCross { ahead: bool },
+    /// Observes the name of the comparison being run against, if it has one.
+    ComparisonName(Option<String>),
+    /// Observes an event on the overlay comparison with the given name.
+    ///
+    /// See [super::Session::add_overlay_provider].
+    Overlay(String, Overlay),
+    /// Observes an event on the ghost replaying a past run alongside this one.
+    ///
+    /// See [super::Session::set_ghost_provider].
+    Ghost(Ghost),
+    /// Observes a client opening an editor on the named split or, if `None`, closing its editor.
+    Editing(Option<short::Name>),
+    /// Observes the run metadata with the given key being set to the given value or, if `None`,
+    /// removed.
+    Metadata(String, Option<String>),
+    /// Observes where the run now stands against its comparison, summarised for overlays.
+    ///
+    /// This repeats information from other events, and is sent whenever the run's times change.
+    Live(super::state::Live),
+    /// Observes the session starting to practise a range of splits or, if `None`, going back to
+    /// full runs.
+    Practice(Option<super::action::Practice>),
+    /// Observes the run being finished, with its final time attached.
+    Finish(timing::time::Time),
+    /// Observes the last split getting a time in a session set to prompt for finishing.
+    ///
+    /// Clients should ask the runner whether to finish the run and, if so, send
+    /// [super::Action::Finish].  See [super::OnLastSplit::Prompt].
+    FinishPrompt,
+    /// Observes an error that occurred while handling an action.
+    Error(super::super::Error),
 }
 
 /// Information about a type of total.
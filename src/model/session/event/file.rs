@@ -0,0 +1,40 @@
+//! An observer that logs events to a file.
+
+use std::{io::Write, path::Path, sync::Mutex};
+
+use super::{observer::Observer, Event};
+
+/// A file logging observer.
+///
+/// Every time an observation occurs, this observer appends it to a file, one event per line.
+pub struct File(Mutex<std::fs::File>);
+
+impl File {
+    /// Opens the file at `path` for logging, creating it if necessary.
+    ///
+    /// Events are appended to anything already in the file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be opened for appending.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self(Mutex::new(file)))
+    }
+}
+
+impl Observer for File {
+    fn observe(&self, evt: Event) {
+        match self.0.lock() {
+            Ok(mut file) => {
+                if let Err(e) = writeln!(file, "{evt:?}") {
+                    log::error!("couldn't log observation to file: {e}");
+                }
+            }
+            Err(e) => log::error!("couldn't lock observation log: {e}"),
+        }
+    }
+}
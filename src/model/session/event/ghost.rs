@@ -0,0 +1,19 @@
+//! Ghost events in attempt observations.
+use crate::model::{short, timing::time};
+
+/// Enumeration of events about the ghost being replayed alongside the run.
+///
+/// See [super::super::ghost] for what ghosts are.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Ghost {
+    /// The ghost passed the named split, at the given cumulative time.
+    Passed(short::Name, time::Time),
+    /// The ghost finished its run, with the given total time.
+    Finished(time::Time),
+    /// The lead between the live run and the ghost changed hands.
+    ///
+    /// `ahead` is true if the live run has just got further through the splits than the ghost,
+    /// and false if the ghost has just got further than the live run.
+    Lead { ahead: bool },
+}
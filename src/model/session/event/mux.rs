@@ -1,19 +1,106 @@
 //! Observation multiplexing.
-use crate::model::session::event::observer::{Observable, Observer};
-use std::sync::Weak;
+use crate::model::session::event::{
+    observer::{Observable, Observer},
+    Event,
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard, Weak},
+    thread,
+};
 
 /// An observation multiplexer.
 ///
 /// A [Mux] contains zero or more weak references to [Observer]s.  It then
 /// implements [Observer] itself, by distributing the incoming events to all of
 /// the still-live attached observers.
+///
+/// By default, observers see events synchronously, on the thread that is
+/// observing the mux.  Slow observers (for instance, ones that write to disk)
+/// can instead be attached with [Dispatch::Queued], so that they consume
+/// events from a bounded queue on their own thread, and don't hold up the
+/// session.  Each observer always sees events in the order the mux saw them.
 #[derive(Default)]
 pub struct Mux {
+    /// Observers that see events synchronously.
     observers: Vec<Weak<dyn Observer>>,
+    /// Queues for observers that see events on their own threads.
+    workers: Vec<Worker>,
+}
+
+/// How a [Mux] delivers events to an observer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dispatch {
+    /// Deliver events by calling the observer directly.
+    ///
+    /// This suits fast, in-process observers.
+    Synchronous,
+    /// Deliver events through a queue holding at most `capacity` events, consumed by the observer
+    /// on its own thread.
+    Queued {
+        /// The most events the queue can hold before `when_full` applies.
+        capacity: usize,
+        /// What to do when an event arrives and the queue is full.
+        when_full: WhenFull,
+    },
+}
+
+/// What a queued observer's queue does when an event arrives and it is full.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WhenFull {
+    /// Drop the oldest event in the queue to make room.
+    #[default]
+    DropOldest,
+    /// Block whoever is sending events to the mux until the observer catches up.
+    Block,
+}
+
+impl Mux {
+    /// Adds an observer to the mux, delivering events to it according to `dispatch`.
+    ///
+    /// Queued observers must be shareable across threads.
+    pub fn add_observer_with(
+        &mut self,
+        observer: Weak<dyn Observer + Send + Sync>,
+        dispatch: Dispatch,
+    ) {
+        match dispatch {
+            Dispatch::Synchronous => self.observers.push(observer),
+            Dispatch::Queued {
+                capacity,
+                when_full,
+            } => self
+                .workers
+                .push(Worker::spawn(observer, capacity, when_full)),
+        }
+    }
+
+    /// Stops delivering events to queued observers, once they have seen every event already
+    /// queued.
+    ///
+    /// This blocks until the queues are empty.  Dropping the mux also shuts it down.
+    pub fn shutdown(&mut self) {
+        for w in &self.workers {
+            w.queue.close();
+        }
+        for w in self.workers.drain(..) {
+            if w.thread.join().is_err() {
+                log::error!("an observer panicked while handling queued events");
+            }
+        }
+    }
+}
+
+impl Drop for Mux {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 impl Observable for Mux {
     /// Adds an observer to the mux.
+    ///
+    /// The observer sees events synchronously.
     fn add_observer(&mut self, observer: Weak<dyn Observer>) {
         self.observers.push(observer);
     }
@@ -28,5 +115,165 @@ impl Observer for Mux {
                 o.observe(evt.clone());
             }
         }
+        for w in &self.workers {
+            w.queue.push(evt.clone());
+        }
+    }
+}
+
+/// A queued observer's thread, and the queue it consumes.
+struct Worker {
+    queue: Arc<Queue>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl Worker {
+    /// Spawns a thread that feeds events from a new queue to `observer`.
+    fn spawn(
+        observer: Weak<dyn Observer + Send + Sync>,
+        capacity: usize,
+        when_full: WhenFull,
+    ) -> Self {
+        let queue = Arc::new(Queue::new(capacity, when_full));
+        let consumer = queue.clone();
+        let thread = thread::spawn(move || {
+            // We keep draining the queue even if the observer goes away, so that blocked senders
+            // don't wait forever.
+            while let Some(evt) = consumer.pop() {
+                if let Some(o) = observer.upgrade() {
+                    o.observe(evt);
+                }
+            }
+        });
+        Self { queue, thread }
+    }
+}
+
+/// A bounded, closable queue of events.
+struct Queue {
+    state: Mutex<QueueState>,
+    /// Signalled when an event arrives, or the queue closes.
+    pushed: Condvar,
+    /// Signalled when an event leaves the queue.
+    popped: Condvar,
+    capacity: usize,
+    when_full: WhenFull,
+}
+
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<Event>,
+    closed: bool,
+}
+
+impl Queue {
+    fn new(capacity: usize, when_full: WhenFull) -> Self {
+        Self {
+            state: Mutex::default(),
+            pushed: Condvar::new(),
+            popped: Condvar::new(),
+            // A queue that can hold nothing would block forever.
+            capacity: capacity.max(1),
+            when_full,
+        }
+    }
+
+    /// Pushes `evt` onto the back of the queue, applying the full-queue policy if needed.
+    fn push(&self, evt: Event) {
+        let mut state = self.lock();
+        while self.capacity <= state.events.len() {
+            match self.when_full {
+                WhenFull::DropOldest => {
+                    state.events.pop_front();
+                    log::warn!("observer queue full, dropping oldest event");
+                }
+                WhenFull::Block => {
+                    state = self.popped.wait(state).expect("couldn't lock queue");
+                }
+            }
+        }
+        state.events.push_back(evt);
+        self.pushed.notify_one();
+    }
+
+    /// Pops the event at the front of the queue, waiting for one if the queue is empty.
+    ///
+    /// Returns `None` once the queue is both closed and empty.
+    fn pop(&self) -> Option<Event> {
+        let mut state = self.lock();
+        loop {
+            if let Some(evt) = state.events.pop_front() {
+                self.popped.notify_one();
+                return Some(evt);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.pushed.wait(state).expect("couldn't lock queue");
+        }
+    }
+
+    /// Closes the queue, letting the consumer finish once it has drained it.
+    fn close(&self) {
+        self.lock().closed = true;
+        self.pushed.notify_one();
+    }
+
+    fn lock(&self) -> MutexGuard<QueueState> {
+        self.state.lock().expect("couldn't lock queue")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{session::test_util::Recorder, short};
+
+    fn weak(obs: &Arc<Recorder>) -> Weak<dyn Observer + Send + Sync> {
+        let weak: Weak<Recorder> = Arc::downgrade(obs);
+        weak
+    }
+
+    fn current(name: &'static str) -> Event {
+        Event::CurrentSplit(short::Name::from(name))
+    }
+
+    /// Queued observers see every event, in order, by the time the mux shuts down.
+    #[test]
+    fn queued_observer_drains_in_order() {
+        let sync = Arc::new(Recorder::default());
+        let queued = Arc::new(Recorder::default());
+
+        let mut mux = Mux::default();
+        mux.add_observer_with(weak(&sync), Dispatch::Synchronous);
+        mux.add_observer_with(
+            weak(&queued),
+            Dispatch::Queued {
+                capacity: 2,
+                when_full: WhenFull::Block,
+            },
+        );
+
+        let events: Vec<Event> = ["a", "b", "c", "d"].into_iter().map(current).collect();
+        for e in &events {
+            mux.observe(e.clone());
+        }
+        assert_eq!(events, sync.events());
+
+        mux.shutdown();
+        assert_eq!(events, queued.events());
+    }
+
+    #[test]
+    fn full_queue_drops_oldest() {
+        let queue = Queue::new(2, WhenFull::DropOldest);
+        queue.push(current("a"));
+        queue.push(current("b"));
+        queue.push(current("c"));
+        queue.close();
+
+        assert_eq!(Some(current("b")), queue.pop());
+        assert_eq!(Some(current("c")), queue.pop());
+        assert_eq!(None, queue.pop());
     }
 }
@@ -0,0 +1,21 @@
+//! Overlay comparison events in attempt observations.
+use crate::model::{
+    short,
+    timing::{aggregate, comparison::delta, time},
+};
+
+/// Enumeration of events about one overlay comparison.
+///
+/// Overlays are extra comparisons shown alongside the main one; these events carry just enough
+/// for a client to show each overlay in its own column.  Paces, colours, and highlights still
+/// come only from the main comparison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Overlay {
+    /// Got the overlay's aggregate times for a split.
+    Aggregate(short::Name, aggregate::Set),
+    /// Got a new delta of a split's attempt times against the overlay.
+    Delta(short::Name, delta::Split),
+    /// Got the overlay's total time for the run, if it has one.
+    Total(Option<time::Time>),
+}
@@ -1,7 +1,10 @@
 //! Split events in attempt observations.
 use crate::model::{
     short,
-    timing::{comparison::delta, time},
+    timing::{
+        comparison::{delta, pace},
+        time,
+    },
 };
 
 /// Enumeration of split-level events.
@@ -12,8 +15,40 @@ pub enum Split {
     Time(time::Time, super::Time),
     /// Got a new delta for the split.
     Delta(delta::Split),
+    /// Got a new pace trend for the split.
+    Trend(pace::Trend),
+    /// Got a new pace for the split's own segment time.
+    SegmentPace(pace::Pace),
+    /// The split fell into the named pace bucket.
+    ///
+    /// See [pace::bucket] for what buckets are.
+    PaceBucket(String),
+    /// Got a new possible timesave for the split.
+    ///
+    /// This is the time by which the split's comparison segment time exceeds its best segment
+    /// time.
+    PossibleTimesave(time::Time),
+    /// Got the best time ever recorded for the split's own segment.
+    ///
+    /// This comes from the run history, whatever the comparison, and doesn't change during a run.
+    BestSegment(time::Time),
+    /// The split's adjustment changed; this is the new adjustment.
+    ///
+    /// Adjustments count towards the split's total, but are separate from its pushed times.
+    Adjustment(time::Time),
     /// One or more times have been popped from the split.
     Popped(super::super::action::Pop),
+    /// The split was the best or worst of a finished run.
+    Highlight(Highlight),
+}
+
+/// Ways in which a split can stand out in a finished run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Highlight {
+    /// The split's own segment time did best against the comparison.
+    Best,
+    /// The split's own segment time did worst against the comparison.
+    Worst,
 }
 
 /// Trait for things that can observe split events.
@@ -0,0 +1,205 @@
+/*! Ghosts: past runs replayed in real time alongside the live run.
+
+A [Ghost] holds the cumulative time at which a past run finished each of its splits.  As the live
+run's elapsed time crosses each of those times, the ghost 'passes' that split, so an overlay can
+animate the past run as though it were being run again.  Ghosts only ever read the past run; they
+never affect times, paces, or comparisons.
+
+The ghost's progress depends only on elapsed time, so it keeps moving whether the live run is
+outpacing it or falling behind.  The ghost separately tracks which of the two runs has got
+further through the splits, so that observers can hear when the lead changes hands.
+*/
+
+use super::super::{
+    short,
+    timing::{time, Comparison},
+};
+
+/// A past run being replayed against the live run's elapsed time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ghost {
+    /// The run position, short name, and cumulative time of each split the past run timed, in run
+    /// order.
+    splits: Vec<(usize, short::Name, time::Time)>,
+    /// How many of `splits` the ghost has passed.
+    passed: usize,
+    /// Whether the live run was ahead of the ghost when the lead was last checked, if known.
+    live_ahead: Option<bool>,
+}
+
+impl Ghost {
+    /// Constructs a ghost of the run behind `comparison`, with `order` as the order of splits in
+    /// the live run.
+    ///
+    /// The ghost replays the comparison's cumulative times in its PB run; splits the comparison
+    /// doesn't have are never passed.
+    ///
+    /// ```
+    /// use zombiesplit::model::{
+    ///     session::ghost::Ghost,
+    ///     short,
+    ///     timing::{aggregate, comparison::{Comparison, Segment}, time::Time},
+    /// };
+    ///
+    /// let (a, b) = (short::Name::from("a"), short::Name::from("b"));
+    /// let s = |secs| Time::from_millis(secs * 1000);
+    /// let mut cmp = Comparison::default();
+    /// for (short, cumulative) in [(a, s(10)), (b, s(25))] {
+    ///     let in_pb_run = aggregate::Set { split: s(0), cumulative };
+    ///     cmp.splits.insert(short, Segment { split_pb: s(0), in_pb_run });
+    /// }
+    ///
+    /// let mut ghost = Ghost::new(&cmp, [a, b]);
+    /// assert!(ghost.advance(s(9)).is_empty());
+    /// assert_eq!(vec![(a, s(10))], ghost.advance(s(12)));
+    /// assert!(!ghost.is_finished());
+    /// assert_eq!(vec![(b, s(25))], ghost.advance(s(30)));
+    /// assert!(ghost.is_finished());
+    /// ```
+    #[must_use]
+    pub fn new(comparison: &Comparison, order: impl IntoIterator<Item = short::Name>) -> Self {
+        let mut splits: Vec<_> = order
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, short)| {
+                comparison
+                    .splits
+                    .get(&short)
+                    .map(|s| (position, short, s.in_pb_run.cumulative))
+            })
+            .collect();
+        // Cumulative times should never go backwards, but if they do, the ghost can't pass a
+        // split before the one it came after.
+        let mut latest = time::Time::default();
+        for (_, _, cumulative) in &mut splits {
+            latest = latest.max(*cumulative);
+            *cumulative = latest;
+        }
+        Self {
+            splits,
+            passed: 0,
+            live_ahead: None,
+        }
+    }
+
+    /// Moves the ghost back to the start of its run, as when the live run resets.
+    pub fn reset(&mut self) {
+        self.passed = 0;
+        self.live_ahead = None;
+    }
+
+    /// Moves the ghost on to `elapsed`, returning the splits (with their cumulative times) that it
+    /// passed on the way, in order.
+    ///
+    /// The ghost never moves backwards; elapsed times before ones already seen pass nothing.
+    pub fn advance(&mut self, elapsed: time::Time) -> Vec<(short::Name, time::Time)> {
+        let start = self.passed;
+        while let Some((_, _, cumulative)) = self.splits.get(self.passed) {
+            if elapsed < *cumulative {
+                break;
+            }
+            self.passed += 1;
+        }
+        self.splits[start..self.passed]
+            .iter()
+            .map(|(_, short, cumulative)| (*short, *cumulative))
+            .collect()
+    }
+
+    /// Gets whether the ghost has passed every split it has.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.passed == self.splits.len()
+    }
+
+    /// Gets the total time of the ghost's run, if it timed any splits.
+    #[must_use]
+    pub fn total(&self) -> Option<time::Time> {
+        self.splits.last().map(|(_, _, cumulative)| *cumulative)
+    }
+
+    /// Gets how many splits into the run the ghost has got, counting any splits it skipped.
+    #[must_use]
+    pub fn progress(&self) -> usize {
+        self.passed
+            .checked_sub(1)
+            .and_then(|last| self.splits.get(last))
+            .map_or(0, |(position, _, _)| position + 1)
+    }
+
+    /// Compares the live run's `progress` (how many splits into the run it has got) with the
+    /// ghost's, returning whether the live run is now ahead if the lead has changed hands.
+    ///
+    /// While the two runs are level, the lead stays with whichever run had it.
+    pub fn update_lead(&mut self, progress: usize) -> Option<bool> {
+        let ghost = self.progress();
+        let ahead = match progress.cmp(&ghost) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => return None,
+        };
+        (self.live_ahead != Some(ahead)).then(|| {
+            self.live_ahead = Some(ahead);
+            ahead
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::timing::{aggregate, comparison::Segment};
+
+    fn s(secs: i32) -> time::Time {
+        time::Time::from_millis(secs * 1000)
+    }
+
+    /// A ghost that timed `a` and `c`, but skipped `b`.
+    fn ghost() -> Ghost {
+        let mut cmp = Comparison::default();
+        for (short, cumulative) in [("a", s(10)), ("c", s(30))] {
+            let in_pb_run = aggregate::Set {
+                split: s(0),
+                cumulative,
+            };
+            cmp.splits.insert(
+                short::Name::from(short),
+                Segment {
+                    split_pb: s(0),
+                    in_pb_run,
+                },
+            );
+        }
+        Ghost::new(&cmp, ["a", "b", "c"].map(short::Name::from))
+    }
+
+    /// Progress counts splits the ghost skipped, once it has passed a later split.
+    #[test]
+    fn progress_counts_skips() {
+        let mut ghost = ghost();
+        assert_eq!(0, ghost.progress());
+        ghost.advance(s(10));
+        assert_eq!(1, ghost.progress());
+        ghost.advance(s(31));
+        assert_eq!(3, ghost.progress());
+        assert_eq!(Some(s(30)), ghost.total());
+    }
+
+    /// The lead changes hands only when one run gets strictly further than the other.
+    #[test]
+    fn lead_changes() {
+        let mut ghost = ghost();
+        assert_eq!(None, ghost.update_lead(0), "level at the start");
+        assert_eq!(Some(true), ghost.update_lead(1), "live run splits first");
+        assert_eq!(None, ghost.update_lead(2), "still ahead");
+
+        ghost.advance(s(10));
+        assert_eq!(None, ghost.update_lead(1), "level keeps the lead");
+        ghost.advance(s(30));
+        assert_eq!(Some(false), ghost.update_lead(2), "ghost overtakes");
+
+        ghost.reset();
+        assert_eq!(0, ghost.progress());
+        assert_eq!(Some(true), ghost.update_lead(1), "reset forgets the lead");
+    }
+}
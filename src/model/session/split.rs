@@ -16,6 +16,16 @@ pub struct Split {
     pub info: game::Split,
     /// The entered times.
     pub times: Vec<time::Time>,
+    /// A signed adjustment to the entered times, such as a penalty or a correction.
+    ///
+    /// This counts towards the split's total, but is kept apart from the entered times so that
+    /// it stays visible in the run history.
+    pub adjustment: time::Time,
+    /// Whether this split has absorbed the split after it (see [super::Action::MergeSplits]).
+    ///
+    /// A merged split's times cover more than one segment, so they don't count as segment times
+    /// for best-segment tracking.
+    pub merged: bool,
 }
 
 impl Split {
@@ -25,6 +35,8 @@ impl Split {
         Self {
             info,
             times: Vec::new(),
+            adjustment: time::Time::default(),
+            merged: false,
         }
     }
 
@@ -34,7 +46,7 @@ impl Split {
         &self.info.name
     }
 
-    /// Gets the total time for this split.
+    /// Gets the total time for this split, including any adjustment.
     ///
     /// # Example
     ///
@@ -45,10 +57,19 @@ impl Split {
     /// s.push(time::Time::from_millis(9));
     /// s.push(time::Time::from_millis(10));
     /// assert_eq!(time::Time::from_millis(19), s.total_time());
+    ///
+    /// s.adjust(time::Time::from_millis(-4));
+    /// assert_eq!(time::Time::from_millis(15), s.total_time());
     /// ```
     #[must_use]
     pub fn total_time(&self) -> time::Time {
-        self.times.iter().copied().sum()
+        self.times.iter().copied().sum::<time::Time>() + self.adjustment
+    }
+
+    /// Adds `by` to this split's adjustment, returning whether the adjustment changed.
+    pub fn adjust(&mut self, by: time::Time) -> bool {
+        self.adjustment += by;
+        by != time::Time::default()
     }
 
     /// Clones a copy of the times for this split.
@@ -68,14 +89,59 @@ impl Split {
         self.times.push(time);
     }
 
+    /// Pushes a time onto this split, following `policy` if the split already has times.
+    ///
+    /// Returns whether any existing times were replaced.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split already has times and `policy` rejects extra times.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zombiesplit::model::{
+    ///     game::{self, category::SplitTimePolicy},
+    ///     session::split::Split,
+    ///     timing::time,
+    /// };
+    ///
+    /// let mut s = Split::new(game::Split::new("pp1", "Palmtree Panic 1"));
+    /// s.push_with_policy(time::Time::from_millis(9), SplitTimePolicy::RejectExtra).unwrap();
+    /// s.push_with_policy(time::Time::from_millis(10), SplitTimePolicy::RejectExtra)
+    ///     .expect_err("second time should be rejected");
+    /// assert!(s.push_with_policy(time::Time::from_millis(11), SplitTimePolicy::Replace).unwrap());
+    /// assert_eq!(time::Time::from_millis(11), s.total_time());
+    /// ```
+    pub fn push_with_policy(
+        &mut self,
+        time: time::Time,
+        policy: game::category::SplitTimePolicy,
+    ) -> super::error::Result<bool> {
+        use game::category::SplitTimePolicy;
+
+        let has_times = !self.times.is_empty();
+        match policy {
+            SplitTimePolicy::RejectExtra if has_times => {
+                return Err(super::Error::ExtraTime(self.info.short))
+            }
+            // Replacing a time shouldn't lose any adjustment made to it.
+            SplitTimePolicy::Replace => self.times.clear(),
+            _ => (),
+        }
+        self.push(time);
+        Ok(has_times && policy == SplitTimePolicy::Replace)
+    }
+
     /// Tries to pop the most recently added time off this split.
     #[must_use]
     pub fn pop(&mut self) -> Option<time::Time> {
         self.times.pop()
     }
 
-    /// Removes all times from this split.
+    /// Removes all times, and any adjustment, from this split.
     pub fn clear(&mut self) {
         self.times.clear();
+        self.adjustment = time::Time::default();
     }
 }
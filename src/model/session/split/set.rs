@@ -2,7 +2,10 @@
 use itertools::{Either, Itertools};
 
 use super::{
-    super::super::{game, short, timing::aggregate},
+    super::{
+        super::{game, short, timing::aggregate},
+        action::MergeName,
+    },
     Split,
 };
 
@@ -69,7 +72,11 @@ impl Set {
         category
             .full_segments(game)
             .flat_map(|r| process_segment_result(r, game))
-            .map_ok(|(n, s)| game::Split::new(n, &s.name))
+            .map_ok(|(n, s)| {
+                game::Split::new(n, &s.name)
+                    .with_marker(s.marker.as_ref())
+                    .with_tolerance(s.tolerance)
+            })
             .collect()
     }
 
@@ -97,6 +104,79 @@ impl Set {
         loc.locate_mut(self)
     }
 
+    /// Gets the position of the split with short name `short`, if it is in this set.
+    #[must_use]
+    pub fn position(&self, short: short::Name) -> Option<usize> {
+        self.cache.get(&short).copied()
+    }
+
+    /// Appends a new, empty split for `info` to the end of this set.
+    ///
+    /// Returns the position of the new split, or `None` if there is already a split with the
+    /// same short name.
+    ///
+    /// ```
+    /// use zombiesplit::model::{game, session::split::Set};
+    ///
+    /// let mut set: Set = std::iter::empty::<game::Split>().collect();
+    /// assert_eq!(Some(0), set.push(game::Split::new("s1", "Split 1")));
+    /// assert_eq!(Some(1), set.push(game::Split::new("s2", "Split 2")));
+    /// assert_eq!(None, set.push(game::Split::new("s1", "Split 1 again")));
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn push(&mut self, info: game::Split) -> Option<usize> {
+        if self.cache.contains_key(&info.short) {
+            return None;
+        }
+        let index = self.contents.len();
+        self.cache.insert(info.short, index);
+        self.contents.push(Split::new(info));
+        Some(index)
+    }
+
+    /// Merges the split at position `index` with the split after it.
+    ///
+    /// The merged split keeps the first split's short name, marker, and nickname, and takes both
+    /// splits' times in order; `name` combines the two display names into one.  The merged split
+    /// is then flagged as [Split::merged].  Returns the short name of the split that was
+    /// absorbed, or `None` (changing nothing) if there is no split at `index` or after it.
+    ///
+    /// ```
+    /// use zombiesplit::model::{
+    ///     game,
+    ///     session::{action::MergeName, split::Set},
+    ///     short,
+    ///     timing::time::Time,
+    /// };
+    ///
+    /// let mut set: Set = [game::Split::new("s1", "Split 1"), game::Split::new("s2", "Split 2")]
+    ///     .into_iter()
+    ///     .collect();
+    /// set.get_mut(1).unwrap().push(Time::from_millis(1000));
+    ///
+    /// assert_eq!(Some(short::Name::from("s2")), set.merge(0, MergeName::Both));
+    /// assert_eq!(1, set.len());
+    /// assert_eq!("Split 1 / Split 2", set.get(0).unwrap().info.name);
+    /// assert_eq!(1, set.get(short::Name::from("s1")).unwrap().num_times());
+    /// assert!(set.get(short::Name::from("s1")).unwrap().merged);
+    /// assert!(set.get(short::Name::from("s2")).is_none());
+    ///
+    /// assert_eq!(None, set.merge(0, MergeName::Both));
+    /// ```
+    pub fn merge(&mut self, index: usize, name: MergeName) -> Option<short::Name> {
+        if self.contents.len() <= index + 1 {
+            return None;
+        }
+        let second = self.contents.remove(index + 1);
+        let first = &mut self.contents[index];
+        first.info.name = name.combine(&first.info.name, &second.info.name);
+        first.times.extend(second.times);
+        first.adjust(second.adjustment);
+        first.merged = true;
+        self.cache = make_cache(&self.contents);
+        Some(second.info.short)
+    }
+
     /// Gets the last split that has a defined time.
     ///
     /// The cumulative time total of this split is effectively the total time of the whole attempt.
@@ -5,10 +5,11 @@ as they connect to the server.
 */
 
 use super::{
-    super::super::model::{short, timing},
-    split, Attempt,
+    super::super::model::{game, history, short, timing},
+    action, split, Attempt,
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 /// The state of a session.
 ///
@@ -20,6 +21,11 @@ pub struct State {
     pub attempt: Attempt,
     /// Comparison data for the game/category currently being run.
     pub comparison: timing::Comparison,
+    /// Extra comparisons shown alongside the main one, keyed by name.
+    ///
+    /// Only the main comparison decides paces, colours, and highlights; overlays just give the
+    /// run more columns of times and deltas (see [super::Session::add_overlay_provider]).
+    pub overlays: BTreeMap<String, timing::Comparison>,
     /// Pre-cached extra data for the splits.
     ///
     /// This is kept separate from the attempt itself as it is effectively a denormalised
@@ -27,6 +33,70 @@ pub struct State {
     pub notes: HashMap<short::Name, SplitNote>,
     /// Total for the run, including a delta against its comparison.
     pub total: Option<timing::comparison::delta::Time>,
+    /// Position of the split the runner is currently on.
+    ///
+    /// This moves to a split whenever a time is pushed to it, and can also be set explicitly.
+    pub current: usize,
+    /// Whether the run has been finished (see [super::Action::Finish]).
+    ///
+    /// A finished run can't be edited until it is reset.
+    pub finished: bool,
+    /// Position of the split open in a client's editor, if any (see [super::Action::Edit]).
+    pub editing: Option<usize>,
+    /// How pushed times are interpreted.
+    ///
+    /// This belongs to the session rather than the attempt, so survives resets.
+    pub entry_mode: EntryMode,
+    /// What happens when the last split gets a time.
+    ///
+    /// Like the entry mode, this belongs to the session and survives resets.
+    pub on_last_split: OnLastSplit,
+    /// The best segment time ever recorded for each split, independently of the comparison.
+    ///
+    /// Like the entry mode, this belongs to the session and survives resets.
+    pub best_segments: short::Map<timing::time::Time>,
+    /// How far a split's segment time can stray from its comparison and still have an even pace
+    /// ('close call'), unless the split overrides it.
+    ///
+    /// A tolerance of zero disables close calls.  Tolerances only affect paces: a split that
+    /// beats its split PB is still gold, however close it is to the comparison.  Like the entry
+    /// mode, this belongs to the session and survives resets.
+    pub tolerance: timing::time::Time,
+    /// The buckets into which splits are classified by pace.
+    ///
+    /// Like the tolerance, these belong to the session and survive resets.
+    pub pace_buckets: timing::comparison::pace::bucket::Buckets,
+    /// The range of splits being practised, if any (see [super::Action::Practice]).
+    ///
+    /// This also survives resets, which return the run to the start of the range.
+    pub practice: Option<action::Practice>,
+}
+
+/// How times pushed to a split are interpreted.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryMode {
+    /// Times are segment times, as taken on the split itself.
+    #[default]
+    Segment,
+    /// Times are cumulative times, as read off a timer running across the whole run.
+    ///
+    /// The segment time is the pushed time less the cumulative time before the push.
+    Cumulative,
+}
+
+/// What a session does when the last split of a run gets a time.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnLastSplit {
+    /// The run stays open for corrections until finished by hand (see [super::Action::Finish]).
+    #[default]
+    Stay,
+    /// The run finishes straight away.
+    AutoFinish,
+    /// The run stays open, but observers are asked to prompt the runner to finish it (see
+    /// [super::event::Event::FinishPrompt]).
+    Prompt,
 }
 
 impl State {
@@ -38,8 +108,18 @@ impl State {
         let mut result = Self {
             attempt: run,
             comparison,
+            overlays: BTreeMap::new(),
             notes: HashMap::default(),
             total: None,
+            current: 0,
+            finished: false,
+            editing: None,
+            entry_mode: EntryMode::default(),
+            on_last_split: OnLastSplit::default(),
+            best_segments: short::Map::new(),
+            tolerance: timing::time::Time::default(),
+            pace_buckets: timing::comparison::pace::bucket::Buckets::default(),
+            practice: None,
         };
         result.reset_notes();
         result
@@ -52,6 +132,325 @@ impl State {
         self.attempt.reset(dest);
         self.reset_notes();
         self.total = None;
+        self.current = self.practice.map_or(0, |p| p.start);
+        self.finished = false;
+        self.editing = None;
+    }
+
+    /// Starts practising the range of splits in `practice` or, if `None`, stops practising.
+    ///
+    /// This doesn't reset the run; the session does that separately.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the range is empty or runs past the last split.
+    pub fn set_practice(&mut self, practice: Option<action::Practice>) -> super::error::Result<()> {
+        if let Some(p) = practice {
+            if p.end < p.start || self.attempt.splits.len() <= p.end {
+                return Err(super::Error::BadPracticeRange {
+                    start: p.start,
+                    end: p.end,
+                });
+            }
+        }
+        self.practice = practice;
+        Ok(())
+    }
+
+    /// Records the segment times of the practised splits as best segments, where they beat the
+    /// existing best segments.
+    ///
+    /// Merged splits don't count, as their times span more than one segment.
+    ///
+    /// Returns the short names and times of the new best segments.
+    pub fn record_practice_bests(&mut self) -> Vec<(short::Name, timing::time::Time)> {
+        let practice = match self.practice {
+            Some(p) => p,
+            None => return Vec::new(),
+        };
+        let mut bests = Vec::new();
+        for (index, split) in self.attempt.splits.iter().enumerate() {
+            if !practice.contains(index) || split.num_times() == 0 || split.merged {
+                continue;
+            }
+            let short = split.info.short;
+            if let Some(note) = self.notes.get(&short) {
+                let time = note.aggregates.split;
+                if self.best_segments.get(&short).map_or(true, |b| time < *b) {
+                    self.best_segments.insert(short, time);
+                    bests.push((short, time));
+                }
+            }
+        }
+        bests
+    }
+
+    /// Replaces the times and adjustments of each split with those in `timing`, as when resuming
+    /// a checkpoint.
+    ///
+    /// Splits missing from `timing` end up with no times, and times for splits not in the attempt
+    /// are ignored.  The current split becomes the [Self::first_incomplete] split.
+    pub fn restore(&mut self, timing: &history::timing::Full) {
+        let len = self.attempt.splits.len();
+        for index in 0..len {
+            if let Some(split) = self.attempt.splits.get_mut(index) {
+                split.clear();
+                for time in timing.times.get(&split.info.short).into_iter().flatten() {
+                    split.push(*time);
+                }
+                if let Some(adjustment) = timing.adjustments.get(&split.info.short) {
+                    split.adjust(*adjustment);
+                }
+            }
+        }
+        self.current = self.first_incomplete();
+        self.recalculate_indirect_fields();
+    }
+
+    /// Summarises where the run stands against its comparison, as of its most recently timed
+    /// split.
+    #[must_use]
+    pub fn live(&self) -> Live {
+        self.attempt
+            .splits
+            .last_entered()
+            .map_or_else(Live::default, |split| {
+                let short = split.info.short;
+                let note = self.notes.get(&short);
+                Live {
+                    split: Some(short),
+                    delta: note.and_then(|n| n.delta),
+                    is_gold: note.map_or(false, |n| {
+                        self.comparison.is_gold(short, n.aggregates.split)
+                    }),
+                }
+            })
+    }
+
+    /// Lists the splits in the run, in order, along with the current split.
+    ///
+    /// This is a lightweight alternative to dumping the whole state, for clients (such as
+    /// controllers) that only need to know what the splits are; they can then follow
+    /// [super::Event::CurrentSplit] to track which split is current.
+    #[must_use]
+    pub fn split_list(&self) -> SplitList {
+        SplitList {
+            splits: self
+                .attempt
+                .splits
+                .iter()
+                .map(|s| SplitEntry {
+                    short: s.info.short,
+                    name: s.name().to_owned(),
+                    has_time: s.num_times() != 0,
+                })
+                .collect(),
+            current: self.current_split(),
+        }
+    }
+
+    /// Gets the position of the first split without any times, or of the last split if every
+    /// split has times.
+    ///
+    /// This is where a runner coming back to the run (for instance, after browsing earlier
+    /// splits) most likely wants to be; clients can move there with [super::Action::SetCurrent].
+    #[must_use]
+    pub fn first_incomplete(&self) -> usize {
+        self.attempt
+            .splits
+            .iter()
+            .position(|s| s.num_times() == 0)
+            .unwrap_or_else(|| self.attempt.splits.len().saturating_sub(1))
+    }
+
+    /// Gets the deltas of each split in the run against the overlay comparison `overlay`.
+    ///
+    /// As with the main comparison, splits without times, or that the overlay doesn't have, get
+    /// no delta.
+    pub fn overlay_deltas<'a>(
+        &'a self,
+        overlay: &'a timing::Comparison,
+    ) -> impl Iterator<Item = (short::Name, timing::comparison::delta::Split)> + 'a {
+        self.attempt.splits.iter().filter_map(move |split| {
+            let note = self.notes.get(&split.info.short)?;
+            let delta = delta_against(overlay, split, note.aggregates)?;
+            Some((split.info.short, delta))
+        })
+    }
+
+    /// Snapshots the run so far as a comparison, as saved to comparison files.
+    ///
+    /// Only splits with times make it into the snapshot, with their times so far; the run total
+    /// is the cumulative time at the last of them.  Each split's PB is the better of its time in
+    /// this run and its PB in the current comparison, if it has one.
+    ///
+    /// The snapshot has no name, so a comparison file holding it takes its name from the file.
+    #[must_use]
+    pub fn snapshot(&self) -> timing::Comparison {
+        let mut result = timing::Comparison::default();
+        let mut sum_of_best = timing::time::Time::default();
+        for split in self.attempt.splits.iter().filter(|s| s.num_times() != 0) {
+            let short = split.info.short;
+            if let Some(note) = self.notes.get(&short) {
+                let in_pb_run = note.aggregates;
+                let split_pb = self
+                    .comparison
+                    .splits
+                    .get(&short)
+                    .map(|s| s.split_pb)
+                    .filter(|pb| pb.into_millis() != 0)
+                    .map_or(in_pb_run.split, |pb| pb.min(in_pb_run.split));
+                sum_of_best += split_pb;
+                result.run.total_in_pb_run = Some(in_pb_run.cumulative);
+                result.splits.insert(
+                    short,
+                    timing::comparison::Segment {
+                        split_pb,
+                        in_pb_run,
+                    },
+                );
+            }
+        }
+        if !result.splits.is_empty() {
+            result.run.sum_of_best = Some(sum_of_best);
+        }
+        result
+    }
+
+    /// Finds the splits whose own segment times did best and worst against the comparison.
+    ///
+    /// Returns the short names of the best and worst splits, in that order, or `None` if no split
+    /// has a delta against the comparison.  Ties go to the earliest split.
+    #[must_use]
+    pub fn best_and_worst_splits(&self) -> Option<(short::Name, short::Name)> {
+        let mut deltas = self.attempt.splits.iter().filter_map(|s| {
+            let short = s.info.short;
+            let delta = self.notes.get(&short).and_then(|n| n.delta)?;
+            Some((short, delta.split))
+        });
+        let first = deltas.next()?;
+        let (best, worst) = deltas.fold((first, first), |(best, worst), x| {
+            (
+                if x.1 < best.1 { x } else { best },
+                if worst.1 < x.1 { x } else { worst },
+            )
+        });
+        Some((best.0, worst.0))
+    }
+
+    /// Gets whether the run is projected to finish ahead of (or level with) its comparison.
+    ///
+    /// The projected total is the cumulative time so far plus the comparison's times for the
+    /// remaining splits; this is ahead exactly when the run delta at the last entered split is.
+    /// Returns `None` if there is no such delta (for instance, if the last entered split has no
+    /// comparison time).
+    #[must_use]
+    pub fn is_projected_ahead(&self) -> Option<bool> {
+        self.attempt
+            .splits
+            .last_entered()
+            .and_then(|s| self.notes.get(&s.info.short))
+            .and_then(|note| note.delta)
+            .map(|d| d.run.pace() == timing::comparison::Pace::Ahead)
+    }
+
+    /// Tries to finish the run, locking it from further edits.
+    ///
+    /// Returns the final time of the run if this finished it, or `None` if it was already
+    /// finished.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the last split of the run has no time.
+    pub fn finish(&mut self) -> super::error::Result<Option<timing::time::Time>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let last_has_time = self
+            .attempt
+            .splits
+            .iter()
+            .last()
+            .map_or(false, |s| 0 < s.num_times());
+        if !last_has_time {
+            return Err(super::Error::Unfinished);
+        }
+        self.finished = true;
+        Ok(Some(self.total.map(|t| t.time).unwrap_or_default()))
+    }
+
+    /// Gets the short name of the current split, if there is one.
+    #[must_use]
+    pub fn current_split(&self) -> Option<short::Name> {
+        self.attempt.splits.get(self.current).map(|s| s.info.short)
+    }
+
+    /// Gets the time to aim for on the split being run: the comparison's segment time for it.
+    ///
+    /// The split being run is the one after the last split with times, whichever split is
+    /// current; a runner pushing a time has finished that split and moved on to the next.
+    ///
+    /// This is `None` if every split has times, or if the comparison has no segment time for the
+    /// split being run (for instance, if the comparison run skipped it).
+    #[must_use]
+    pub fn target(&self) -> Option<timing::time::Time> {
+        let running = self
+            .attempt
+            .splits
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.num_times() != 0)
+            .last()
+            .map_or(0, |(last, _)| last + 1);
+        self.attempt
+            .splits
+            .get(running)
+            .and_then(|s| self.comparison.aggregate_for(s.info.short))
+            .map(|a| a[timing::aggregate::Scope::Split])
+            .filter(|t| t.into_millis() != 0)
+    }
+
+    /// Moves the current split to position `index`, if it is in range.
+    ///
+    /// Returns the short name of the new current split if it changed.
+    pub fn set_current(&mut self, index: usize) -> Option<short::Name> {
+        if index == self.current || self.attempt.splits.len() <= index {
+            return None;
+        }
+        self.current = index;
+        self.current_split()
+    }
+
+    /// Marks the split at position `index` as being open in an editor, or, if `index` is `None`,
+    /// marks the editor as closed.
+    ///
+    /// Returns `None` if nothing changed (including if `index` is out of range); otherwise,
+    /// returns the short name of the split now being edited, if any.
+    pub fn set_editing(&mut self, index: Option<usize>) -> Option<Option<short::Name>> {
+        let short = match index {
+            Some(i) => Some(self.attempt.splits.get(i)?.info.short),
+            None => None,
+        };
+        if index == self.editing {
+            return None;
+        }
+        self.editing = index;
+        Some(short)
+    }
+
+    /// Sets the run metadata `key` to `value`, or removes it if `value` is empty.
+    ///
+    /// Returns whether the metadata changed.
+    pub fn set_meta(&mut self, key: &str, value: &str) -> bool {
+        let metadata = &mut self.attempt.metadata;
+        if value.is_empty() {
+            metadata.remove(key).is_some()
+        } else if metadata.get(key).map(String::as_str) == Some(value) {
+            false
+        } else {
+            metadata.insert(key.to_owned(), value.to_owned());
+            true
+        }
     }
 
     /// Gets a mutable reference to the split at the given location.
@@ -62,46 +461,235 @@ impl State {
 
     /// Tries to locate the given split and, if found, pushes the given time to it.
     ///
-    /// Returns the short-name of the split if successful.
+    /// The push follows the attempt's split time policy, and interprets `time` according to the
+    /// entry mode.  Returns information about the push if the split was found.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split policy rejected the push, or if `time` is a cumulative time that is
+    /// before the cumulative time already on the run.
     pub fn push_to(
         &mut self,
         split: impl split::Locator,
         time: timing::time::Time,
-    ) -> Option<short::Name> {
-        self.act_on_split(split, |s| s.push(time))
+    ) -> super::error::Result<Option<Push>> {
+        self.push_to_with_mode(split, time, self.entry_mode)
+    }
+
+    /// Like [Self::push_to], but reads `time` as a cumulative time whatever the entry mode.
+    ///
+    /// This is useful for one-off entries read from an in-game timer showing the total so far.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split policy rejected the push, or if `time` is before the cumulative time
+    /// already on the run.
+    pub fn push_total_to(
+        &mut self,
+        split: impl split::Locator,
+        time: timing::time::Time,
+    ) -> super::error::Result<Option<Push>> {
+        self.push_to_with_mode(split, time, EntryMode::Cumulative)
+    }
+
+    fn push_to_with_mode(
+        &mut self,
+        split: impl split::Locator,
+        time: timing::time::Time,
+        mode: EntryMode,
+    ) -> super::error::Result<Option<Push>> {
+        let short = match split.locate(&self.attempt.splits) {
+            Some(s) => s.info.short,
+            None => return Ok(None),
+        };
+        let time = self.entered_segment(short, time, mode)?;
+        let policy = self.attempt.time_policy;
+        let push = self
+            .try_act_on_split(short, |s| {
+                s.push_with_policy(time, policy).map(|replaced| Push {
+                    short: s.info.short,
+                    time,
+                    replaced,
+                    new_current: None,
+                })
+            })
+            .transpose()?;
+        Ok(push.map(|mut push| {
+            if let Some(index) = self.attempt.splits.position(push.short) {
+                push.new_current = self.set_current(index);
+            }
+            push
+        }))
+    }
+
+    /// Converts `time`, as entered for the split `short` in `mode`, into a segment time.
+    fn entered_segment(
+        &self,
+        short: short::Name,
+        time: timing::time::Time,
+        mode: EntryMode,
+    ) -> super::error::Result<timing::time::Time> {
+        match mode {
+            EntryMode::Segment => Ok(time),
+            EntryMode::Cumulative => {
+                let previous = self.cumulative_before(short);
+                if time < previous {
+                    Err(super::Error::CumulativeBehind {
+                        split: short,
+                        previous,
+                    })
+                } else {
+                    Ok(time - previous)
+                }
+            }
+        }
+    }
+
+    /// Gets the cumulative time of the run just before a push to the split `short`.
+    ///
+    /// This includes the split's own existing times only if the push will add to them.
+    fn cumulative_before(&self, short: short::Name) -> timing::time::Time {
+        let mut previous = timing::time::Time::default();
+        for split in self.attempt.splits.iter() {
+            if split.info.short == short {
+                if self.attempt.time_policy == game::category::SplitTimePolicy::Accumulate {
+                    previous += split.total_time();
+                }
+                break;
+            }
+            previous += split.total_time();
+        }
+        previous
+    }
+
+    /// Appends a new split with short name `short`, then pushes `time` to it.
+    ///
+    /// The new split's display name is its short name.  Returns the position of the new split,
+    /// alongside information about the push.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is already a split named `short`, or if the push was rejected.
+    pub fn push_new_split(
+        &mut self,
+        short: short::Name,
+        time: timing::time::Time,
+    ) -> super::error::Result<(usize, Option<Push>)> {
+        let index = self
+            .attempt
+            .splits
+            .push(game::Split::new(short, &short))
+            .ok_or(super::Error::DuplicateSplit(short))?;
+        self.notes.insert(short, SplitNote::default());
+        Ok((index, self.push_to(index, time)?))
+    }
+
+    /// Merges the split at position `index` with the split after it, naming the result according
+    /// to `name`.
+    ///
+    /// The current and editing positions move so that they still point to the same splits (or,
+    /// if they pointed to the absorbed split, to the merged split).  Returns `None` if there is no
+    /// split at `index`; otherwise, returns the short name of the absorbed split.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the split at `index` is the last split.
+    pub fn merge_splits(
+        &mut self,
+        index: usize,
+        name: action::MergeName,
+    ) -> super::error::Result<Option<short::Name>> {
+        let first = match self.attempt.splits.get(index) {
+            Some(s) => s.info.short,
+            None => return Ok(None),
+        };
+        let second = self
+            .attempt
+            .splits
+            .merge(index, name)
+            .ok_or(super::Error::NothingToMerge(first))?;
+
+        self.notes.remove(&second);
+        if index < self.current {
+            self.current -= 1;
+        }
+        self.editing = self.editing.map(|e| if index < e { e - 1 } else { e });
+        self.recalculate_indirect_fields();
+        Ok(Some(second))
     }
 
     /// Tries to locate the given split and, if found, pops the most recent time from it.
     ///
-    /// Returns the short-name of the split if fully successful.
+    /// Returns the short-name of the split if it had a time to pop.
     pub fn pop_from(&mut self, split: impl split::Locator) -> Option<short::Name> {
-        self.act_on_split(split, |s| {
-            let _ = s.pop();
-        })
+        self.act_on_split(split, |s| s.pop().is_some())
     }
 
     /// Tries to locate the given split and, if found, clears all times from it.
     ///
-    /// Returns the short-name of the split if successful.
+    /// Returns the short-name of the split if it had any times to clear.
     pub fn clear_at(&mut self, split: impl split::Locator) -> Option<short::Name> {
-        self.act_on_split(split, split::Split::clear)
+        self.act_on_split(split, |s| {
+            let had_times = 0 < s.num_times() || s.adjustment != timing::time::Time::default();
+            s.clear();
+            had_times
+        })
+    }
+
+    /// Clears the times and adjustments of every split.
+    ///
+    /// Unlike [Self::reset], this leaves the attempt counts, metadata, and current split alone.
+    /// Returns the short names of the splits that had anything to clear.
+    pub fn clear_all(&mut self) -> Vec<short::Name> {
+        (0..self.attempt.splits.len())
+            .filter_map(|i| self.clear_at(i))
+            .collect()
+    }
+
+    /// Tries to locate the given split and, if found, adds `by` to its adjustment.
+    ///
+    /// Returns the short-name of the split, and its new adjustment, if the adjustment changed.
+    pub fn adjust(
+        &mut self,
+        split: impl split::Locator,
+        by: timing::time::Time,
+    ) -> Option<(short::Name, timing::time::Time)> {
+        self.try_act_on_split(split, |s| {
+            if s.adjust(by) {
+                Ok((s.info.short, s.adjustment))
+            } else {
+                Err(())
+            }
+        })
+        .and_then(Result::ok)
     }
 
     /// Common pattern of various actions on splits.
+    ///
+    /// `f` returns whether it changed the split; if not, this returns `None`.
     fn act_on_split(
         &mut self,
         split: impl split::Locator,
-        f: impl FnOnce(&mut split::Split),
+        f: impl FnOnce(&mut split::Split) -> bool,
     ) -> Option<short::Name> {
-        self.get_split_mut(split)
-            .map(|s| {
-                f(s);
-                s.info.short
-            })
-            .map(|result| {
+        self.try_act_on_split(split, |s| if f(s) { Ok(s.info.short) } else { Err(()) })
+            .and_then(Result::ok)
+    }
+
+    /// Common pattern of various fallible actions on splits.
+    ///
+    /// Indirect fields are recalculated only if `f` succeeds.
+    fn try_act_on_split<T, E>(
+        &mut self,
+        split: impl split::Locator,
+        f: impl FnOnce(&mut split::Split) -> Result<T, E>,
+    ) -> Option<Result<T, E>> {
+        self.get_split_mut(split).map(f).map(|result| {
+            if result.is_ok() {
                 self.recalculate_indirect_fields();
-                result
-            })
+            }
+            result
+        })
     }
 
     fn recalculate_indirect_fields(&mut self) {
@@ -119,28 +707,89 @@ impl State {
     /// Populates the notes table with recalculated notes.
     fn recalculate_notes(&mut self) {
         // TODO(@MattWindsor91): only recalculate from the changed split.
+        // The last known run delta, for working out trends; splits without a comparison carry
+        // this forward.
+        let mut last_delta = None;
         for (s, a) in self.attempt.splits.aggregates() {
-            self.notes.insert(s.info.short, self.note(s, a));
+            let note = self.note(s, a, last_delta);
+            last_delta = note.delta.map(|d| d.run).or(last_delta);
+            self.notes.insert(s.info.short, note);
         }
     }
 
-    fn note(&self, split: &split::Split, aggregates: timing::aggregate::Set) -> SplitNote {
+    fn note(
+        &self,
+        split: &split::Split,
+        aggregates: timing::aggregate::Set,
+        last_delta: Option<timing::comparison::Delta>,
+    ) -> SplitNote {
+        let delta = self.split_delta(split, aggregates);
+        let trend = delta.map_or_else(Default::default, |d| d.run.trend_since(last_delta));
+        let segment_pace = self.segment_pace(split, delta);
+        let is_gold = self.comparison.is_gold(split.info.short, aggregates.split);
+        let pace = delta.map_or_else(Default::default, |d| {
+            if is_gold {
+                timing::comparison::pace::SplitInRun::SplitPersonalBest
+            } else {
+                d.pace_within(self.tolerance_for(split))
+            }
+        });
+        let bucket = delta.and_then(|d| self.pace_buckets.position(d, is_gold));
         SplitNote {
             aggregates,
-            delta: self.split_delta(split, aggregates),
+            delta,
+            pace,
+            trend,
+            segment_pace,
+            bucket,
         }
     }
 
+    /// Gets the pace bucket into which the note for `split` put it, if any.
+    #[must_use]
+    pub fn pace_bucket(
+        &self,
+        split: short::Name,
+    ) -> Option<&timing::comparison::pace::bucket::Bucket> {
+        self.notes
+            .get(&split)
+            .and_then(|n| n.bucket)
+            .and_then(|i| self.pace_buckets.0.get(i))
+    }
+
+    /// Gets the pace of `split` considered on its own, given its `delta`.
+    ///
+    /// This is inconclusive if the comparison has no segment time for the split; for instance,
+    /// if the comparison run skipped it.  It is even if the delta is within the split's tolerance.
+    fn segment_pace(
+        &self,
+        split: &split::Split,
+        delta: Option<timing::comparison::delta::Split>,
+    ) -> timing::comparison::Pace {
+        let has_segment = self
+            .comparison
+            .aggregate_for(split.info.short)
+            .map_or(false, |a| a.split.into_millis() != 0);
+        delta
+            .filter(|_| has_segment)
+            .map_or_else(Default::default, |d| {
+                d.split.pace_within(self.tolerance_for(split))
+            })
+    }
+
+    /// Gets the close-call tolerance for `split`: its own override if it has one, and otherwise
+    /// the session-wide tolerance.
+    #[must_use]
+    pub fn tolerance_for(&self, split: &split::Split) -> timing::time::Time {
+        split.info.tolerance.unwrap_or(self.tolerance)
+    }
+
     fn split_delta(
         &self,
         split: &split::Split,
         aggregates: timing::aggregate::Set,
     ) -> Option<timing::comparison::delta::Split> {
-        if split.num_times() == 0 {
-            None
-        } else {
-            Some(self.comparison.delta(split.info.short, aggregates))
-        }
+        delta_against(&self.comparison, split, aggregates)
     }
 
     fn recalculate_total(&mut self) {
@@ -149,15 +798,41 @@ impl State {
             .splits
             .last_entered()
             .and_then(|s| self.notes.get(&s.info.short))
-            .and_then(|note| {
-                note.delta.map(|d| super::comparison::delta::Time {
-                    time: note.aggregates.cumulative,
-                    delta: d.run,
-                })
+            .map(|note| super::comparison::delta::Time {
+                time: note.aggregates.cumulative,
+                delta: note.delta.map(|d| d.run).unwrap_or_default(),
             });
     }
 }
 
+/// Gets the delta of `split`, with attempt aggregates `aggregates`, against `comparison`.
+fn delta_against(
+    comparison: &timing::Comparison,
+    split: &split::Split,
+    aggregates: timing::aggregate::Set,
+) -> Option<timing::comparison::delta::Split> {
+    // Splits missing from the comparison have an inconclusive pace.
+    let has_comparison = comparison.aggregate_for(split.info.short).is_some();
+    if split.num_times() == 0 || !has_comparison {
+        None
+    } else {
+        Some(comparison.delta(split.info.short, aggregates))
+    }
+}
+
+/// Information about a successful push to a split.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Push {
+    /// The short name of the split pushed to.
+    pub short: short::Name,
+    /// The segment time pushed, after interpreting the entered time.
+    pub time: timing::time::Time,
+    /// Whether the push replaced the split's existing times.
+    pub replaced: bool,
+    /// The short name of the new current split, if the push changed it.
+    pub new_current: Option<short::Name>,
+}
+
 /// A precomputed series of facts about a split.
 ///
 /// These are things that the client could compute itself, but which we keep in the state to
@@ -169,8 +844,60 @@ pub struct SplitNote {
     /// Comparison-level aggregates are in the comparison.
     pub aggregates: timing::aggregate::Set,
     /// Delta between this split and comparison.
-    /// May be missing, if there are no times.
+    /// May be missing, if there are no times or the comparison has no time for this split.
     pub delta: Option<timing::comparison::delta::Split>,
+    /// Split-in-run pace of this split against comparison.
+    ///
+    /// Splits within their tolerance of the comparison are even, and gold splits are personal
+    /// bests whatever their tolerance.  This is inconclusive if there is no delta.
+    pub pace: timing::comparison::pace::SplitInRun,
+    /// Whether the run delta grew or shrank over this split.
+    ///
+    /// This is neutral on the first split with a comparison, and on any split without one.
+    pub trend: timing::comparison::pace::Trend,
+    /// Pace of this split's own segment time against the comparison's segment time.
+    ///
+    /// Unlike the pace of the run delta, this distinguishes a good segment in a run that is still
+    /// behind from a bad one.  It is inconclusive if there is no comparison segment time.
+    pub segment_pace: timing::comparison::Pace,
+    /// Position, in the state's pace buckets, of the first bucket matching this split.
+    ///
+    /// This is missing if the split has no delta.
+    pub bucket: Option<usize>,
+}
+
+/// A denormalised summary of where the run stands against its comparison.
+///
+/// This gathers, into one place, the information an overlay typically shows about the split the
+/// runner has most recently finished.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Live {
+    /// The short name of the most recently timed split, or `None` if no split has times.
+    pub split: Option<short::Name>,
+    /// The segment and cumulative deltas of that split against the comparison, if it has them.
+    pub delta: Option<timing::comparison::delta::Split>,
+    /// Whether that split's segment time beats its personal best.
+    pub is_gold: bool,
+}
+
+/// A lightweight listing of the splits in a run (see [State::split_list]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SplitList {
+    /// The splits, in run order.
+    pub splits: Vec<SplitEntry>,
+    /// The short name of the current split, if there is one.
+    pub current: Option<short::Name>,
+}
+
+/// One split in a [SplitList].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitEntry {
+    /// The split's short name, for use in actions.
+    pub short: short::Name,
+    /// The split's display name.
+    pub name: String,
+    /// Whether the split has any times.
+    pub has_time: bool,
 }
 
 impl SplitNote {
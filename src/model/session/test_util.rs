@@ -0,0 +1,261 @@
+/*! Helpers for testing code that uses sessions.
+
+This module is available to zombiesplit's own tests, and to other crates through the `test-util`
+feature.  It provides:
+
+- [Recorder], an observer that records every event it sees;
+- [fixed_timestamp], a timestamper that always returns the same time;
+- [Builder] (also available as [Session::builder]), for making sessions over ad-hoc splits.
+*/
+
+use std::sync::Mutex;
+
+use chrono::TimeZone;
+
+use super::{
+    super::{
+        game::{self, category},
+        history, short,
+        timing::{time, Comparison},
+    },
+    Attempt, Event, Observer, Session,
+};
+
+/// An observer that records every event it observes, in order.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    log: Mutex<Vec<Event>>,
+}
+
+impl Recorder {
+    /// Gets a copy of every event observed so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log's lock has been poisoned.
+    #[must_use]
+    pub fn events(&self) -> Vec<Event> {
+        self.log.lock().expect("couldn't lock log").clone()
+    }
+
+    /// Gets whether `evt` has been observed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log's lock has been poisoned.
+    #[must_use]
+    pub fn contains(&self, evt: &Event) -> bool {
+        self.log.lock().expect("couldn't lock log").contains(evt)
+    }
+
+    /// Forgets every event observed so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log's lock has been poisoned.
+    pub fn clear(&self) {
+        self.log.lock().expect("couldn't lock log").clear();
+    }
+}
+
+impl Observer for Recorder {
+    fn observe(&self, evt: Event) {
+        self.log.lock().expect("couldn't lock log").push(evt);
+    }
+}
+
+/// A timestamper that always gives midnight on 1 January 2000 (UTC).
+///
+/// # Panics
+///
+/// Never panics in practice; the fixed date is always valid.
+#[must_use]
+pub fn fixed_timestamp() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc
+        .timestamp_opt(FIXED_TIMESTAMP, 0)
+        .single()
+        .expect("fixed timestamp should be valid")
+}
+
+/// A builder for test sessions.
+#[derive(Debug, Default)]
+pub struct Builder {
+    splits: Vec<game::Split>,
+    comparison: Option<Comparison>,
+    time_policy: category::SplitTimePolicy,
+}
+
+impl Builder {
+    /// Adds splits with the short names `shorts`.
+    ///
+    /// Each split's display name is its short name.
+    #[must_use]
+    pub fn splits<S: Into<short::Name>>(mut self, shorts: impl IntoIterator<Item = S>) -> Self {
+        self.splits.extend(shorts.into_iter().map(|s| {
+            let short = s.into();
+            game::Split::new(short, &short)
+        }));
+        self
+    }
+
+    /// Sets the comparison against which the session runs.
+    #[must_use]
+    pub fn comparison(mut self, comparison: Comparison) -> Self {
+        self.comparison = Some(comparison);
+        self
+    }
+
+    /// Sets the time policy of the session's attempt.
+    #[must_use]
+    pub fn time_policy(mut self, policy: category::SplitTimePolicy) -> Self {
+        self.time_policy = policy;
+        self
+    }
+
+    /// Builds a session observed by `observer`.
+    ///
+    /// The session uses [fixed_timestamp] to timestamp runs.
+    #[must_use]
+    pub fn build<'cmp, 'obs, O: Observer>(self, observer: &'obs O) -> Session<'cmp, 'obs, O> {
+        let attempt = Attempt {
+            category: category::Target {
+                game: TEST_NAME.to_owned(),
+                category: TEST_NAME.to_owned(),
+                short: category::ShortDescriptor::new(TEST_NAME, TEST_NAME),
+            },
+            info: category::AttemptInfo::default(),
+            splits: self.splits.into_iter().collect(),
+            time_policy: self.time_policy,
+            rounding: time::Rounding::default(),
+            metadata: history::run::Metadata::default(),
+        };
+
+        let mut session = Session::new(attempt, observer);
+        session.set_timestamper(fixed_timestamp);
+        if let Some(comparison) = self.comparison {
+            session.set_comparison_provider(Box::new(Some(comparison)));
+        }
+        session
+    }
+}
+
+// The observer type here is only a placeholder, so that `Session::builder()` needs no annotations;
+// the builder can build sessions for any observer.
+impl<'cmp, 'obs> Session<'cmp, 'obs, super::event::observer::Null> {
+    /// Starts building a session for testing.
+    #[must_use]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+/// Name used for the game and category of test sessions.
+const TEST_NAME: &str = "test";
+
+/// Unix timestamp of midnight on 1 January 2000 (UTC).
+const FIXED_TIMESTAMP: i64 = 946_684_800;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{
+        session::{action::Handler, event, Action},
+        timing::time,
+    };
+
+    /// An example of using the harness to check a session's behaviour.
+    #[test]
+    fn split_advances_current() {
+        let obs = Recorder::default();
+        let mut session = Session::builder().splits(["s1", "s2"]).build(&obs);
+
+        session
+            .handle(Action::Split(time::Time::from_millis(1000)))
+            .expect("action shouldn't fail");
+
+        assert!(obs.contains(&Event::CurrentSplit(short::Name::from("s2"))));
+        assert_eq!(1, session.dump().expect("dump shouldn't fail").current);
+    }
+
+    #[test]
+    fn first_incomplete_skips_timed_splits() {
+        let obs = Recorder::default();
+        let mut session = Session::builder().splits(["s1", "s2"]).build(&obs);
+        let time = time::Time::from_millis(1000);
+
+        session
+            .handle(Action::Push(0, time))
+            .expect("action shouldn't fail");
+        assert_eq!(
+            1,
+            session
+                .dump()
+                .expect("dump shouldn't fail")
+                .first_incomplete()
+        );
+
+        session
+            .handle(Action::Push(1, time))
+            .expect("action shouldn't fail");
+        assert_eq!(
+            1,
+            session
+                .dump()
+                .expect("dump shouldn't fail")
+                .first_incomplete(),
+            "a fully timed run should go to the last split"
+        );
+    }
+
+    /// Adjustments count towards a split's total, but stay apart from its times.
+    #[test]
+    fn adjust_keeps_adjustment_separate() {
+        let obs = Recorder::default();
+        let mut session = Session::builder().splits(["s1", "s2"]).build(&obs);
+        let s1 = short::Name::from("s1");
+
+        session
+            .handle(Action::Push(0, time::Time::from_millis(1000)))
+            .expect("action shouldn't fail");
+        session
+            .handle(Action::Adjust(0, time::Time::from_millis(500)))
+            .expect("action shouldn't fail");
+        assert!(obs.contains(&Event::Split(
+            s1,
+            event::split::Split::Adjustment(time::Time::from_millis(500))
+        )));
+
+        let state = session.dump().expect("dump shouldn't fail");
+        let split = state.attempt.splits.get(0).expect("split should exist");
+        assert_eq!(vec![time::Time::from_millis(1000)], split.all_times());
+        assert_eq!(time::Time::from_millis(1500), split.total_time());
+
+        let timing = state.attempt.timing_as_historic();
+        assert_eq!(
+            Some(&time::Time::from_millis(500)),
+            timing.adjustments.get(&s1)
+        );
+    }
+
+    #[test]
+    fn split_list_marks_timed_splits() {
+        let obs = Recorder::default();
+        let mut session = Session::builder().splits(["s1", "s2"]).build(&obs);
+
+        session
+            .handle(Action::Split(time::Time::from_millis(1000)))
+            .expect("action shouldn't fail");
+
+        let list = session.dump().expect("dump shouldn't fail").split_list();
+        let timed: Vec<(String, bool)> = list
+            .splits
+            .iter()
+            .map(|s| (s.short.to_string(), s.has_time))
+            .collect();
+        assert_eq!(
+            vec![("s1".to_owned(), true), ("s2".to_owned(), false)],
+            timed
+        );
+        assert_eq!(Some(short::Name::from("s2")), list.current);
+    }
+}
@@ -1,9 +1,13 @@
 //! Type aliases for short names and associated types.
 
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
 
-use rusqlite::{types::FromSql, ToSql};
+use rusqlite::{
+    types::{FromSql, FromSqlError},
+    ToSql,
+};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
 
 /// A 'short name', used to identify parts of a model outside of the database.
 ///
@@ -14,22 +18,53 @@ use serde_with::{DeserializeFromStr, SerializeDisplay};
 /// Externally, short names convert to and from strings when interfacing with
 /// humans, and are usually exchanged for primary keys when interfacing with the
 /// database.
+///
+/// Valid short names are nonempty, and contain no whitespace, uppercase letters, or slashes.
+/// Parsing a short name (including deserialising one or reading one from the database) checks
+/// this.  Short names can also convert [From] string literals, which aren't checked; anything
+/// that comes from outside the program should go through [Name::new] or [FromStr] instead.
 #[derive(
     Clone, Copy, Debug, Hash, DeserializeFromStr, SerializeDisplay, PartialEq, Eq, PartialOrd, Ord,
 )]
 pub struct Name(symbol::Symbol);
 
-impl Default for Name {
-    fn default() -> Self {
-        Name(symbol::Symbol::from(""))
+impl Name {
+    /// Constructs a short name from `s`, checking that it is valid.
+    ///
+    /// ```
+    /// use zombiesplit::model::short;
+    ///
+    /// assert_eq!(Ok(short::Name::from("btg-sonic")), short::Name::new("btg-sonic"));
+    /// assert_eq!(Err(short::Error::Empty), short::Name::new(""));
+    /// assert!(short::Name::new("btg sonic").is_err());
+    /// assert!(short::Name::new("BTG-Sonic").is_err());
+    /// assert!(short::Name::new("btg/sonic").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if `s` is empty, or contains whitespace, uppercase letters, or slashes.
+    pub fn new(s: &str) -> Result<Self, Error> {
+        if s.is_empty() {
+            Err(Error::Empty)
+        } else if s.contains(char::is_whitespace) {
+            Err(Error::Whitespace(s.to_owned()))
+        } else if s.contains(char::is_uppercase) {
+            Err(Error::Uppercase(s.to_owned()))
+        } else if s.contains('/') {
+            Err(Error::Slash(s.to_owned()))
+        } else {
+            Ok(Name(symbol::Symbol::from(s)))
+        }
     }
 }
 
+/// Short names parse with validation (see [Name::new]).
 impl FromStr for Name {
-    type Err = Infallible;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Name(symbol::Symbol::from(s)))
+        Self::new(s)
     }
 }
 
@@ -39,9 +74,10 @@ impl std::fmt::Display for Name {
     }
 }
 
-impl<T: Into<symbol::Symbol>> From<T> for Name {
-    fn from(x: T) -> Name {
-        Name(x.into())
+/// Literal short names convert without validation, as they are known when writing the program.
+impl From<&'static str> for Name {
+    fn from(x: &'static str) -> Name {
+        Name(symbol::Symbol::from(x))
     }
 }
 
@@ -55,9 +91,57 @@ impl ToSql for Name {
 
 impl FromSql for Name {
     fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        Ok(Name(symbol::Symbol::from(value.as_str()?)))
+        Name::new(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
     }
 }
 
+impl schemars::JsonSchema for Name {
+    fn schema_name() -> String {
+        "ShortName".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        super::load::schema::string(
+            "A short name: nonempty, with no whitespace, uppercase letters, or slashes.",
+            Some(r"^[^\sA-Z/]+$"),
+        )
+    }
+}
+
+/// Errors that can occur when validating a short name.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The short name was empty.
+    #[error("short names can't be empty")]
+    Empty,
+    /// The short name contained whitespace.
+    #[error("short name {0:?} contains whitespace")]
+    Whitespace(String),
+    /// The short name contained uppercase letters.
+    #[error("short name {0:?} contains uppercase letters")]
+    Uppercase(String),
+    /// The short name contained a slash, which would clash with short descriptors.
+    #[error("short name {0:?} contains a slash")]
+    Slash(String),
+}
+
 /// Type alias for maps from short names to items.
 pub type Map<T> = std::collections::HashMap<Name, T>;
+
+/// Type alias for sets of short names.
+pub type Set = std::collections::HashSet<Name>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that short names read from the database are validated.
+    #[test]
+    fn from_sql_validates() {
+        let conn = rusqlite::Connection::open_in_memory().expect("couldn't open database");
+        let get = |s: &str| conn.query_row("SELECT ?", [s], |r| r.get::<_, Name>(0));
+        assert_eq!(Name::from("scd11"), get("scd11").expect("should be valid"));
+        get("Sonic CD").expect_err("should be invalid");
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::ops::{Index, IndexMut};
 
+use serde::{Deserialize, Serialize};
+
 use super::{
     super::{super::session::split, time},
     index::{Kind, Scope, Source},
@@ -56,7 +58,7 @@ impl IndexMut<Kind> for Full {
 }
 
 /// A set of aggregate times at various scopes.
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Set {
     /// Single time for this split only.
     pub split: time::Time,
@@ -1,14 +1,28 @@
 //! Parts of the model related to comparisons.
 
+pub mod balanced;
+pub mod composite;
 pub mod delta;
+pub mod file;
+pub mod goal;
+pub mod latest;
 pub mod pace;
 pub mod provider;
 pub mod run;
+pub mod weighted;
 
+pub use balanced::BalancedProvider;
+pub use composite::CompositeProvider;
 pub use delta::Delta;
+pub use file::FileProvider;
+pub use goal::GoalProvider;
+pub use latest::LatestRunProvider;
 pub use pace::{Pace, PacedTime};
 pub use provider::Provider;
 pub use run::Run;
+pub use weighted::WeightedProvider;
+
+use serde::{Deserialize, Serialize};
 
 use super::{super::short, aggregate, time};
 
@@ -17,8 +31,13 @@ use super::{super::short, aggregate, time};
 /// A comparison struct contains both per-split and aggregated times.  There is no inherent checking
 /// that the former and latter agree, as some comparison providers may have ways of calculating the
 /// latter that don't involve calculating the former.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Comparison {
+    /// The name of the comparison, as shown to the user (for instance, 'Personal Best').
+    ///
+    /// Sessions fill this in from their comparison provider's [Provider::name], if it has one.
+    pub name: Option<String>,
     /// Split comparisons.
     pub splits: short::Map<Segment>,
     /// Precomputed run data.
@@ -34,6 +53,37 @@ impl Comparison {
             .map_or(delta::Split::default(), |x| x.delta(against))
     }
 
+    /// Gets the possible timesave for the split with short name `split`, if available.
+    ///
+    /// See [Segment::possible_timesave].
+    #[must_use]
+    pub fn possible_timesave(&self, split: short::Name) -> Option<time::Time> {
+        self.splits.get(&split).and_then(Segment::possible_timesave)
+    }
+
+    /// Gets whether `split_time` for the split with short name `split` beats the split's personal
+    /// best (that is, whether it is a 'gold split').
+    ///
+    /// Splits without a personal best in this comparison are never gold.
+    ///
+    /// ```
+    /// use zombiesplit::model::{short, timing::{comparison::{Comparison, Segment}, time::Time}};
+    ///
+    /// let pp1 = short::Name::from("pp1");
+    /// let mut comparison = Comparison::default();
+    /// assert!(!comparison.is_gold(pp1, Time::from_millis(1)));
+    ///
+    /// comparison.splits.insert(pp1, Segment { split_pb: Time::from_millis(20_000), ..Segment::default() });
+    /// assert!(comparison.is_gold(pp1, Time::from_millis(19_999)));
+    /// assert!(!comparison.is_gold(pp1, Time::from_millis(20_000)));
+    /// ```
+    #[must_use]
+    pub fn is_gold(&self, split: short::Name, split_time: time::Time) -> bool {
+        self.splits
+            .get(&split)
+            .map_or(false, |x| x.is_personal_best(split_time))
+    }
+
     /// Gets the aggregate times for the split with short name `split`, if
     /// available.
     #[must_use]
@@ -80,7 +130,7 @@ impl FromIterator<(short::Name, Segment)> for Comparison {
 /// - a set of aggregates that represent the important times logged for this
 ///   split on the comparison run (right now, there is only one comparison run,
 ///   the PB).
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct Segment {
     /// The personal best for this segment.
     ///
@@ -113,7 +163,34 @@ impl Segment {
         Delta::of_comparison(against[scope], self.in_pb_run[scope])
     }
 
-    // TODO(@Ashi): use is_personal_best again
+    /// Gets how much time could be saved on this segment, relative to the comparison run, by
+    /// matching the segment's personal best.
+    ///
+    /// Returns `None` if either the comparison run or the personal best has no time for the
+    /// segment.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{aggregate, comparison::Segment, time::Time};
+    ///
+    /// let mut segment = Segment::default();
+    /// assert_eq!(None, segment.possible_timesave());
+    ///
+    /// segment.split_pb = Time::from_millis(20_000);
+    /// segment.in_pb_run = aggregate::Set {
+    ///     split: Time::from_millis(25_500),
+    ///     cumulative: Time::from_millis(25_500),
+    /// };
+    /// assert_eq!(Some(Time::from_millis(5_500)), segment.possible_timesave());
+    /// ```
+    #[must_use]
+    pub fn possible_timesave(&self) -> Option<time::Time> {
+        let unknown = time::Time::default();
+        if self.split_pb == unknown || self.in_pb_run.split == unknown {
+            None
+        } else {
+            Some(self.in_pb_run.split - self.split_pb)
+        }
+    }
 
     /// Checks whether `split time` is a new personal best.
     fn is_personal_best(&self, split_time: time::Time) -> bool {
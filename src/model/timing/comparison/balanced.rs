@@ -0,0 +1,213 @@
+/*! Balanced personal-best comparisons.
+
+A plain PB comparison expects every segment to go exactly as it did in the PB run.  The PB run
+probably had some lucky segments and some unlucky ones, though, so comparing against it
+overstates leads built on segments that were lucky in the PB, and understates them elsewhere.
+
+A balanced PB keeps the PB's total time, but spreads it over the splits according to how each
+split tends to go in the run history.  The method (after LiveSplit's) is:
+
+1. For each split, sort every segment time recorded for that split in the history.
+2. For a percentile `q` between 0 and 1, the '`q`-th percentile run' takes the `q`-th percentile
+   segment time of each split, interpolating linearly between samples.  Its total rises with `q`.
+3. Find, by bisection, the `q` at which the percentile run's total equals the PB's total.
+4. Compare against that percentile run's segment times, putting any rounding error on the last
+   split.
+
+The balanced comparison therefore has the same final cumulative time as the PB, but the
+cumulative times along the way reflect how hard each split usually is.  Split PBs ('gold splits')
+and run-level totals carry over from the PB unchanged.
+
+If any split in the PB has fewer than [MIN_SAMPLES] times in the history, or the PB's total is
+outside the range the history can produce, there's no sensible way to balance the run, and
+[BalancedProvider] falls back to the plain PB.
+*/
+
+use super::{
+    super::{super::short, aggregate, time},
+    provider, Comparison, Segment,
+};
+
+/// The fewest historic times a split can have for the run to be balanced.
+pub const MIN_SAMPLES: usize = 2;
+
+/// How many bisection steps to take when searching for the balancing percentile.
+///
+/// This is far more than enough to pin the percentile down to well under a millisecond.
+const ITERATIONS: usize = 64;
+
+/// Segment times for each split, across the run history.
+pub type History = short::Map<Vec<time::Time>>;
+
+/// Trait of comparison providers that can also supply a run history.
+pub trait Source: provider::Provider {
+    /// Gets, for each split, every segment time recorded for it in the run history.
+    ///
+    /// # Errors
+    ///
+    /// Propagates forwards any errors from the underlying history storage.
+    fn segment_history(&mut self) -> Result<History, provider::Error>;
+}
+
+/// Balances the PB comparison `pb` against `history`, with `order` as the order of splits.
+///
+/// Returns `None` if there isn't enough history to balance the PB.
+///
+/// ```
+/// use zombiesplit::model::{
+///     short,
+///     timing::{aggregate, comparison::{balanced, Comparison, Segment}, time::Time},
+/// };
+///
+/// let s = |secs| Time::from_millis(secs * 1000);
+/// let segment = |split, cumulative| Segment {
+///     split_pb: split,
+///     in_pb_run: aggregate::Set { split, cumulative },
+/// };
+/// let (a, b) = (short::Name::from("a"), short::Name::from("b"));
+///
+/// // The PB was lucky on `a` and unlucky on `b`.
+/// let mut pb = Comparison::default();
+/// pb.splits.insert(a, segment(s(10), s(10)));
+/// pb.splits.insert(b, segment(s(20), s(30)));
+///
+/// let mut history = balanced::History::default();
+/// history.insert(a, vec![s(10), s(14)]);
+/// history.insert(b, vec![s(16), s(20)]);
+///
+/// let balanced = balanced::balance(&pb, &[a, b], &history).unwrap();
+/// assert_eq!(s(12), balanced.aggregate_for(a).unwrap().cumulative);
+/// assert_eq!(s(30), balanced.aggregate_for(b).unwrap().cumulative);
+///
+/// // Not enough history.
+/// history.get_mut(&b).unwrap().pop();
+/// assert!(balanced::balance(&pb, &[a, b], &history).is_none());
+/// ```
+#[must_use]
+pub fn balance(pb: &Comparison, order: &[short::Name], history: &History) -> Option<Comparison> {
+    let mut splits = Vec::new();
+    for short in order {
+        if let Some(segment) = pb.splits.get(short) {
+            let mut times: Vec<i32> = history
+                .get(short)?
+                .iter()
+                .map(|t| t.into_millis())
+                .collect();
+            if times.len() < MIN_SAMPLES {
+                return None;
+            }
+            times.sort_unstable();
+            splits.push((*short, segment, times));
+        }
+    }
+    let last = splits.len().checked_sub(1)?;
+
+    let target: i32 = splits
+        .iter()
+        .map(|(_, s, _)| s.in_pb_run.split.into_millis())
+        .sum();
+    let total_at = |q| -> f64 { splits.iter().map(|(_, _, t)| percentile(t, q)).sum() };
+    let target_f = f64::from(target);
+    if target_f < total_at(0.0) || total_at(1.0) < target_f {
+        return None;
+    }
+
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..ITERATIONS {
+        let mid = (lo + hi) / 2.0;
+        if total_at(mid) < target_f {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let q = (lo + hi) / 2.0;
+
+    let mut balanced: Vec<i32> = splits
+        .iter()
+        .map(|(_, _, t)| round(percentile(t, q)))
+        .collect();
+    balanced[last] += target - balanced.iter().sum::<i32>();
+
+    let mut result = Comparison {
+        run: pb.run,
+        ..Comparison::default()
+    };
+    let mut cumulative = time::Time::default();
+    for ((short, segment, _), split) in splits.into_iter().zip(balanced) {
+        let split = time::Time::from_millis(split);
+        cumulative += split;
+        result.splits.insert(
+            short,
+            Segment {
+                split_pb: segment.split_pb,
+                in_pb_run: aggregate::Set { split, cumulative },
+            },
+        );
+    }
+    Some(result)
+}
+
+/// Gets the `q`-th percentile of the non-empty, sorted `samples`, interpolating linearly.
+// Sample counts and millisecond times are far too small for these casts to lose anything.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn percentile(samples: &[i32], q: f64) -> f64 {
+    let pos = q * (samples.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    let frac = pos - pos.floor();
+    f64::from(samples[lower]) * (1.0 - frac) + f64::from(samples[upper]) * frac
+}
+
+/// Rounds a millisecond count to the nearest millisecond.
+// Percentiles lie between millisecond times, so they always fit back into one.
+#[allow(clippy::cast_possible_truncation)]
+fn round(ms: f64) -> i32 {
+    ms.round() as i32
+}
+
+/// A comparison provider that balances the PB from another provider against its run history.
+///
+/// See the module documentation for the method.
+pub struct BalancedProvider<S> {
+    /// The underlying provider of PBs and history.
+    source: S,
+    /// The order of splits in the run.
+    order: Vec<short::Name>,
+}
+
+impl<S: Source> BalancedProvider<S> {
+    /// Constructs a balanced provider over `source`, with `order` as the order of splits in the
+    /// run.
+    #[must_use]
+    pub fn new(source: S, order: impl IntoIterator<Item = short::Name>) -> Self {
+        Self {
+            source,
+            order: order.into_iter().collect(),
+        }
+    }
+}
+
+impl<S: Source> provider::Provider for BalancedProvider<S> {
+    fn comparison(&mut self) -> provider::Result {
+        let pb = match self.source.comparison()? {
+            Some(pb) => pb,
+            None => return Ok(None),
+        };
+        let history = self.source.segment_history()?;
+        Ok(Some(balance(&pb, &self.order, &history).unwrap_or(pb)))
+    }
+
+    /// The balanced PB changes exactly when the underlying PB or history does.
+    fn revision(&mut self) -> Result<Option<u64>, provider::Error> {
+        self.source.revision()
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Balanced PB".to_owned())
+    }
+}
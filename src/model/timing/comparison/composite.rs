@@ -0,0 +1,194 @@
+/*! Composite comparisons.
+
+A composite comparison draws on several other comparisons at once: for instance, racing the PB
+for the splits it has, and falling back to split PBs for the rest.  The sources are ranked, and
+each split takes its segment from the highest-ranked source that has a time for it; the split
+PB comes along with the segment, from the same source.  A zero split time counts as no time (as
+when the PB run skipped the split), so the split falls through to the next source.
+
+Cumulative times can't be taken from the sources, since each source's cumulative times only
+make sense alongside its own earlier splits.  Instead, they're recomputed by summing the merged
+split times in split order, as are the comparison's run totals.
+*/
+
+use std::hash::{Hash, Hasher};
+
+use super::{
+    super::{super::short, aggregate, time},
+    provider, Comparison, Segment,
+};
+
+/// Merges `sources`, highest-ranked first, into one comparison, with `order` as the order of
+/// splits.
+///
+/// Splits that no source has a time for are left out.  See the module documentation for the
+/// precedence rules.
+///
+/// ```
+/// use zombiesplit::model::{
+///     short,
+///     timing::{aggregate, comparison::{composite, Comparison, Segment}, time::Time},
+/// };
+///
+/// let s = |secs| Time::from_millis(secs * 1000);
+/// let segment = |split, cumulative| Segment {
+///     split_pb: split,
+///     in_pb_run: aggregate::Set { split, cumulative },
+/// };
+/// let (a, b) = (short::Name::from("a"), short::Name::from("b"));
+///
+/// let mut pb = Comparison::default();
+/// pb.splits.insert(b, segment(s(20), s(30)));
+/// let mut best = Comparison::default();
+/// best.splits.insert(a, segment(s(8), s(8)));
+/// best.splits.insert(b, segment(s(15), s(23)));
+///
+/// let merged = composite::merge(&[pb, best], &[a, b]);
+/// // `a` comes from the second source, and `b` from the first...
+/// assert_eq!(s(8), merged.aggregate_for(a).unwrap().split);
+/// assert_eq!(s(20), merged.aggregate_for(b).unwrap().split);
+/// // ...but `b`'s cumulative time is recomputed from the merged splits.
+/// assert_eq!(s(28), merged.aggregate_for(b).unwrap().cumulative);
+/// assert_eq!(Some(s(28)), merged.run.total_in_pb_run);
+/// ```
+#[must_use]
+pub fn merge(sources: &[Comparison], order: &[short::Name]) -> Comparison {
+    let mut result = Comparison::default();
+    let mut cumulative = time::Time::default();
+    let mut sum_of_best = time::Time::default();
+
+    for short in order {
+        let segment = sources
+            .iter()
+            .filter_map(|c| c.splits.get(short))
+            .find(|s| s.in_pb_run.split != time::Time::default());
+        if let Some(segment) = segment {
+            let split = segment.in_pb_run.split;
+            cumulative += split;
+            sum_of_best += segment.split_pb;
+            result.splits.insert(
+                *short,
+                Segment {
+                    split_pb: segment.split_pb,
+                    in_pb_run: aggregate::Set { split, cumulative },
+                },
+            );
+        }
+    }
+
+    if !result.splits.is_empty() {
+        result.run.total_in_pb_run = Some(cumulative);
+        result.run.sum_of_best = Some(sum_of_best);
+    }
+    result
+}
+
+/// A comparison provider that merges the comparisons of several other providers.
+///
+/// See the module documentation for how the comparisons are merged.
+pub struct CompositeProvider<'p> {
+    /// The underlying providers, highest-ranked first.
+    sources: Vec<Box<dyn provider::Provider + 'p>>,
+    /// The order of splits in the run.
+    order: Vec<short::Name>,
+}
+
+impl<'p> CompositeProvider<'p> {
+    /// Constructs a composite provider over `sources`, highest-ranked first, with `order` as the
+    /// order of splits in the run.
+    #[must_use]
+    pub fn new(
+        sources: Vec<Box<dyn provider::Provider + 'p>>,
+        order: impl IntoIterator<Item = short::Name>,
+    ) -> Self {
+        Self {
+            sources,
+            order: order.into_iter().collect(),
+        }
+    }
+}
+
+impl<'p> provider::Provider for CompositeProvider<'p> {
+    /// Merges the sources' comparisons, ignoring sources that have none.
+    ///
+    /// Returns `None` if no source has a comparison.
+    fn comparison(&mut self) -> provider::Result {
+        let mut comparisons = Vec::with_capacity(self.sources.len());
+        for source in &mut self.sources {
+            if let Some(comparison) = source.comparison()? {
+                comparisons.push(comparison);
+            }
+        }
+        Ok(if comparisons.is_empty() {
+            None
+        } else {
+            Some(merge(&comparisons, &self.order))
+        })
+    }
+
+    /// The composite changes whenever any of its sources do, so it only has a revision if every
+    /// source has one.
+    fn revision(&mut self) -> Result<Option<u64>, provider::Error> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for source in &mut self.sources {
+            match source.revision()? {
+                Some(revision) => revision.hash(&mut hasher),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(hasher.finish()))
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Composite".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segment(split: i32) -> Segment {
+        let split = time::Time::from_millis(split);
+        Segment {
+            split_pb: split,
+            in_pb_run: aggregate::Set {
+                split,
+                cumulative: split,
+            },
+        }
+    }
+
+    /// Tests that a split with a zero time in a higher-ranked source falls through.
+    #[test]
+    fn zero_split_falls_through() {
+        let (a, b) = (short::Name::from("a"), short::Name::from("b"));
+        let mut pb = Comparison::default();
+        pb.splits.insert(a, segment(0));
+        pb.splits.insert(b, segment(2000));
+        let mut best = Comparison::default();
+        best.splits.insert(a, segment(1000));
+
+        let merged = merge(&[pb, best], &[a, b]);
+        assert_eq!(
+            Some(time::Time::from_millis(1000)),
+            merged.aggregate_for(a).map(|x| x.split)
+        );
+        assert_eq!(
+            Some(time::Time::from_millis(3000)),
+            merged.aggregate_for(b).map(|x| x.cumulative)
+        );
+    }
+
+    /// Tests that a split with a zero time in every source is left out.
+    #[test]
+    fn zero_split_everywhere_left_out() {
+        let a = short::Name::from("a");
+        let mut pb = Comparison::default();
+        pb.splits.insert(a, segment(0));
+
+        let merged = merge(&[pb], &[a]);
+        assert!(merged.splits.is_empty());
+        assert_eq!(None, merged.run.total_in_pb_run);
+    }
+}
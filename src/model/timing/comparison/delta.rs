@@ -8,7 +8,9 @@ use super::{super::time, pace};
 ///
 /// Usually, these will represent run-wide cumulative deltas, but they are also used to construct
 /// [Split]s.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+///
+/// Deltas order from most ahead to most behind.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Delta(time::Time);
 
 impl Delta {
@@ -23,11 +25,68 @@ impl Delta {
         }
     }
 
+    /// Gets the pace of this delta, counting it as even if it is no further than `tolerance` from
+    /// zero in either direction.
+    ///
+    /// A tolerance of zero (or less) gives the same pace as [Delta::pace].
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{comparison::{delta::Delta, Pace}, time::Time};
+    ///
+    /// let t = |ms| Time::from_millis(ms);
+    /// let tolerance = t(250);
+    ///
+    /// assert_eq!(Pace::Even, Delta::of_comparison(t(1200), t(1000)).pace_within(tolerance));
+    /// assert_eq!(Pace::Even, Delta::of_comparison(t(1000), t(1250)).pace_within(tolerance));
+    /// assert_eq!(Pace::Behind, Delta::of_comparison(t(1300), t(1000)).pace_within(tolerance));
+    /// assert_eq!(Pace::Ahead, Delta::of_comparison(t(1000), t(1000)).pace_within(Time::default()));
+    /// ```
+    #[must_use]
+    pub fn pace_within(&self, tolerance: time::Time) -> pace::Pace {
+        let tolerance = i64::from(tolerance.into_millis());
+        if 0 < tolerance && i64::from(self.0.into_millis()).abs() <= tolerance {
+            pace::Pace::Even
+        } else {
+            self.pace()
+        }
+    }
+
     /// Calculates the delta by comparing `time` to `compared_to`, if it exists.
     #[must_use]
     pub fn of_comparison(time: time::Time, compared_to: time::Time) -> Self {
         Self(time - compared_to)
     }
+
+    /// Gets the time difference underlying this delta.
+    ///
+    /// This is positive when behind the comparison, and negative when ahead of it.
+    #[must_use]
+    pub fn into_time(self) -> time::Time {
+        self.0
+    }
+
+    /// Gets the trend of this delta relative to the `previous` delta, if there is one.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{comparison::{delta::Delta, pace::Trend}, time::Time};
+    ///
+    /// let t = |ms| Time::from_millis(ms);
+    /// let behind = Delta::of_comparison(t(200), t(100));
+    /// let further_behind = Delta::of_comparison(t(300), t(100));
+    ///
+    /// assert_eq!(Trend::Neutral, behind.trend_since(None));
+    /// assert_eq!(Trend::Neutral, behind.trend_since(Some(behind)));
+    /// assert_eq!(Trend::Losing, further_behind.trend_since(Some(behind)));
+    /// assert_eq!(Trend::Gaining, behind.trend_since(Some(further_behind)));
+    /// ```
+    #[must_use]
+    pub fn trend_since(&self, previous: Option<Delta>) -> pace::Trend {
+        match previous.map(|p| self.0.cmp(&p.0)) {
+            None | Some(std::cmp::Ordering::Equal) => pace::Trend::Neutral,
+            Some(std::cmp::Ordering::Less) => pace::Trend::Gaining,
+            Some(std::cmp::Ordering::Greater) => pace::Trend::Losing,
+        }
+    }
 }
 
 /// A time difference at the split level.
@@ -52,6 +111,15 @@ impl Split {
     pub fn pace(&self) -> pace::SplitInRun {
         pace::SplitInRun::new(self.split.pace(), self.run.pace())
     }
+
+    /// Gets the split-in-run pace from this delta, counting the split as even if its delta is no
+    /// further than `tolerance` from zero.
+    ///
+    /// Only the split delta is subject to the tolerance; the run delta is paced as usual.
+    #[must_use]
+    pub fn pace_within(&self, tolerance: time::Time) -> pace::SplitInRun {
+        pace::SplitInRun::new(self.split.pace_within(tolerance), self.run.pace())
+    }
 }
 
 /// A pair of a time and its delta against comparison.
@@ -0,0 +1,108 @@
+/*! Comparisons loaded from files.
+
+A comparison file holds a serialised [Comparison], so that a runner can share a target (for
+instance, with someone they're coaching) without sharing their database.  It is a TOML file that
+looks like this:
+
+```toml
+[run]
+total_in_pb_run = "50s500"
+sum_of_best = "49s"
+
+[splits.pp1]
+split_pb = "24s"
+in_pb_run = { split = "25s", cumulative = "25s" }
+
+[splits.pp2]
+split_pb = "25s"
+in_pb_run = { split = "25s500", cumulative = "50s500" }
+```
+*/
+
+use std::path::Path;
+
+use super::{
+    super::super::{load::Loadable, short},
+    provider, Comparison,
+};
+
+/// A comparison provider that compares against a comparison loaded from a file.
+pub struct FileProvider {
+    /// The loaded comparison.
+    comparison: Comparison,
+}
+
+impl FileProvider {
+    /// Constructs a file provider that compares against `comparison`.
+    #[must_use]
+    pub fn new(comparison: Comparison) -> Self {
+        Self { comparison }
+    }
+
+    /// Loads a file provider from the comparison file at `path`.
+    ///
+    /// If the file doesn't name the comparison, it takes the name of the file (less its
+    /// extension).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> crate::model::load::Result<Self> {
+        let path = path.as_ref();
+        let mut comparison = Comparison::from_toml_file(path)?;
+        if comparison.name.is_none() {
+            comparison.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned());
+        }
+        Ok(Self::new(comparison))
+    }
+
+    /// Gets the splits in the comparison that aren't in `order`, the splits of the category
+    /// being run.
+    ///
+    /// ```
+    /// use zombiesplit::model::{short, timing::comparison::{Comparison, FileProvider}};
+    ///
+    /// let mut comparison = Comparison::default();
+    /// comparison.splits.insert(short::Name::from("s1"), Default::default());
+    /// comparison.splits.insert(short::Name::from("s9"), Default::default());
+    ///
+    /// let provider = FileProvider::new(comparison);
+    /// let order = ["s1", "s2"].map(short::Name::from);
+    /// assert_eq!(vec![short::Name::from("s9")], provider.unknown_splits(&order));
+    /// ```
+    #[must_use]
+    pub fn unknown_splits(&self, order: &[short::Name]) -> Vec<short::Name> {
+        let mut unknown: Vec<short::Name> = self
+            .comparison
+            .splits
+            .keys()
+            .filter(|s| !order.contains(s))
+            .copied()
+            .collect();
+        unknown.sort_by_key(ToString::to_string);
+        unknown
+    }
+
+    /// Gets whether the comparison shares any splits with `order`.
+    #[must_use]
+    pub fn overlaps(&self, order: &[short::Name]) -> bool {
+        order.iter().any(|s| self.comparison.splits.contains_key(s))
+    }
+}
+
+impl provider::Provider for FileProvider {
+    fn comparison(&mut self) -> provider::Result {
+        Ok(Some(self.comparison.clone()))
+    }
+
+    /// Comparison files are loaded once, so never change.
+    fn revision(&mut self) -> std::result::Result<Option<u64>, provider::Error> {
+        Ok(Some(0))
+    }
+
+    fn name(&self) -> Option<String> {
+        self.comparison.name.clone()
+    }
+}
@@ -0,0 +1,108 @@
+/*! Goal comparisons.
+
+A goal is a hand-authored set of target split times (for instance, for a sub-X attempt), usually
+loaded from a TOML file that looks like this:
+
+```toml
+[splits]
+pp1 = "25s"
+pp2 = "25s500"
+```
+
+Goals feed into the same pace machinery as comparisons from run history.
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    super::{super::short, aggregate, time},
+    provider, Comparison, Segment,
+};
+
+/// A set of goal split times.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    /// The goal time for each split.
+    ///
+    /// Splits without a goal have an inconclusive pace.
+    #[serde(default)]
+    pub splits: short::Map<time::Time>,
+}
+
+impl Goal {
+    /// Converts this goal into a comparison, using `order` as the order of splits in the run.
+    ///
+    /// Cumulative goals are the running sum of all goals up to and including each split.  Splits
+    /// without a goal don't contribute to the running sum.
+    ///
+    /// ```
+    /// use zombiesplit::model::{short, timing::{comparison::goal::Goal, time::Time}};
+    ///
+    /// let mut goal = Goal::default();
+    /// goal.splits.insert(short::Name::from("s1"), Time::from_millis(1000));
+    /// goal.splits.insert(short::Name::from("s3"), Time::from_millis(3000));
+    ///
+    /// let order = ["s1", "s2", "s3"].map(short::Name::from);
+    /// let comparison = goal.comparison(order);
+    ///
+    /// assert!(comparison.aggregate_for(short::Name::from("s2")).is_none());
+    /// let s3 = comparison.aggregate_for(short::Name::from("s3")).unwrap();
+    /// assert_eq!(Time::from_millis(4000), s3.cumulative);
+    /// assert_eq!(Some(Time::from_millis(4000)), comparison.run.total_in_pb_run);
+    /// ```
+    #[must_use]
+    pub fn comparison(&self, order: impl IntoIterator<Item = short::Name>) -> Comparison {
+        let mut result = Comparison::default();
+        let mut cumulative = time::Time::default();
+
+        for short in order {
+            if let Some(split) = self.splits.get(&short) {
+                cumulative += *split;
+                result.splits.insert(
+                    short,
+                    Segment {
+                        split_pb: *split,
+                        in_pb_run: aggregate::Set {
+                            split: *split,
+                            cumulative,
+                        },
+                    },
+                );
+            }
+        }
+
+        result.run.total_in_pb_run = Some(cumulative);
+        result
+    }
+}
+
+/// A comparison provider that compares against a [Goal].
+pub struct GoalProvider {
+    /// The precomputed goal comparison.
+    comparison: Comparison,
+}
+
+impl GoalProvider {
+    /// Constructs a goal provider for `goal`, with `order` as the order of splits in the run.
+    #[must_use]
+    pub fn new(goal: &Goal, order: impl IntoIterator<Item = short::Name>) -> Self {
+        Self {
+            comparison: goal.comparison(order),
+        }
+    }
+}
+
+impl provider::Provider for GoalProvider {
+    fn comparison(&mut self) -> provider::Result {
+        Ok(Some(self.comparison.clone()))
+    }
+
+    /// Goals never change once loaded.
+    fn revision(&mut self) -> std::result::Result<Option<u64>, provider::Error> {
+        Ok(Some(0))
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Goal".to_owned())
+    }
+}
@@ -0,0 +1,184 @@
+/*! Comparisons against the most recent run.
+
+Racing the run you just did, rather than your PB, shows whether you're improving from attempt to
+attempt.  A latest-run comparison takes its segment and total times from the most recently dated
+run in the history, timed or not, and carries the split PBs ('gold splits') and sum of best over
+from the PB comparison.
+
+Only splits that the latest run timed get a comparison; cumulative times count just those splits.
+If there is no run in the history yet, there is nothing to compare against, and
+[LatestRunProvider] gives no comparison.
+*/
+
+use super::{
+    super::{
+        super::{history, short},
+        aggregate,
+    },
+    provider, Comparison, Run, Segment,
+};
+
+/// Trait of comparison providers that can also supply the most recent run.
+pub trait Source: provider::Provider {
+    /// Gets the split totals of the most recently dated run in the history, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Propagates forwards any errors from the underlying history storage.
+    fn latest_run(&mut self) -> Result<Option<history::timing::Totals>, provider::Error>;
+}
+
+/// Makes a comparison against the run with split totals `latest`, taking split PBs and the sum of
+/// best from `pb`, with `order` as the order of splits.
+///
+/// ```
+/// use zombiesplit::model::{
+///     history::timing::Totals,
+///     short,
+///     timing::{comparison::{latest, Comparison}, time::Time},
+/// };
+///
+/// let (a, b, c) = (short::Name::from("a"), short::Name::from("b"), short::Name::from("c"));
+/// let s = |secs| Time::from_millis(secs * 1000);
+///
+/// // The latest run didn't time `b`.
+/// let latest = Totals { totals: [(a, s(10)), (c, s(30))].into_iter().collect() };
+/// let cmp = latest::against(&Comparison::default(), &latest, &[a, b, c]);
+///
+/// assert!(cmp.splits.get(&b).is_none());
+/// assert_eq!(s(40), cmp.splits[&c].in_pb_run.cumulative);
+/// assert_eq!(Some(s(40)), cmp.run.total_in_pb_run);
+/// ```
+#[must_use]
+pub fn against(
+    pb: &Comparison,
+    latest: &history::timing::Totals,
+    order: &[short::Name],
+) -> Comparison {
+    let timed = order
+        .iter()
+        .filter_map(|short| latest.totals.get(short).map(|time| (*short, *time)));
+    let splits = aggregate::Set::accumulate_pairs(timed)
+        .map(|(short, in_run)| {
+            // If the PB doesn't have this split, the latest run's time is the best we know of.
+            let split_pb = pb.splits.get(&short).map_or(in_run.split, |s| s.split_pb);
+            (
+                short,
+                Segment {
+                    split_pb,
+                    in_pb_run: in_run,
+                },
+            )
+        })
+        .collect();
+
+    Comparison {
+        name: None,
+        splits,
+        run: Run {
+            total_in_pb_run: Some(history::timing::Timing::total(latest)),
+            sum_of_best: pb.run.sum_of_best,
+        },
+    }
+}
+
+/// A comparison provider that compares against the most recent run from another provider.
+///
+/// See the module documentation for details.
+pub struct LatestRunProvider<S> {
+    /// The underlying provider of PBs and the latest run.
+    source: S,
+    /// The order of splits in the run.
+    order: Vec<short::Name>,
+}
+
+impl<S: Source> LatestRunProvider<S> {
+    /// Constructs a latest-run provider over `source`, with `order` as the order of splits in the
+    /// run.
+    #[must_use]
+    pub fn new(source: S, order: impl IntoIterator<Item = short::Name>) -> Self {
+        Self {
+            source,
+            order: order.into_iter().collect(),
+        }
+    }
+}
+
+impl<S: Source> provider::Provider for LatestRunProvider<S> {
+    fn comparison(&mut self) -> provider::Result {
+        let latest = match self.source.latest_run()? {
+            Some(latest) => latest,
+            None => return Ok(None),
+        };
+        let pb = self.source.comparison()?.unwrap_or_default();
+        Ok(Some(against(&pb, &latest, &self.order)))
+    }
+
+    /// The latest run changes exactly when the run history does, as on a reset that saves the
+    /// outgoing run.
+    fn revision(&mut self) -> Result<Option<u64>, provider::Error> {
+        self.source.revision()
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Latest Run".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::timing::time::Time;
+
+    /// A source with a fixed PB and, optionally, a latest run.
+    struct Fixed {
+        pb: Comparison,
+        latest: Option<history::timing::Totals>,
+    }
+
+    impl provider::Provider for Fixed {
+        fn comparison(&mut self) -> provider::Result {
+            Ok(Some(self.pb.clone()))
+        }
+    }
+
+    impl Source for Fixed {
+        fn latest_run(&mut self) -> Result<Option<history::timing::Totals>, provider::Error> {
+            Ok(self.latest.clone())
+        }
+    }
+
+    /// Without any runs, there is nothing to compare against.
+    #[test]
+    fn no_latest_run_is_inconclusive() {
+        let source = Fixed {
+            pb: Comparison::default(),
+            latest: None,
+        };
+        let mut provider = LatestRunProvider::new(source, [short::Name::from("a")]);
+        assert!(provider::Provider::comparison(&mut provider)
+            .expect("comparison shouldn't fail")
+            .is_none());
+    }
+
+    /// Split PBs come from the PB comparison, while segment times come from the latest run.
+    #[test]
+    fn split_pbs_come_from_pb() {
+        let a = short::Name::from("a");
+        let mut pb = Comparison::default();
+        pb.splits.insert(
+            a,
+            Segment {
+                split_pb: Time::from_millis(900),
+                in_pb_run: aggregate::Set::default(),
+            },
+        );
+        let latest = history::timing::Totals {
+            totals: [(a, Time::from_millis(1000))].into_iter().collect(),
+        };
+
+        let cmp = against(&pb, &latest, &[a]);
+        assert_eq!(Time::from_millis(900), cmp.splits[&a].split_pb);
+        assert_eq!(Time::from_millis(1000), cmp.splits[&a].in_pb_run.split);
+    }
+}
@@ -1,5 +1,7 @@
 //! Structs and functions for pace computation.
 
+pub mod bucket;
+
 use crate::model::timing::time;
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +12,12 @@ pub enum Pace {
     Inconclusive,
     /// Time is behind its comparison.
     Behind,
+    /// Time is within a tolerance band of its comparison, so is neither meaningfully ahead nor
+    /// behind ('close call').
+    ///
+    /// Paces are only ever even when computed with a tolerance (see
+    /// [super::delta::Delta::pace_within]).
+    Even,
     /// Time is ahead (or breaking even on) its comparison.
     Ahead,
     /// Time is a personal best ('gold split').
@@ -86,6 +94,27 @@ impl PacedTime {
     }
 }
 
+/// The direction in which a run's delta moved over a split.
+///
+/// This is the 'delta of deltas': whether the gap between the run and its comparison grew or
+/// shrank since the previous split.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trend {
+    /// There is no previous delta to compare against, or the delta didn't move.
+    Neutral,
+    /// The delta went down over the split, so the run gained time.
+    Gaining,
+    /// The delta went up over the split, so the run lost time.
+    Losing,
+}
+
+/// The default trend is neutral.
+impl Default for Trend {
+    fn default() -> Self {
+        Self::Neutral
+    }
+}
+
 /// Combined pace note for a split in the context of a run in progress.
 ///
 /// These note the pace of a run, as well as how the current split has affected
@@ -104,6 +133,10 @@ pub enum SplitInRun {
     AheadAndLosing,
     /// The run is ahead, and we gained time (or broke even) on the given split.
     AheadAndGaining,
+    /// The split was within the tolerance band of its comparison ('close call').
+    ///
+    /// Like a personal best, this describes the split rather than the run.
+    Even,
 }
 
 /// The default split-in-run pace is inconclusive.
@@ -134,16 +167,22 @@ impl SplitInRun {
     /// assert_eq!(
     ///     pace::SplitInRun::AheadAndLosing,
     ///     pace::SplitInRun::new(pace::Pace::Behind, pace::Pace::Ahead));
+    /// assert_eq!(
+    ///     pace::SplitInRun::Even,
+    ///     pace::SplitInRun::new(pace::Pace::Even, pace::Pace::Behind));
     /// ```
+    ///
+    /// An even run so far counts as ahead, as it would without a tolerance.
     #[must_use]
     pub fn new(split: Pace, run_so_far: Pace) -> Self {
         match (split, run_so_far) {
             (Pace::Inconclusive, _) | (_, Pace::Inconclusive) => Self::Inconclusive,
             (Pace::PersonalBest, _) => Self::SplitPersonalBest,
+            (Pace::Even, _) => Self::Even,
             (Pace::Behind, Pace::Behind) => Self::BehindAndLosing,
             (Pace::Ahead, Pace::Behind) => Self::BehindAndGaining,
-            (Pace::Behind, Pace::Ahead | Pace::PersonalBest) => Self::AheadAndLosing,
-            (Pace::Ahead, Pace::Ahead | Pace::PersonalBest) => Self::AheadAndGaining,
+            (Pace::Behind, Pace::Even | Pace::Ahead | Pace::PersonalBest) => Self::AheadAndLosing,
+            (Pace::Ahead, Pace::Even | Pace::Ahead | Pace::PersonalBest) => Self::AheadAndGaining,
         }
     }
 
@@ -159,6 +198,7 @@ impl SplitInRun {
     pub fn overall(self) -> Pace {
         match self {
             SplitInRun::Inconclusive => Pace::Inconclusive,
+            SplitInRun::Even => Pace::Even,
             SplitInRun::SplitPersonalBest | SplitInRun::AheadAndGaining | Self::AheadAndLosing => {
                 Pace::Ahead
             }
@@ -0,0 +1,320 @@
+/*! Configurable pace buckets.
+
+A [SplitInRun] pace sorts each split into one of a fixed set of categories.  Pace buckets
+generalise this: each [Bucket] names a category, says which split and run deltas fall into it, and
+says how clients should show splits in it.  A split goes into the first bucket that matches it, so
+later buckets can act as catch-alls for earlier ones.
+
+The default [Buckets] give the same categories as [SplitInRun], other than close calls (which
+depend on per-split tolerances; a bucket with a narrow split range around zero does much the same
+job).  Buckets must between them cover every possible delta; see [Buckets::validate].
+
+[SplitInRun]: super::SplitInRun
+*/
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::super::{
+    super::time::{millis, Time},
+    delta,
+};
+
+/// A user-defined category of split pace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bucket {
+    /// The name of the bucket, which observers see whenever a split falls into it.
+    pub name: String,
+    /// The colour in which clients should show splits in this bucket, such as `#ffd700`.
+    pub colour: String,
+    /// A symbol clients can show next to splits in this bucket, if any.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// If set, the bucket only matches splits that are (or aren't) gold.
+    ///
+    /// A gold split beats the split PB in the comparison.
+    #[serde(default)]
+    pub gold: Option<bool>,
+    /// The range of split deltas this bucket matches.
+    #[serde(default)]
+    pub split: Range,
+    /// The range of run deltas this bucket matches.
+    #[serde(default)]
+    pub run: Range,
+}
+
+impl Bucket {
+    /// Gets whether this bucket matches a split with the given `delta` and gold status.
+    #[must_use]
+    pub fn matches(&self, delta: delta::Split, gold: bool) -> bool {
+        self.gold.map_or(true, |g| g == gold)
+            && self.split.contains(delta.split)
+            && self.run.contains(delta.run)
+    }
+}
+
+/// A half-open range of deltas, in milliseconds.
+///
+/// Deltas are positive when behind and negative when ahead.  A missing bound leaves that end of
+/// the range open, so the default range contains every delta.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Range {
+    /// The lowest delta in the range, if any.
+    #[serde(with = "millis::option")]
+    pub from: Option<Time>,
+    /// The lowest delta above the range, if any.
+    #[serde(with = "millis::option")]
+    pub to: Option<Time>,
+}
+
+impl Range {
+    /// Gets whether `delta` is in this range.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{comparison::{delta::Delta, pace::bucket::Range}, time::Time};
+    ///
+    /// let t = |ms| Time::from_millis(ms);
+    /// let behind = Range { from: Some(t(1)), to: None };
+    ///
+    /// assert!(behind.contains(Delta::of_comparison(t(1001), t(1000))));
+    /// assert!(!behind.contains(Delta::of_comparison(t(1000), t(1000))));
+    /// ```
+    #[must_use]
+    pub fn contains(&self, delta: delta::Delta) -> bool {
+        let delta = delta.into_time();
+        self.from.map_or(true, |from| from <= delta) && self.to.map_or(true, |to| delta < to)
+    }
+
+    /// Adds this range's bounds to `bounds`.
+    fn push_bounds(&self, bounds: &mut Vec<i32>) {
+        bounds.extend(
+            self.from
+                .iter()
+                .chain(self.to.iter())
+                .map(|t| t.into_millis()),
+        );
+    }
+}
+
+/// An ordered list of pace buckets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Buckets(pub Vec<Bucket>);
+
+/// The default buckets mirror the fixed split-in-run paces.
+impl Default for Buckets {
+    fn default() -> Self {
+        let behind = Range {
+            from: Some(Time::from_millis(1)),
+            to: None,
+        };
+        let ahead = Range {
+            from: None,
+            to: Some(Time::from_millis(1)),
+        };
+        let bucket = |name: &str, colour: &str, split, run| Bucket {
+            name: name.to_owned(),
+            colour: colour.to_owned(),
+            symbol: None,
+            gold: None,
+            split,
+            run,
+        };
+        Self(vec![
+            Bucket {
+                symbol: Some("*".to_owned()),
+                gold: Some(true),
+                ..bucket(
+                    "personal-best",
+                    "#ffd700",
+                    Range::default(),
+                    Range::default(),
+                )
+            },
+            bucket("behind-and-losing", "#ff0000", behind, behind),
+            bucket("behind-and-gaining", "#ff8080", ahead, behind),
+            bucket("ahead-and-losing", "#80ff80", behind, ahead),
+            bucket("ahead-and-gaining", "#00ff00", ahead, ahead),
+        ])
+    }
+}
+
+impl Buckets {
+    /// Finds the first bucket matching a split with the given `delta` and gold status.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::{comparison::{delta, pace::bucket::Buckets}, time::Time};
+    ///
+    /// let t = |ms| Time::from_millis(ms);
+    /// let split = delta::Delta::of_comparison(t(900), t(1000));
+    /// let run = delta::Delta::of_comparison(t(5000), t(4000));
+    ///
+    /// let buckets = Buckets::default();
+    /// let bucket = buckets.classify(delta::Split::new(split, run), false).unwrap();
+    /// assert_eq!("behind-and-gaining", bucket.name);
+    /// ```
+    #[must_use]
+    pub fn classify(&self, delta: delta::Split, gold: bool) -> Option<&Bucket> {
+        self.position(delta, gold).map(|i| &self.0[i])
+    }
+
+    /// Finds the position of the first bucket matching a split with the given `delta` and gold
+    /// status.
+    #[must_use]
+    pub fn position(&self, delta: delta::Split, gold: bool) -> Option<usize> {
+        self.0.iter().position(|b| b.matches(delta, gold))
+    }
+
+    /// Checks that the buckets have distinct names, and that every split falls into one of them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first duplicate name found, or with an example of a split that no bucket
+    /// matches.
+    pub fn validate(&self) -> Result<()> {
+        let mut names = std::collections::HashSet::new();
+        if let Some(b) = self.0.iter().find(|b| !names.insert(&b.name)) {
+            return Err(Error::DuplicateName(b.name.clone()));
+        }
+
+        // Every delta between two adjacent bounds (or beyond the outermost bounds) matches the
+        // same buckets, so it is enough to check one delta from each such interval.
+        let mut split_bounds = Vec::new();
+        let mut run_bounds = Vec::new();
+        for b in &self.0 {
+            b.split.push_bounds(&mut split_bounds);
+            b.run.push_bounds(&mut run_bounds);
+        }
+        let split_samples = samples(split_bounds);
+        let run_samples = samples(run_bounds);
+
+        for gold in [false, true] {
+            for split in &split_samples {
+                for run in &run_samples {
+                    let delta = delta::Split::new(delta_of(*split), delta_of(*run));
+                    if self.position(delta, gold).is_none() {
+                        return Err(Error::Gap {
+                            gold,
+                            split: split.into_millis(),
+                            run: run.into_millis(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gets one delta from each interval the given bounds divide the delta line into.
+fn samples(mut bounds: Vec<i32>) -> Vec<Time> {
+    bounds.sort_unstable();
+    bounds.dedup();
+    // Each bound stands for the interval starting at it; the interval below the lowest bound
+    // needs its own sample, unless there is no such interval.
+    let below = bounds
+        .first()
+        .map_or(Some(0), |lowest| lowest.checked_sub(1));
+    below
+        .into_iter()
+        .chain(bounds)
+        .map(Time::from_millis)
+        .collect()
+}
+
+fn delta_of(time: Time) -> delta::Delta {
+    delta::Delta::of_comparison(time, Time::default())
+}
+
+/// Errors arising from pace bucket validation.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Two buckets have the same name.
+    #[error("more than one pace bucket is named {0}")]
+    DuplicateName(String),
+    /// No bucket matches a split with the given gold status and deltas.
+    #[error(
+        "no pace bucket matches a split delta of {split:+}ms and run delta of {run:+}ms (gold: {gold})"
+    )]
+    Gap {
+        /// Whether the unmatched split is gold.
+        gold: bool,
+        /// The unmatched split delta, in milliseconds.
+        split: i32,
+        /// The unmatched run delta, in milliseconds.
+        run: i32,
+    },
+}
+
+/// Shorthand for results over pace bucket [Error]s.
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_is_valid() {
+        Buckets::default()
+            .validate()
+            .expect("default buckets should be valid");
+    }
+
+    /// Tests that the default buckets agree with the fixed split-in-run paces.
+    #[test]
+    fn default_matches_split_in_run() {
+        let buckets = Buckets::default();
+        for split in [-1000, -1, 0, 1, 1000] {
+            for run in [-1000, -1, 0, 1, 1000] {
+                let delta = delta::Split::new(
+                    delta_of(Time::from_millis(split)),
+                    delta_of(Time::from_millis(run)),
+                );
+                let bucket = buckets.classify(delta, false).expect("no bucket");
+                let expected = format!("{:?}", delta.pace());
+                assert_eq!(
+                    expected.to_lowercase(),
+                    bucket.name.replace('-', ""),
+                    "mismatch at split {split}ms, run {run}ms"
+                );
+            }
+        }
+        let gold = buckets
+            .classify(delta::Split::default(), true)
+            .expect("no bucket");
+        assert_eq!("personal-best", gold.name);
+    }
+
+    #[test]
+    fn gaps_are_found() {
+        let mut buckets = Buckets::default();
+        // Without the last bucket, nothing covers splits and runs that are both ahead.
+        buckets.0.pop();
+        match buckets.validate() {
+            Err(Error::Gap { gold, split, run }) => {
+                assert!(!gold);
+                assert!(split <= 0);
+                assert!(run <= 0);
+            }
+            other => panic!("expected a gap, got {other:?}"),
+        }
+
+        buckets.0.clear();
+        let err = buckets
+            .validate()
+            .expect_err("no buckets should leave a gap");
+        assert_eq!(
+            "no pace bucket matches a split delta of +0ms and run delta of +0ms (gold: false)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn duplicate_names_are_found() {
+        let mut buckets = Buckets::default();
+        let first = buckets.0[0].clone();
+        buckets.0.push(first);
+        assert!(matches!(buckets.validate(), Err(Error::DuplicateName(n)) if n == "personal-best"));
+    }
+}
@@ -9,6 +9,68 @@ pub trait Provider {
     ///
     /// Propagates forwards any errors from the particular comparison method itself.
     fn comparison(&mut self) -> Result;
+
+    /// Gets a revision token for the data underlying this provider's comparisons.
+    ///
+    /// If two calls return the same `Some` token, the provider promises that the comparison
+    /// hasn't changed between them, and so callers (such as [Cached]) can reuse the comparison
+    /// they already have.  Providers that can't cheaply tell whether their data has changed
+    /// return `None`, which is the default, and are always fully refreshed.
+    ///
+    /// # Errors
+    ///
+    /// Propagates forwards any errors from the particular comparison method itself.
+    fn revision(&mut self) -> std::result::Result<Option<u64>, Error> {
+        Ok(None)
+    }
+
+    /// Gets a name for the comparisons this provider gives, to show to the user.
+    ///
+    /// Providers without a name of their own return `None`, which is the default; their
+    /// comparisons keep whatever name they already had.
+    fn name(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A provider that caches the comparisons of another provider, reusing them for as long as the
+/// other provider's [Provider::revision] stays the same.
+pub struct Cached<P> {
+    /// The provider being cached.
+    inner: P,
+    /// The most recent comparison, alongside its revision.
+    cache: Option<(u64, Option<Comparison>)>,
+}
+
+impl<P> Cached<P> {
+    /// Wraps `inner` in a cache.
+    #[must_use]
+    pub fn new(inner: P) -> Self {
+        Self { inner, cache: None }
+    }
+}
+
+impl<P: Provider> Provider for Cached<P> {
+    fn comparison(&mut self) -> Result {
+        let revision = self.inner.revision()?;
+        if let (Some(revision), Some((cached, comparison))) = (revision, &self.cache) {
+            if revision == *cached {
+                return Ok(comparison.clone());
+            }
+        }
+
+        let comparison = self.inner.comparison()?;
+        self.cache = revision.map(|r| (r, comparison.clone()));
+        Ok(comparison)
+    }
+
+    fn revision(&mut self) -> std::result::Result<Option<u64>, Error> {
+        self.inner.revision()
+    }
+
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
 }
 
 /// A provider that never provides comparisons.
@@ -41,3 +103,46 @@ pub enum Error {
 
 /// Shorthand for results from comparison providers.
 pub type Result = std::result::Result<Option<Comparison>, Error>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A provider that counts how many times it has been asked for a comparison.
+    #[derive(Default)]
+    struct Counting {
+        revision: u64,
+        calls: usize,
+    }
+
+    impl Provider for Counting {
+        fn comparison(&mut self) -> Result {
+            self.calls += 1;
+            Ok(Some(Comparison::default()))
+        }
+
+        fn revision(&mut self) -> std::result::Result<Option<u64>, Error> {
+            Ok(Some(self.revision))
+        }
+    }
+
+    #[test]
+    fn cached_reuses_comparison_until_revision_changes() {
+        let mut cached = Cached::new(Counting::default());
+
+        for _ in 0..3 {
+            assert!(cached.comparison().unwrap().is_some());
+        }
+        assert_eq!(
+            1, cached.inner.calls,
+            "comparison should only be fetched once"
+        );
+
+        cached.inner.revision += 1;
+        assert!(cached.comparison().unwrap().is_some());
+        assert_eq!(
+            2, cached.inner.calls,
+            "new revision should refetch comparison"
+        );
+    }
+}
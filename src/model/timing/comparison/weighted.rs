@@ -0,0 +1,255 @@
+/*! Recency-weighted comparisons.
+
+A PB comparison reflects one good run, which may be long ago; a flat average over the run history
+drags in every bad run from when the category was new.  A weighted comparison instead targets
+'current form': each split's segment time is a mean over the history, in which each run counts
+`decay` times as much as the run after it.  With a decay of 1, this is a plain average; the closer
+the decay gets to 0, the more the comparison follows the latest few runs.
+
+Runs are weighted by their position in the whole history, not just among the runs that timed a
+given split.  A split that some runs skipped takes its mean over only the runs that timed it, so
+skipped splits neither drag the mean down nor shift the weights of other runs.
+
+Cumulative times and the run total come from summing the weighted segment times in split order.
+Split PBs ('gold splits') and the sum of best carry over from the PB comparison; splits the PB
+doesn't have use their weighted time as their split PB.  If there are no runs in the history, there
+is nothing to weigh, and [WeightedProvider] gives no comparison.
+*/
+
+use super::{
+    super::{
+        super::{history, short},
+        aggregate, time,
+    },
+    provider, Comparison, Segment,
+};
+
+/// The default half-life, in runs, of weighted comparisons.
+pub const DEFAULT_HALF_LIFE: u32 = 5;
+
+/// Split totals for each run in the history, oldest first.
+pub type History = Vec<history::timing::Totals>;
+
+/// Trait of comparison providers that can also supply a dated run history.
+pub trait Source: provider::Provider {
+    /// Gets the split totals of every run in the history, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Propagates forwards any errors from the underlying history storage.
+    fn dated_history(&mut self) -> Result<History, provider::Error>;
+}
+
+/// Gets the decay at which a run counts half as much as the run `runs` places after it.
+///
+/// Half-lives of 0 count as 1.
+///
+/// ```
+/// use zombiesplit::model::timing::comparison::weighted;
+///
+/// assert_eq!(0.5, weighted::decay_for_half_life(1));
+/// let decay = weighted::decay_for_half_life(4);
+/// assert!((decay.powi(4) - 0.5).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn decay_for_half_life(runs: u32) -> f64 {
+    0.5_f64.powf(1.0 / f64::from(runs.max(1)))
+}
+
+/// Weighs `history` (oldest run first) with `decay`, taking split PBs and the sum of best from
+/// `pb`, with `order` as the order of splits.
+///
+/// Decays outside `(0, 1]` are clamped into it.  Returns `None` if no run in the history timed any
+/// split in `order`.
+///
+/// ```
+/// use zombiesplit::model::{
+///     history::timing::Totals,
+///     short,
+///     timing::{comparison::{weighted, Comparison}, time::Time},
+/// };
+///
+/// let (a, b) = (short::Name::from("a"), short::Name::from("b"));
+/// let s = |secs| Time::from_millis(secs * 1000);
+///
+/// // The newer run skipped `b`.
+/// let history = vec![
+///     Totals { totals: [(a, s(10)), (b, s(20))].into_iter().collect() },
+///     Totals { totals: [(a, s(7))].into_iter().collect() },
+/// ];
+/// let cmp = weighted::weigh(&Comparison::default(), &history, &[a, b], 0.5).unwrap();
+///
+/// // `a` is (10*0.5 + 7*1) / 1.5 = 8s; `b` only has the older run.
+/// assert_eq!(s(8), cmp.splits[&a].in_pb_run.split);
+/// assert_eq!(s(20), cmp.splits[&b].in_pb_run.split);
+/// assert_eq!(Some(s(28)), cmp.run.total_in_pb_run);
+/// ```
+#[must_use]
+pub fn weigh(
+    pb: &Comparison,
+    history: &[history::timing::Totals],
+    order: &[short::Name],
+    decay: f64,
+) -> Option<Comparison> {
+    let decay = if decay.is_nan() {
+        1.0
+    } else {
+        decay.clamp(f64::MIN_POSITIVE, 1.0)
+    };
+
+    let mut result = Comparison {
+        run: pb.run,
+        ..Comparison::default()
+    };
+    let mut cumulative = time::Time::default();
+    for short in order {
+        if let Some(split) = weighted_mean(history, *short, decay) {
+            cumulative += split;
+            let split_pb = pb.splits.get(short).map_or(split, |s| s.split_pb);
+            result.splits.insert(
+                *short,
+                Segment {
+                    split_pb,
+                    in_pb_run: aggregate::Set { split, cumulative },
+                },
+            );
+        }
+    }
+
+    if result.splits.is_empty() {
+        return None;
+    }
+    result.run.total_in_pb_run = Some(cumulative);
+    Some(result)
+}
+
+/// Gets the mean time of `split` over the runs in `history` that timed it, with each run weighted
+/// `decay` times as much as the run after it.
+// Millisecond times are far too small for these casts to lose anything, and the mean always lies
+// between two of them.
+#[allow(clippy::cast_possible_truncation)]
+fn weighted_mean(
+    history: &[history::timing::Totals],
+    split: short::Name,
+    decay: f64,
+) -> Option<time::Time> {
+    let (mut sum, mut weights) = (0.0, 0.0);
+    let mut weight = 1.0;
+    for run in history.iter().rev() {
+        if let Some(time) = run.totals.get(&split) {
+            sum += weight * f64::from(time.into_millis());
+            weights += weight;
+        }
+        weight *= decay;
+    }
+    (0.0 < weights).then(|| time::Time::from_millis((sum / weights).round() as i32))
+}
+
+/// A comparison provider that weighs the run history from another provider towards recent runs.
+///
+/// See the module documentation for the method.
+pub struct WeightedProvider<S> {
+    /// The underlying provider of PBs and history.
+    source: S,
+    /// The order of splits in the run.
+    order: Vec<short::Name>,
+    /// How much each run counts relative to the run after it.
+    decay: f64,
+}
+
+impl<S: Source> WeightedProvider<S> {
+    /// Constructs a weighted provider over `source`, with `order` as the order of splits in the
+    /// run and `decay` as the weight of each run relative to the run after it.
+    ///
+    /// See [decay_for_half_life] for a friendlier way to pick a decay.
+    #[must_use]
+    pub fn new(source: S, order: impl IntoIterator<Item = short::Name>, decay: f64) -> Self {
+        Self {
+            source,
+            order: order.into_iter().collect(),
+            decay,
+        }
+    }
+}
+
+impl<S: Source> provider::Provider for WeightedProvider<S> {
+    fn comparison(&mut self) -> provider::Result {
+        let history = self.source.dated_history()?;
+        if history.is_empty() {
+            return Ok(None);
+        }
+        let pb = self.source.comparison()?.unwrap_or_default();
+        Ok(weigh(&pb, &history, &self.order, self.decay))
+    }
+
+    /// The weighted comparison changes exactly when the run history does.
+    fn revision(&mut self) -> Result<Option<u64>, provider::Error> {
+        self.source.revision()
+    }
+
+    fn name(&self) -> Option<String> {
+        Some("Current Form".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn s(secs: i32) -> time::Time {
+        time::Time::from_millis(secs * 1000)
+    }
+
+    fn run(times: &[(short::Name, time::Time)]) -> history::timing::Totals {
+        history::timing::Totals {
+            totals: times.iter().copied().collect(),
+        }
+    }
+
+    /// A decay of 1 weighs every run equally.
+    #[test]
+    fn unit_decay_is_plain_average() {
+        let a = short::Name::from("a");
+        let history = vec![run(&[(a, s(10))]), run(&[(a, s(20))]), run(&[(a, s(30))])];
+        let cmp = weigh(&Comparison::default(), &history, &[a], 1.0).expect("should weigh");
+        assert_eq!(s(20), cmp.splits[&a].in_pb_run.split);
+    }
+
+    /// Runs that skipped a split don't count towards its mean, but still age the runs before them.
+    #[test]
+    fn skipped_runs_still_age_older_runs() {
+        let a = short::Name::from("a");
+        let b = short::Name::from("b");
+        let history = vec![run(&[(a, s(40))]), run(&[(b, s(5))]), run(&[(a, s(10))])];
+        // Weights are 0.25 for the oldest run and 1 for the newest: (40*0.25 + 10) / 1.25 = 16s.
+        let cmp = weigh(&Comparison::default(), &history, &[a, b], 0.5).expect("should weigh");
+        assert_eq!(s(16), cmp.splits[&a].in_pb_run.split);
+        assert_eq!(s(5), cmp.splits[&b].in_pb_run.split);
+        assert_eq!(s(21), cmp.splits[&b].in_pb_run.cumulative);
+    }
+
+    /// Split PBs and the sum of best come from the PB, where it has them.
+    #[test]
+    fn split_pbs_come_from_pb() {
+        let a = short::Name::from("a");
+        let mut pb = Comparison::default();
+        pb.splits.insert(
+            a,
+            Segment {
+                split_pb: s(9),
+                in_pb_run: aggregate::Set::default(),
+            },
+        );
+        pb.run.sum_of_best = Some(s(9));
+
+        let cmp = weigh(&pb, &[run(&[(a, s(12))])], &[a], 0.5).expect("should weigh");
+        assert_eq!(s(9), cmp.splits[&a].split_pb);
+        assert_eq!(Some(s(9)), cmp.run.sum_of_best);
+    }
+
+    #[test]
+    fn empty_history_gives_nothing() {
+        let a = short::Name::from("a");
+        assert!(weigh(&Comparison::default(), &[], &[a], 0.5).is_none());
+    }
+}
@@ -3,16 +3,22 @@
 pub use error::Error;
 pub use format::Format;
 pub use position::Position;
+pub use rounding::Rounding;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
+    fmt,
     iter::Sum,
     ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
 };
 
 pub mod error;
 pub mod field;
 pub mod format;
 pub mod human;
+pub mod millis;
 pub mod position;
+pub mod rounding;
 
 /// A time.
 ///
@@ -20,7 +26,25 @@ pub mod position;
 /// The exact representation is subject to change, but should be enough to accommodate all but the
 /// most pathological speedrun cumulative times (eg, it can stretch to several days before
 /// overflow).
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Default, Hash)]
+///
+/// Times are always stored, and summed, at full millisecond precision; see [rounding] for how
+/// they become less precise for display.
+///
+/// Times serialise in their human-readable form (see [human::Time]); use [millis] to serialise
+/// them as plain millisecond counts instead.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Default,
+    Hash,
+    SerializeDisplay,
+    DeserializeFromStr,
+)]
 pub struct Time(i32);
 
 impl Time {
@@ -46,6 +70,41 @@ impl Time {
     }
 }
 
+/// Times display in their human-readable form.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        human::Time::from(*self).fmt(f)
+    }
+}
+
+/// Times parse from their human-readable form.
+impl FromStr for Time {
+    type Err = Error;
+
+    /// Parses a time from its human-readable form.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::Time;
+    ///
+    /// let time: Time = "1m6s60".parse().unwrap();
+    /// assert_eq!(66_600, time.into_millis());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<human::Time>().and_then(Self::try_from)
+    }
+}
+
+/// Times have the same schema as their human-readable form.
+impl schemars::JsonSchema for Time {
+    fn schema_name() -> String {
+        <human::Time as schemars::JsonSchema>::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <human::Time as schemars::JsonSchema>::json_schema(gen)
+    }
+}
+
 impl Sum for Time {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         Self::from_millis(iter.map(Self::into_millis).sum())
@@ -70,7 +129,7 @@ impl Sub for Time {
 
 impl AddAssign for Time {
     fn add_assign(&mut self, rhs: Self) {
-        self.0 -= rhs.0;
+        self.0 += rhs.0;
     }
 }
 
@@ -106,6 +165,18 @@ mod tests {
         assert_eq!(t1, (t1 + t2) - t2);
     }
 
+    /// Tests that adding and subtracting in place agree with their binary counterparts.
+    #[test]
+    fn time_add_sub_assign() {
+        let t1: Time = Time::from_millis(15100);
+        let t2: Time = Time::from_millis(6410);
+        let mut t = t1;
+        t += t2;
+        assert_eq!(t1 + t2, t);
+        t -= t2;
+        assert_eq!(t1, t);
+    }
+
     /// Tests that subtracting a large time from a short time does not saturate.
     #[test]
     fn time_sub_no_sat() {
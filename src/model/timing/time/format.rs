@@ -1,8 +1,10 @@
 /*! User-suppliable formatting for times.
 
-These structures and related support let users tell zombiesplit how to lay out times on the UI. */
+These structures and related support let users tell zombiesplit how to lay out times, both on the
+UI and anywhere else times are shown to them as text.  (The on-disk form of times is fixed; see
+[super::human].) */
 
-use super::Position;
+use super::{Position, Rounding, Time};
 use itertools::Itertools;
 use num_integer::Integer;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
@@ -16,7 +18,7 @@ use thiserror::Error;
 ///
 /// This conceptually takes the form of a list of (position, digit length) pairs, for instance
 /// (hour, 3).
-#[derive(DeserializeFromStr, SerializeDisplay, Clone, Debug)]
+#[derive(DeserializeFromStr, SerializeDisplay, Clone, Debug, PartialEq, Eq)]
 pub struct Format(Vec<Component>);
 
 /// The default format is mm'ss"uuu.
@@ -46,6 +48,154 @@ impl Format {
     pub fn components(&self) -> impl Iterator<Item = &Component> {
         self.0.iter()
     }
+
+    /// Iterates over the positions in this time layout, alongside their digit widths.
+    ///
+    /// Positions always come in decreasing order of significance.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{Format, Position};
+    ///
+    /// let format: Format = "h:mm:ss.uu".parse().unwrap();
+    /// let positions: Vec<_> = format.positions().collect();
+    /// assert_eq!(
+    ///     vec![
+    ///         (Position::Hours, 1),
+    ///         (Position::Minutes, 2),
+    ///         (Position::Seconds, 2),
+    ///         (Position::Milliseconds, 2),
+    ///     ],
+    ///     positions
+    /// );
+    /// ```
+    pub fn positions(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.0.iter().filter_map(|c| match *c {
+            Component::Position { position, width } => Some((position, width)),
+            Component::Delimiter(_) => None,
+        })
+    }
+
+    /// Gets the number of milliseconds in the smallest unit this format displays.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::Format;
+    ///
+    /// assert_eq!(1, Format::default().precision());
+    /// assert_eq!(10, "mm:ss.uu".parse::<Format>().unwrap().precision());
+    /// assert_eq!(1000, "mm:ss".parse::<Format>().unwrap().precision());
+    /// ```
+    #[must_use]
+    pub fn precision(&self) -> i32 {
+        self.positions()
+            .last()
+            .map_or(1, |(position, width)| match position {
+                Position::Milliseconds => {
+                    let dropped = position.default_width().saturating_sub(width);
+                    10_i32.pow(u32::try_from(dropped).unwrap_or_default())
+                }
+                _ => position.ms_offset(),
+            })
+    }
+
+    /// Rounds `time`, according to `rounding`, to the precision this format displays.
+    ///
+    /// Rendering code should do this before splitting the time into its positions, so that
+    /// rounding up carries over into the more significant positions.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{Format, Rounding, Time};
+    ///
+    /// let format: Format = "mm:ss.uu".parse().unwrap();
+    /// let time = Time::from_millis(59_999);
+    /// assert_eq!(Time::from_millis(59_990), format.round(time, Rounding::Truncate));
+    /// assert_eq!(Time::from_millis(60_000), format.round(time, Rounding::Nearest));
+    /// ```
+    #[must_use]
+    pub fn round(&self, time: Time, rounding: Rounding) -> Time {
+        rounding.round(time, self.precision())
+    }
+
+    /// Lays out `time` as text in this format, first rounding away any digits the format doesn't
+    /// show according to `rounding`.
+    ///
+    /// The most significant position shown takes in any larger units, so (for instance) `m:ss`
+    /// shows an hour and two minutes as `62:00`.  Negative times start with `-`.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{Format, Rounding, Time};
+    ///
+    /// let time = Time::from_millis(66_600);
+    /// assert_eq!("01'06\"600", Format::default().format(time, Rounding::Truncate));
+    ///
+    /// let clock: Format = "m:ss.uu".parse().unwrap();
+    /// assert_eq!("1:06.60", clock.format(time, Rounding::Truncate));
+    /// assert_eq!("62:00.00", clock.format(Time::from_millis(3_720_000), Rounding::Truncate));
+    ///
+    /// let whole: Format = "m:ss".parse().unwrap();
+    /// assert_eq!("-1:06", whole.format(Time::from_millis(-66_600), Rounding::Truncate));
+    /// assert_eq!("-1:07", whole.format(Time::from_millis(-66_600), Rounding::Nearest));
+    /// ```
+    #[must_use]
+    pub fn format(&self, time: Time, rounding: Rounding) -> String {
+        let millis = self.round(time, rounding).into_millis();
+        let magnitude = millis.unsigned_abs();
+        let first = self.positions().next().map(|(position, _)| position);
+
+        let mut result = String::new();
+        if millis < 0 {
+            result.push('-');
+        }
+        for c in &self.0 {
+            match *c {
+                Component::Position { position, width } => {
+                    let value = magnitude / position.ms_offset().unsigned_abs();
+                    let value = if first == Some(position) {
+                        value
+                    } else {
+                        value % u32::from(position.capacity())
+                    };
+                    write_position(&mut result, position, width, value);
+                }
+                Component::Delimiter(d) => result.push(d),
+            }
+        }
+        result
+    }
+
+    /// Lays out `delta` as text in this format, as [Format::format] does, but always showing its
+    /// sign.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{Format, Rounding, Time};
+    ///
+    /// let format: Format = "s.uu".parse().unwrap();
+    /// assert_eq!("+1.50", format.delta(Time::from_millis(1_500), Rounding::Truncate));
+    /// assert_eq!("-1.50", format.delta(Time::from_millis(-1_500), Rounding::Truncate));
+    /// assert_eq!("+0.00", format.delta(Time::from_millis(-4), Rounding::Truncate));
+    /// ```
+    #[must_use]
+    pub fn delta(&self, delta: Time, rounding: Rounding) -> String {
+        let formatted = self.format(delta, rounding);
+        if self.round(delta, rounding).into_millis() < 0 {
+            formatted
+        } else {
+            format!("+{formatted}")
+        }
+    }
+}
+
+/// Writes `value`, the amount at `position`, padded to `width` digits.
+///
+/// Milliseconds show their most significant digits, so a width of 2 gives hundredths.
+fn write_position(out: &mut String, position: Position, width: usize, value: u32) {
+    // Writing to a string can't fail.
+    let _ = if position == Position::Milliseconds {
+        let digits = format!("{value:03}");
+        let shown = &digits[..width.min(digits.len())];
+        write!(out, "{shown:0<width$}")
+    } else {
+        write!(out, "{value:0>width$}")
+    };
 }
 
 impl FromStr for Format {
@@ -95,6 +245,7 @@ impl Parser {
         if self.is_escaping {
             Err(Error::UnbalancedEscape)
         } else {
+            check_order(&self.result)?;
             Ok(self.result)
         }
     }
@@ -111,6 +262,23 @@ impl Parser {
     }
 }
 
+/// Checks that the positions in `components` come in strictly decreasing order of significance.
+fn check_order(components: &[Component]) -> Result<()> {
+    let significance = |p: Position| Position::ALL.iter().position(|x| *x == p);
+    let mut last: Option<Position> = None;
+    for c in components {
+        if let Component::Position { position, .. } = *c {
+            if let Some(after) = last {
+                if significance(position) <= significance(after) {
+                    return Err(Error::PositionOrder { position, after });
+                }
+            }
+            last = Some(position);
+        }
+    }
+    Ok(())
+}
+
 fn parse_position_char(c: char) -> Option<Position> {
     match c {
         CHAR_HOUR => Some(Position::Hours),
@@ -192,6 +360,10 @@ fn emit_delimiter(f: &mut Formatter, c: char) -> std::fmt::Result {
 pub enum Error {
     #[error("Expected a character after '\\'")]
     UnbalancedEscape,
+    #[error(
+        "{position} can't come after {after}; positions must go from hours down to milliseconds"
+    )]
+    PositionOrder { position: Position, after: Position },
 }
 
 /// Shorthand for results over time parsing.
@@ -302,6 +474,44 @@ mod tests {
         assert_eq!(expected, apos);
     }
 
+    /// Tests that positions out of order, or repeated, fail to parse.
+    #[test]
+    fn test_time_parse_bad_order() {
+        let e = "ss:mm"
+            .parse::<Format>()
+            .expect_err("should have failed to parse here");
+        assert_eq!(
+            Error::PositionOrder {
+                position: Position::Minutes,
+                after: Position::Seconds
+            },
+            e
+        );
+
+        "mm:ss:mm"
+            .parse::<Format>()
+            .expect_err("repeated positions shouldn't parse");
+    }
+
+    /// Tests that rounding up carries into more significant positions when formatting.
+    #[test]
+    fn test_format_round_carries() {
+        let format: Format = "h:mm:ss.u".parse().expect("parse failure");
+        let time = Time::from_millis(3_599_960);
+        assert_eq!("0:59:59.9", format.format(time, Rounding::Truncate));
+        assert_eq!("1:00:00.0", format.format(time, Rounding::Nearest));
+    }
+
+    /// Tests that wide millisecond positions pad with trailing zeros.
+    #[test]
+    fn test_format_wide_millis() {
+        let format: Format = "ss.uuuu".parse().expect("parse failure");
+        assert_eq!(
+            "05.0070",
+            format.format(Time::from_millis(5_007), Rounding::Truncate)
+        );
+    }
+
     /// Tests that round-tripping the parse/emit for index characters works ok.
     #[test]
     fn test_parse_char_round_trip() {
@@ -4,9 +4,10 @@ use super::{
     error::{Error, Result},
     Position,
 };
+use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
-    fmt,
+    fmt::{self, Write},
     ops::{Index, IndexMut},
     str::FromStr,
 };
@@ -45,6 +46,21 @@ pub struct Time {
     pub millis: u16,
 }
 
+/// What to do when a field of a time holds more than it can, such as 90 seconds.
+///
+/// Parsing a time checks only that each field fits its storage, so that (for instance) editors
+/// can hold a field mid-edit.  Once a time is complete, [Time::validate] decides whether such
+/// a field is a mistake or a shorthand.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Overflow {
+    /// Reject the time.
+    #[default]
+    Reject,
+    /// Carry the excess into more significant fields, so 90 seconds becomes 1 minute 30.
+    Carry,
+}
+
 impl Time {
     /// Constructs a non-negative time from its hour, minute, second, and millisecond positions.
     ///
@@ -115,11 +131,28 @@ impl Time {
 
     /// Converts a [Time] into a 32-bit millisecond timestamp.
     ///
+    /// The timestamp is negative if the time is.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::human;
+    ///
+    /// let time = human::Time { is_negative: true, ..human::Time::new(0, 0, 1, 500) };
+    /// assert_eq!(Ok(-1500), time.try_into_millis());
+    /// ```
+    ///
     /// # Errors
     ///
     /// Fails if the time doesn't fit into a signed 32-bit integer.
     pub fn try_into_millis(self) -> Result<i32> {
-        Position::ALL.iter().map(|f| self.field_ms(*f)).sum()
+        let magnitude = Position::ALL.iter().try_fold(0_i32, |acc, f| {
+            self.field_ms(*f)
+                .and_then(|ms| acc.checked_add(ms).ok_or(Error::MsecOverflow(self)))
+        })?;
+        Ok(if self.is_negative {
+            -magnitude
+        } else {
+            magnitude
+        })
     }
 
     /// Tries to construct a [Time] from a given number of seconds.
@@ -167,8 +200,97 @@ impl Time {
         self.hours == 0 && self.mins == 0 && self.secs == 0 && self.millis == 0
     }
 
+    /// Adds one unit of the field at `position` to this time.
+    ///
+    /// Overflow carries into more significant fields, so (for instance) incrementing the seconds
+    /// of `59s` gives `1m00s`.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{human, Position};
+    ///
+    /// let time = human::Time::new(0, 1, 59, 500);
+    /// assert_eq!(
+    ///     human::Time::new(0, 2, 0, 500),
+    ///     time.increment(Position::Seconds).unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the result is too large to represent.
+    pub fn increment(self, position: Position) -> Result<Self> {
+        self.step(position, 1)
+    }
+
+    /// Subtracts one unit of the field at `position` from this time.
+    ///
+    /// Underflow borrows from more significant fields, so (for instance) decrementing the seconds
+    /// of `1m00s` gives `59s`.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{human, Position};
+    ///
+    /// let time = human::Time::new(0, 1, 0, 500);
+    /// assert_eq!(
+    ///     human::Time::new(0, 0, 59, 500),
+    ///     time.decrement(Position::Seconds).unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the result is too large to represent.
+    pub fn decrement(self, position: Position) -> Result<Self> {
+        self.step(position, -1)
+    }
+
+    /// Adds `steps` units of the field at `position` to this time, carrying as needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the result is too large to represent.
+    pub fn step(self, position: Position, steps: i32) -> Result<Self> {
+        let millis = self.try_into_millis()?;
+        position
+            .ms_offset()
+            .checked_mul(steps)
+            .and_then(|delta| millis.checked_add(delta))
+            .map(Self::from_millis)
+            .ok_or(Error::MsecOverflow(self))
+    }
+
+    /// Checks that no field of this time holds more than it can, following `overflow` if one does.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::human::{Overflow, Time};
+    ///
+    /// let time = Time::new(0, 0, 90, 0);
+    /// time.validate(Overflow::Reject).expect_err("90 seconds is out of range");
+    /// assert_eq!(Time::new(0, 1, 30, 0), time.validate(Overflow::Carry).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if a field is out of range and `overflow` rejects it, or if carrying makes the time
+    /// too large to represent.
+    pub fn validate(self, overflow: Overflow) -> Result<Self> {
+        match overflow {
+            Overflow::Reject => Position::ALL
+                .iter()
+                .find(|p| p.capacity() <= self[**p])
+                .map_or(Ok(self), |p| {
+                    Err(Error::FieldTooBig {
+                        pos: *p,
+                        val: u32::from(self[*p]),
+                    })
+                }),
+            // Stepping by nothing renormalises the fields through milliseconds.
+            Overflow::Carry => self.step(Position::Milliseconds, 0),
+        }
+    }
+
     fn field_ms(&self, field: Position) -> Result<i32> {
-        let offset = i32::from(field.ms_offset());
+        let offset = field.ms_offset();
         let base = i32::from(self[field]);
         offset.checked_mul(base).ok_or(Error::MsecOverflow(*self))
     }
@@ -211,28 +333,78 @@ impl TryFrom<Time> for super::Time {
 }
 
 impl fmt::Display for Time {
+    /// Formats a time in the same delimited format that [FromStr] parses.
+    ///
+    /// Negative times start with `-`.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::human;
+    ///
+    /// let time = human::Time::new(0, 1, 6, 600);
+    /// assert_eq!("01m06s600", time.to_string());
+    /// assert_eq!(time, time.to_string().parse().unwrap());
+    ///
+    /// let negative = human::Time { is_negative: true, ..time };
+    /// assert_eq!("-01m06s600", negative.to_string());
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative && !self.is_zero() {
+            f.write_char('-')?;
+        }
         Position::ALL
             .iter()
-            .try_for_each(|p| p.fmt_value(f, self[*p]))
+            .try_for_each(|p| p.fmt_value_delimited(f, self[*p]))
     }
 }
 
 impl FromStr for Time {
     type Err = Error;
 
+    /// Parses a time from its delimited format.
+    ///
+    /// The seconds can end in `s`, a decimal point, or a decimal comma, whichever is more natural
+    /// to type.  A leading `-` makes the time negative.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::human;
+    ///
+    /// let time = human::Time::new(0, 1, 23, 456);
+    /// assert_eq!(time, "1m23s456".parse().unwrap());
+    /// assert_eq!(time, "1m23.456".parse().unwrap());
+    /// assert_eq!(time, "1m23,456".parse().unwrap());
+    ///
+    /// let negative: human::Time = "-1m23.456".parse().unwrap();
+    /// assert!(negative.is_negative);
+    /// assert_eq!(Ok(-83_456), negative.try_into_millis());
+    /// ```
     fn from_str(s: &str) -> Result<Self> {
         let mut result = Self::default();
-        let mut rest = s;
+        let (is_negative, mut rest) = s.strip_prefix('-').map_or((false, s), |r| (true, r));
         for p in Position::ALL {
-            let (val, r) = p.split_and_parse(s)?;
+            let (val, r) = p.split_and_parse(rest)?;
             result[*p] = val;
             rest = r;
         }
+        // Negative zero is subnormal, so we don't produce it.
+        result.is_negative = is_negative && !result.is_zero();
         Ok(result)
     }
 }
 
+impl schemars::JsonSchema for Time {
+    fn schema_name() -> String {
+        "Time".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        super::super::super::load::schema::string(
+            "A time in hours, minutes, seconds, and milliseconds, such as 1h23m45s678 or \
+             1m23.456; hours and minutes are optional, and a leading - makes the time negative.",
+            None,
+        )
+    }
+}
+
 /// We can index into a time by position index, returning a field.
 impl Index<Position> for Time {
     type Output = u16;
@@ -262,6 +434,56 @@ impl IndexMut<Position> for Time {
 mod tests {
     use super::*;
 
+    /// Tests that rejecting overflow catches each out-of-range field.
+    #[test]
+    fn validate_rejects_each_field() {
+        for (time, pos) in [
+            (Time::new(0, 60, 0, 0), Position::Minutes),
+            (Time::new(0, 0, 60, 0), Position::Seconds),
+            (Time::new(0, 0, 0, 1000), Position::Milliseconds),
+        ] {
+            assert_eq!(
+                Err(Error::FieldTooBig {
+                    pos,
+                    val: u32::from(time[pos])
+                }),
+                time.validate(Overflow::Reject)
+            );
+        }
+    }
+
+    /// Tests that in-range times pass validation unchanged, whatever the overflow policy.
+    #[test]
+    fn validate_accepts_in_range() {
+        let time = Time::new(1, 59, 59, 999);
+        assert_eq!(Ok(time), time.validate(Overflow::Reject));
+        assert_eq!(Ok(time), time.validate(Overflow::Carry));
+    }
+
+    /// Tests that carrying overflow moves excess into each more significant field.
+    #[test]
+    fn validate_carries_each_field() {
+        assert_eq!(
+            Ok(Time::new(1, 30, 0, 0)),
+            Time::new(0, 90, 0, 0).validate(Overflow::Carry)
+        );
+        assert_eq!(
+            Ok(Time::new(0, 1, 1, 500)),
+            Time::new(0, 0, 60, 1500).validate(Overflow::Carry)
+        );
+        let negative = Time {
+            is_negative: true,
+            ..Time::new(0, 0, 75, 0)
+        };
+        assert_eq!(
+            Ok(Time {
+                is_negative: true,
+                ..Time::new(0, 1, 15, 0)
+            }),
+            negative.validate(Overflow::Carry)
+        );
+    }
+
     #[test]
     fn time_from_str_empty() {
         let t: Time = "".parse().expect("should be valid");
@@ -317,6 +539,45 @@ mod tests {
         assert_eq!(u16::from(t.millis), 456);
     }
 
+    #[test]
+    fn time_from_str_decimal_comma() {
+        let t: Time = "10,5".parse().expect("should be valid");
+        assert_eq!(u16::from(t.secs), 10);
+        assert_eq!(u16::from(t.millis), 500);
+    }
+
+    #[test]
+    fn time_from_str_decimal_point_twice() {
+        "10.5.5"
+            .parse::<Time>()
+            .expect_err("only one decimal separator should be allowed");
+    }
+
+    /// Tests that negative times survive a round trip through their string form.
+    #[test]
+    fn negative_round_trip() {
+        for millis in [-1, -1_000, -83_456, -3_723_456] {
+            let time = Time::from_millis(millis);
+            let parsed: Time = time.to_string().parse().expect("should be valid");
+            assert_eq!(time, parsed);
+            assert_eq!(Ok(millis), parsed.try_into_millis());
+
+            let internal = super::super::Time::from_millis(millis);
+            assert_eq!(
+                internal,
+                internal.to_string().parse().expect("should be valid")
+            );
+        }
+    }
+
+    /// Tests that a negative zero parses as a plain zero.
+    #[test]
+    fn negative_zero_from_str() {
+        let t: Time = "-0s".parse().expect("should be valid");
+        assert_eq!(Time::default(), t);
+        assert_eq!("000", t.to_string());
+    }
+
     /// Tests that indexing seems to work properly.
     #[test]
     fn index() {
@@ -0,0 +1,80 @@
+/*! Serialising times as plain millisecond counts.
+
+By default, [Time]s serialise in their human-readable form (such as `"1m6s60"`), which suits
+hand-authored files.  External tools reading wire or export formats usually find it easier to deal
+with a single integer number of milliseconds instead; this module, and its [option] submodule for
+optional times, can be used with serde's `with` attribute to get that representation.
+
+```
+use serde::{Deserialize, Serialize};
+use zombiesplit::model::timing::time::{millis, Time};
+
+#[derive(Serialize, Deserialize)]
+struct Export {
+    #[serde(with = "millis")]
+    total: Time,
+    #[serde(with = "millis::option")]
+    delta: Option<Time>,
+}
+
+let export = Export { total: Time::from_millis(66_600), delta: Some(Time::from_millis(-250)) };
+let toml = toml::to_string(&export).unwrap();
+assert_eq!("total = 66600\ndelta = -250\n", toml);
+
+let back: Export = toml::from_str(&toml).unwrap();
+assert_eq!(Time::from_millis(66_600), back.total);
+assert_eq!(Some(Time::from_millis(-250)), back.delta);
+```
+*/
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::Time;
+
+/// Serialises `time` as its number of milliseconds.
+///
+/// # Errors
+///
+/// Propagates any errors from `serializer`.
+pub fn serialize<S: Serializer>(time: &Time, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i32(time.into_millis())
+}
+
+/// Deserialises a time from a number of milliseconds.
+///
+/// # Errors
+///
+/// Fails if the input isn't an integer that fits in a time.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Time, D::Error> {
+    i32::deserialize(deserializer).map(Time::from_millis)
+}
+
+/// Serialising optional times as optional millisecond counts.
+pub mod option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Time;
+
+    /// Serialises `time` as its number of milliseconds, if it is present.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any errors from `serializer`.
+    pub fn serialize<S: Serializer>(time: &Option<Time>, serializer: S) -> Result<S::Ok, S::Error> {
+        match time {
+            Some(t) => serializer.serialize_some(&t.into_millis()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialises an optional time from an optional number of milliseconds.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input is present, but isn't an integer that fits in a time.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Time>, D::Error> {
+        Option::<i32>::deserialize(deserializer).map(|t| t.map(Time::from_millis))
+    }
+}
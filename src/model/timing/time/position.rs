@@ -59,12 +59,26 @@ impl Position {
         }
     }
 
+    /// Characters, other than [Self::delimiter], that also end this position when parsing.
+    ///
+    /// Seconds can end in a decimal point or a decimal comma, as in `1m23.456` or `1m23,456`;
+    /// neither is ambiguous, as times never contain thousands separators.
+    pub(super) const fn alt_delimiters(self) -> &'static [char] {
+        match self {
+            Self::Seconds => &['.', ','],
+            _ => &[],
+        }
+    }
+
     /// The multiplier needed to convert this position to milliseconds.
-    pub(super) const fn ms_offset(self) -> u16 {
+    ///
+    /// This is also the number of milliseconds in one unit of this position.
+    pub(super) const fn ms_offset(self) -> i32 {
+        // The hour offset doesn't fit in a u16, so we widen before multiplying.
         match self {
-            Self::Hours => MINS_IN_HOUR * SECS_IN_MIN * MSECS_IN_SEC,
-            Self::Minutes => SECS_IN_MIN * MSECS_IN_SEC,
-            Self::Seconds => MSECS_IN_SEC,
+            Self::Hours => MINS_IN_HOUR as i32 * SECS_IN_MIN as i32 * MSECS_IN_SEC as i32,
+            Self::Minutes => SECS_IN_MIN as i32 * MSECS_IN_SEC as i32,
+            Self::Seconds => MSECS_IN_SEC as i32,
             Self::Milliseconds => 1,
         }
     }
@@ -89,8 +103,13 @@ impl Position {
 
     /// Splits a string on this field's delimiter, parses the first part of the string as a
     /// value at this particular field, and passes through the second part for further parsing.
+    ///
+    /// Missing fields parse as zero.
     pub(super) fn split_and_parse(self, s: &str) -> Result<(u16, &str)> {
         let (before, after) = self.split_delimiter(s);
+        if before.is_empty() {
+            return Ok((0, after));
+        }
         self.preprocess_string(before)
             .parse()
             .map_err(|err: ParseIntError| Error::FieldParse { pos: self, err })
@@ -117,7 +136,9 @@ impl Position {
     pub(super) fn split_delimiter(self, s: &str) -> (&str, &str) {
         match self.delimiter() {
             None => (s, ""),
-            Some(d) => s.split_once(d).unwrap_or(("", s)),
+            Some(d) => s
+                .split_once(|c| c == d || self.alt_delimiters().contains(&c))
+                .unwrap_or(("", s)),
         }
     }
 
@@ -0,0 +1,194 @@
+/*! Rounding times to lower precisions.
+
+Times are always stored, and summed into aggregates, at full millisecond precision; rounding only
+happens when deriving a less precise time from them, such as when displaying a time with fewer
+than three millisecond digits.  A [Rounding] policy says how to do this.
+
+Rounding works on the magnitude of a time, so a negative time always rounds to the negation of
+its positive counterpart.  This keeps deltas symmetric: -1.234s truncated to hundredths is
+-1.23s, just as 1.234s is 1.23s.
+*/
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use rusqlite::{
+    types::{FromSql, FromSqlError},
+    ToSql,
+};
+use schemars::JsonSchema;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+use super::{super::super::load::schema, Time};
+
+/// Policy for rounding times to a lower precision.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub enum Rounding {
+    /// Drop any digits beyond the precision, as most in-game timers do.
+    #[default]
+    Truncate,
+    /// Round to the nearest representable time, with halves rounding away from zero.
+    Nearest,
+    /// Round up (away from zero) to the next representable time.
+    Ceil,
+}
+
+impl Rounding {
+    /// Rounds `time` to a multiple of `unit` milliseconds.
+    ///
+    /// Units below one millisecond leave the time alone.  If rounding away from zero would
+    /// overflow, the time truncates instead.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::{Rounding, Time};
+    ///
+    /// let t = Time::from_millis(1_235);
+    /// assert_eq!(Time::from_millis(1_230), Rounding::Truncate.round(t, 10));
+    /// assert_eq!(Time::from_millis(1_240), Rounding::Nearest.round(t, 10));
+    /// assert_eq!(Time::from_millis(1_240), Rounding::Ceil.round(t, 10));
+    ///
+    /// // Negative times round by their magnitude.
+    /// let t = Time::from_millis(-1_234);
+    /// assert_eq!(Time::from_millis(-1_230), Rounding::Truncate.round(t, 10));
+    /// assert_eq!(Time::from_millis(-1_240), Rounding::Ceil.round(t, 10));
+    /// ```
+    #[must_use]
+    pub fn round(self, time: Time, unit: i32) -> Time {
+        if unit <= 1 {
+            return time;
+        }
+        let millis = i64::from(time.into_millis());
+        let unit = i64::from(unit);
+        let magnitude = millis.abs();
+
+        let rem = magnitude % unit;
+        let truncated = magnitude - rem;
+        let rounded = match self {
+            Self::Truncate => truncated,
+            Self::Nearest if rem * 2 < unit => truncated,
+            Self::Nearest | Self::Ceil if rem == 0 => truncated,
+            Self::Nearest | Self::Ceil => truncated + unit,
+        };
+
+        // Truncating can never overflow, as it only ever moves towards zero.
+        let sign = millis.signum();
+        let rounded = i32::try_from(sign * rounded)
+            .or_else(|_| i32::try_from(sign * truncated))
+            .unwrap_or_else(|_| time.into_millis());
+        Time::from_millis(rounded)
+    }
+}
+
+impl Display for Rounding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Truncate => "truncate",
+            Self::Nearest => "nearest",
+            Self::Ceil => "ceil",
+        })
+    }
+}
+
+impl FromStr for Rounding {
+    type Err = RoundingError;
+
+    /// Parses a rounding policy from its name.
+    ///
+    /// ```
+    /// use zombiesplit::model::timing::time::Rounding;
+    ///
+    /// assert_eq!(Rounding::Nearest, "nearest".parse().unwrap());
+    /// "floor".parse::<Rounding>().expect_err("not a policy");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truncate" => Ok(Self::Truncate),
+            "nearest" => Ok(Self::Nearest),
+            "ceil" => Ok(Self::Ceil),
+            _ => Err(RoundingError(s.to_owned())),
+        }
+    }
+}
+
+impl ToSql for Rounding {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::Owned(
+            rusqlite::types::Value::Text(self.to_string()),
+        ))
+    }
+}
+
+impl FromSql for Rounding {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl JsonSchema for Rounding {
+    fn schema_name() -> String {
+        "Rounding".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schema::string_enum([Self::Truncate, Self::Nearest, Self::Ceil])
+    }
+}
+
+/// Error returned when parsing an unknown rounding policy.
+#[derive(Debug, Error)]
+#[error("unknown rounding policy: {0}")]
+pub struct RoundingError(String);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round(rounding: Rounding, millis: i32, unit: i32) -> i32 {
+        rounding
+            .round(Time::from_millis(millis), unit)
+            .into_millis()
+    }
+
+    /// Tests rounding exactly on, just below, and just above a half unit.
+    #[test]
+    fn nearest_halves() {
+        assert_eq!(1_000, round(Rounding::Nearest, 1_499, 1_000));
+        assert_eq!(2_000, round(Rounding::Nearest, 1_500, 1_000));
+        assert_eq!(-2_000, round(Rounding::Nearest, -1_500, 1_000));
+        assert_eq!(1_000, round(Rounding::Nearest, 1_001, 1_000));
+    }
+
+    /// Tests that times already at the precision don't move.
+    #[test]
+    fn exact_times_stay_put() {
+        for rounding in [Rounding::Truncate, Rounding::Nearest, Rounding::Ceil] {
+            assert_eq!(0, round(rounding, 0, 10));
+            assert_eq!(1_230, round(rounding, 1_230, 10));
+            assert_eq!(-1_230, round(rounding, -1_230, 10));
+            assert_eq!(1_234, round(rounding, 1_234, 1));
+        }
+    }
+
+    /// Tests that ceiling moves any remainder, however small, up to the next unit.
+    #[test]
+    fn ceil_smallest_remainder() {
+        assert_eq!(1_010, round(Rounding::Ceil, 1_001, 10));
+        assert_eq!(10, round(Rounding::Ceil, 1, 10));
+        assert_eq!(-10, round(Rounding::Ceil, -1, 10));
+    }
+
+    /// Tests that rounding near the limits of a time doesn't overflow.
+    #[test]
+    fn extremes_dont_overflow() {
+        assert_eq!(i32::MAX - 7, round(Rounding::Ceil, i32::MAX, 10));
+        assert_eq!(i32::MIN + 8, round(Rounding::Truncate, i32::MIN, 10));
+    }
+}
@@ -1,9 +1,13 @@
 /*! zombiesplit client/server netcode.
 
 zombiesplit uses a client/server separation whereby the client and server talk to each other using
-a protocol defined in `proto`.  The netcode is asynchronous and built using tokio. */
+a protocol defined in `proto`.  The netcode is asynchronous and built using tokio.
+
+Servers can also announce themselves on the local network, so that clients can find them; see
+`discovery`. */
 
 pub mod client;
+pub mod discovery;
 pub mod metadata;
 pub mod proto;
 pub mod server;
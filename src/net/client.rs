@@ -30,8 +30,11 @@ impl<O: session::Observer> Handler for Sync<O> {
         self.rt.block_on(self.inner.dump())
     }
 
-    fn handle(&mut self, a: session::Action) -> Result<()> {
-        self.rt.block_on(self.inner.handle_action(a))
+    fn handle(&mut self, a: session::Action) -> Result<session::action::Outcome> {
+        self.rt.block_on(self.inner.handle_action(a))?;
+        // TODO(@MattWindsor91): outcomes aren't yet carried over the wire, so we assume that the
+        // action changed something.
+        Ok(session::action::Outcome::Applied)
     }
 }
 
@@ -54,6 +57,15 @@ impl<O: session::Observer> Sync<O> {
         Ok(Self { inner, rt })
     }
 
+    /// Asks the server for the list of splits in the run, and which is current.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any part of the dumping process fails (primarily network or transcoding errors).
+    pub fn splits(&mut self) -> Result<session::state::SplitList> {
+        self.rt.block_on(self.inner.splits())
+    }
+
     /// Runs the observation loop for the client, until the given cancellation channel fires.
     ///
     /// # Errors
@@ -192,11 +204,26 @@ impl<O: session::Observer> Client<O> {
         Ok(self.grpc.dump(proto::DumpRequest {}).await?.into_inner())
     }
 
-    /// Asks the server to perform an action.
+    /// Asks the server for the list of splits in the run, and which is current.
+    ///
+    /// This suits controller-style clients that need the split list but not the rest of the
+    /// session state.
     ///
     /// # Errors
     ///
     /// Fails if any part of the dumping process fails (primarily network or transcoding errors).
+    pub async fn splits(&mut self) -> Result<session::state::SplitList> {
+        // TODO(@MattWindsor91): the protocol doesn't yet have a lighter request for just the
+        // splits, so we go through a full dump.
+        Ok(self.dump().await?.split_list())
+    }
+
+    /// Asks the server to perform an action.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any part of the dumping process fails (primarily network or transcoding errors),
+    /// or if the protocol has no way of sending `action`.
     pub async fn handle_action(&mut self, action: session::Action) -> Result<()> {
         match action {
             session::Action::NewRun(dest) => {
@@ -216,6 +243,19 @@ impl<O: session::Observer> Client<O> {
                     .pop(proto::encode::action::pop(index, ty)?)
                     .await?;
             }
+            session::Action::SetCurrent(_)
+            | session::Action::PushTotal(..)
+            | session::Action::Adjust(..)
+            | session::Action::ClearAll
+            | session::Action::Split(_)
+            | session::Action::SplitReset
+            | session::Action::PushNewSplit(..)
+            | session::Action::Finish
+            | session::Action::Edit(_)
+            | session::Action::MergeSplits(..)
+            | session::Action::SetMeta(..)
+            | session::Action::Practice(_)
+            | session::Action::ExportSnapshot(_) => return Err(Error::UnsupportedAction(action)),
         }
         Ok(())
     }
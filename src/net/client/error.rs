@@ -19,6 +19,9 @@ pub enum Error {
     /// A client-side decoding error.
     #[error("error decoding response from server")]
     Decode(#[from] super::proto::decode::Error),
+    /// The protocol has no way to send this action to the server.
+    #[error("action not supported over the network: {0:?}")]
+    UnsupportedAction(crate::model::session::Action),
     /// Couldn't cancel the server's observation loop.
     #[error("couldn't cancel the observer")]
     ObserverCancelFail,
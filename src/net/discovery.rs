@@ -0,0 +1,240 @@
+/*! LAN discovery of zombiesplit servers.
+
+A server can announce itself by broadcasting a small UDP datagram every few seconds; clients on the
+same network listen for these announcements to find servers without anyone typing in addresses.
+
+Each announcement is a single line of text:
+
+```text
+zombiesplit 0.1.0 192.168.1.5:1337 scd11/btg-sonic
+```
+
+giving, in order, a fixed magic word, the server's protocol version, the address of its `gRPC`
+endpoint, and the game/category it is running.  Clients ignore announcements from servers whose
+protocol version they can't speak.
+*/
+
+use std::{
+    fmt::{self, Display, Formatter},
+    net::SocketAddr,
+    str::FromStr,
+    time::Duration,
+};
+
+use thiserror::Error;
+use tokio::net::UdpSocket;
+
+use super::super::model::game::category::ShortDescriptor;
+
+/// An announcement of a server's presence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Announcement {
+    /// The protocol version the server speaks.
+    pub version: semver::Version,
+    /// The address of the server's `gRPC` endpoint.
+    pub address: SocketAddr,
+    /// The game/category the server is running.
+    pub target: ShortDescriptor,
+}
+
+impl Announcement {
+    /// Gets whether a client speaking protocol version `version` can talk to the announcing
+    /// server.
+    ///
+    /// Versions are compatible when they agree on the major version or, before 1.0, on the minor
+    /// version.
+    ///
+    /// ```
+    /// use zombiesplit::net::discovery::Announcement;
+    ///
+    /// let ann: Announcement = "zombiesplit 0.1.2 127.0.0.1:1337 scd11/btg-sonic".parse().unwrap();
+    /// assert!(ann.is_compatible(&semver::Version::new(0, 1, 0)));
+    /// assert!(!ann.is_compatible(&semver::Version::new(0, 2, 0)));
+    /// ```
+    #[must_use]
+    pub fn is_compatible(&self, version: &semver::Version) -> bool {
+        self.version.major == version.major
+            && (version.major != 0 || self.version.minor == version.minor)
+    }
+}
+
+impl Display for Announcement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{MAGIC} {} {} {}",
+            self.version, self.address, self.target
+        )
+    }
+}
+
+impl FromStr for Announcement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut fields = s.split_whitespace();
+        if fields.next() != Some(MAGIC) {
+            return Err(Error::NotAnAnnouncement);
+        }
+        let (version, address, target) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(version), Some(address), Some(target)) => (version, address, target),
+            _ => return Err(Error::Malformed(s.to_owned())),
+        };
+        let malformed = || Error::Malformed(s.to_owned());
+        Ok(Self {
+            version: version.parse().map_err(|_| malformed())?,
+            address: address.parse().map_err(|_| malformed())?,
+            target: target.parse().map_err(|_| malformed())?,
+        })
+    }
+}
+
+/// Broadcasts `announcement` to `destination` every `interval`, forever.
+///
+/// The interval can't be shorter than [MIN_INTERVAL], so that servers can't flood the network.
+/// Failing to send one announcement (for instance, because the network is briefly down) is
+/// logged, and announcing carries on at the next interval.
+///
+/// # Errors
+///
+/// Fails if the announcing socket can't be set up.
+pub async fn announce(
+    destination: SocketAddr,
+    announcement: &Announcement,
+    interval: Duration,
+) -> std::io::Result<()> {
+    let bind: SocketAddr = if destination.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind).await?;
+    socket.set_broadcast(true)?;
+
+    let message = announcement.to_string();
+    let mut ticker = tokio::time::interval(interval.max(MIN_INTERVAL));
+    loop {
+        ticker.tick().await;
+        if let Err(e) = socket.send_to(message.as_bytes(), destination).await {
+            log::warn!("couldn't announce server to {destination}: {e}");
+        }
+    }
+}
+
+/// Listens on `port` for `wait`, returning every distinct server announced in that time whose
+/// protocol version is compatible with `version`.
+///
+/// Servers that announce an unspecified address (because they listen on every interface) are
+/// reported at the address the announcement came from.
+///
+/// # Errors
+///
+/// Fails if the listening socket can't be set up, or receiving fails.
+pub async fn discover(
+    port: u16,
+    wait: Duration,
+    version: &semver::Version,
+) -> std::io::Result<Vec<Announcement>> {
+    let socket = UdpSocket::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+    let mut found: Vec<Announcement> = Vec::new();
+    let mut buf = [0; MAX_ANNOUNCEMENT_LEN];
+
+    let deadline = tokio::time::Instant::now() + wait;
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        let mut ann = match std::str::from_utf8(&buf[..len]).map(str::parse::<Announcement>) {
+            Ok(Ok(ann)) => ann,
+            _ => {
+                log::debug!("ignoring stray datagram from {from}");
+                continue;
+            }
+        };
+        if !ann.is_compatible(version) {
+            log::info!(
+                "ignoring server at {} with incompatible version {}",
+                ann.address,
+                ann.version
+            );
+            continue;
+        }
+        if ann.address.ip().is_unspecified() {
+            ann.address.set_ip(from.ip());
+        }
+        if !found.contains(&ann) {
+            found.push(ann);
+        }
+    }
+    Ok(found)
+}
+
+/// Errors that can occur when reading announcements.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The message wasn't a zombiesplit announcement at all.
+    #[error("not a zombiesplit announcement")]
+    NotAnAnnouncement,
+    /// The message looked like an announcement, but couldn't be parsed.
+    #[error("malformed announcement: {0:?}")]
+    Malformed(String),
+}
+
+/// Shorthand for results over announcement errors.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The word with which every announcement starts.
+const MAGIC: &str = "zombiesplit";
+
+/// The shortest interval between announcements.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The longest announcement we expect to receive, in bytes.
+const MAX_ANNOUNCEMENT_LEN: usize = 512;
+
+/// The default port on which servers announce themselves.
+pub const DEFAULT_PORT: u16 = 1338;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn announcement() -> Announcement {
+        Announcement {
+            version: semver::Version::new(0, 1, 0),
+            address: ([192, 168, 1, 5], 1337).into(),
+            target: ShortDescriptor::new("scd11", "btg-sonic"),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let ann = announcement();
+        assert_eq!(ann, ann.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn rejects_other_messages() {
+        assert!(matches!(
+            "hello 0.1.0 127.0.0.1:1337 scd11/btg-sonic".parse::<Announcement>(),
+            Err(Error::NotAnAnnouncement)
+        ));
+        assert!(matches!(
+            "zombiesplit 0.1.0 127.0.0.1:1337".parse::<Announcement>(),
+            Err(Error::Malformed(_))
+        ));
+    }
+
+    /// Major versions must always match, and minor versions must too before 1.0.
+    #[test]
+    fn compatibility() {
+        let ann = announcement();
+        assert!(ann.is_compatible(&semver::Version::new(0, 1, 9)));
+        assert!(!ann.is_compatible(&semver::Version::new(0, 2, 0)));
+        assert!(!ann.is_compatible(&semver::Version::new(1, 1, 0)));
+
+        let stable = Announcement {
+            version: semver::Version::new(1, 2, 0),
+            ..announcement()
+        };
+        assert!(stable.is_compatible(&semver::Version::new(1, 0, 0)));
+    }
+}
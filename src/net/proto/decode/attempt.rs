@@ -2,7 +2,7 @@
 
 use super::{
     super::{
-        super::super::model::{game, session, short, timing::time::human},
+        super::super::model::{game, session, short, timing::time},
         dump_response,
     },
     error::{Missing, Result},
@@ -12,11 +12,11 @@ use super::{
 ///
 /// # Errors
 ///
-/// Fails if the counts in the attempt information overflow `usize`s on this machine, or if any of
-/// the times supplied for the splits are ill-formed.
+/// Fails if the counts in the attempt information overflow `usize`s on this machine, if any of
+/// the times supplied for the splits are ill-formed, or if any of the short names are invalid.
 pub fn decode(run: &dump_response::Attempt) -> Result<session::Attempt> {
     Ok(session::Attempt {
-        category: target(Missing::AttemptInfo.require(run.game_category.as_ref())?),
+        category: target(Missing::AttemptInfo.require(run.game_category.as_ref())?)?,
         info: run
             .attempt_info
             .as_ref()
@@ -24,15 +24,23 @@ pub fn decode(run: &dump_response::Attempt) -> Result<session::Attempt> {
             .transpose()?
             .unwrap_or_default(),
         splits: splits(&run.splits)?,
+        // TODO(@MattWindsor91): time policies aren't yet carried over the wire.
+        time_policy: game::category::SplitTimePolicy::default(),
+        // TODO(@MattWindsor91): nor are rounding policies, or run metadata.
+        rounding: Default::default(),
+        metadata: Default::default(),
     })
 }
 
-fn target(info: &dump_response::attempt::Target) -> game::category::Target {
-    game::category::Target {
+fn target(info: &dump_response::attempt::Target) -> Result<game::category::Target> {
+    Ok(game::category::Target {
         game: info.game_name.clone(),
         category: info.category_name.clone(),
-        short: game::category::ShortDescriptor::new(&info.game_sid, &info.category_sid),
-    }
+        short: game::category::ShortDescriptor::new(
+            short::Name::new(&info.game_sid)?,
+            short::Name::new(&info.category_sid)?,
+        ),
+    })
 }
 
 fn splits(splits: &[dump_response::attempt::Split]) -> Result<session::split::Set> {
@@ -42,19 +50,27 @@ fn splits(splits: &[dump_response::attempt::Split]) -> Result<session::split::Se
 fn split(split: &dump_response::attempt::Split) -> Result<session::split::Split> {
     Ok(session::split::Split {
         info: game::Split {
-            short: short::Name::from(&split.sid),
+            short: short::Name::new(&split.sid)?,
             name: split.name.clone(),
             nickname: split.nickname.clone(),
+            // TODO(@MattWindsor91): markers aren't yet carried over the wire.
+            marker: None,
+            // TODO(@MattWindsor91): nor are tolerances.
+            tolerance: None,
         },
         times: times(split)?,
+        // TODO(@MattWindsor91): adjustments aren't yet carried over the wire, so a client sees
+        // only the entered times.
+        adjustment: time::Time::default(),
+        // TODO(@MattWindsor91): nor are merged splits.
+        merged: false,
     })
 }
 
-fn times(split: &dump_response::attempt::Split) -> Result<Vec<human::Time>> {
+fn times(split: &dump_response::attempt::Split) -> Result<Vec<time::Time>> {
     split
         .times
         .iter()
-        .copied()
-        .map(crate::model::timing::time::Time::from_millis)
+        .map(|t| Ok(time::Time::from_millis(i32::try_from(*t)?)))
         .collect()
 }
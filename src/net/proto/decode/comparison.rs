@@ -12,6 +12,8 @@ use std::collections::HashMap;
 /// Decodes a comparison.
 pub fn decode(cmp: &dump_response::Comparison) -> Result<timing::comparison::Comparison> {
     Ok(timing::comparison::Comparison {
+        // TODO(@MattWindsor91): send comparison names over the wire.
+        name: None,
         run: cmp.run.as_ref().map(run).transpose()?.unwrap_or_default(),
         splits: segments(&cmp.splits)?,
     })
@@ -35,7 +37,7 @@ fn segments(
 ) -> Result<short::Map<timing::comparison::Segment>> {
     segments
         .iter()
-        .map(|(sid, sp)| Ok((short::Name::from(sid), segment(sp)?)))
+        .map(|(sid, sp)| Ok((short::Name::new(sid)?, segment(sp)?)))
         .collect()
 }
 
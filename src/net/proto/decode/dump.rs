@@ -27,6 +27,18 @@ pub fn dump(dump: &DumpResponse) -> Result<session::State> {
             .unwrap_or_default(),
         notes: notes(&dump.notes)?,
         total: total(dump)?,
+        // TODO(@MattWindsor91): the current split isn't yet carried over the wire.
+        current: 0,
+        // TODO(@MattWindsor91): nor is whether the run is finished, or which split is being
+        // edited.
+        finished: false,
+        editing: None,
+        // TODO(@MattWindsor91): nor is the entry mode or last-split behaviour, though clients
+        // don't yet need them.
+        entry_mode: session::EntryMode::default(),
+        on_last_split: session::OnLastSplit::default(),
+        // TODO(@MattWindsor91): best segments aren't yet carried over the wire either.
+        best_segments: short::Map::new(),
     })
 }
 
@@ -35,7 +47,7 @@ fn notes(
 ) -> Result<short::Map<session::state::SplitNote>> {
     notes
         .iter()
-        .map(|(s, n)| Ok((short::Name::from(&s), note(n)?)))
+        .map(|(s, n)| Ok((short::Name::new(s)?, note(n)?)))
         .collect()
 }
 
@@ -50,7 +62,16 @@ fn note(note: &dump_response::SplitNote) -> Result<session::state::SplitNote> {
         .as_ref()
         .map(super::timing::split_delta)
         .transpose()?;
-    Ok(session::state::SplitNote { aggregates, delta })
+    Ok(session::state::SplitNote {
+        aggregates,
+        delta,
+        // TODO(@MattWindsor91): split-in-run paces, trends, segment paces, and pace buckets
+        // aren't yet carried over the wire.
+        pace: timing::comparison::pace::SplitInRun::default(),
+        trend: timing::comparison::pace::Trend::default(),
+        segment_pace: timing::comparison::Pace::default(),
+        bucket: None,
+    })
 }
 
 fn total(dump: &DumpResponse) -> Result<Option<timing::comparison::delta::Time>> {
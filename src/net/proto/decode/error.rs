@@ -1,6 +1,6 @@
 //! Decoding errors.
 
-use crate::model::timing::time;
+use crate::model::{short, timing::time};
 use thiserror::Error;
 
 /// Shorthand for results over [Error].
@@ -19,6 +19,9 @@ pub enum Error {
     /// Couldn't decode a time.
     #[error("couldn't decode time")]
     Time(#[from] time::Error),
+    /// Couldn't decode a short name.
+    #[error("couldn't decode short name")]
+    Short(#[from] short::Error),
     /// Something we needed was missing.
     #[error("missing data in response: {0:?}")]
     Missing(Missing),
@@ -33,6 +36,7 @@ impl From<Error> for tonic::Status {
         match e {
             Error::IndexConversion(_) => tonic::Status::out_of_range(e.to_string()),
             Error::Time(e) => adapt_time_error(e),
+            Error::Short(_) => tonic::Status::invalid_argument(e.to_string()),
             Error::Missing(_) => tonic::Status::data_loss(e.to_string()),
             Error::Unknown(_) => tonic::Status::out_of_range(e.to_string()),
         }
@@ -64,7 +64,7 @@ fn comparison_total_type_inner(
 }
 
 fn split(s: &event::Split) -> Result<session::Event> {
-    let sid = short::Name::from(&s.sid);
+    let sid = short::Name::new(&s.sid)?;
     let event = match Missing::SplitEvent.require(s.payload.as_ref())? {
         event::split::Payload::Time(t) => split_time(t)?,
         event::split::Payload::Delta(p) => split_delta(p)?,
@@ -12,11 +12,34 @@ use super::super::{
 /// Fails with `out_of_range` if any attempt counts cannot be stored as 64-bit integers.
 pub fn encode(event: &session::Event) -> super::Result<Event> {
     Ok(Event {
-        payload: Some(match event {
-            session::Event::Total(ty, time) => event::Payload::Total(total(*ty, *time)),
-            session::Event::Reset(info) => event::Payload::Reset(super::attempt_info(info)?),
-            session::Event::Split(sid, event) => event::Payload::Split(split(sid, event)),
-        }),
+        payload: payload(event)?,
+    })
+}
+
+/// Encodes the payload of an event.
+///
+/// Events that the protocol can't yet represent get an empty payload, which clients ignore.
+fn payload(event: &session::Event) -> super::Result<Option<event::Payload>> {
+    Ok(match event {
+        session::Event::Total(ty, time) => Some(event::Payload::Total(total(*ty, *time))),
+        session::Event::Reset(info) => Some(event::Payload::Reset(super::attempt_info(info)?)),
+        session::Event::Split(sid, event) => Some(event::Payload::Split(split(sid, event))),
+        session::Event::AddSplit(..)
+        | session::Event::MergeSplits(..)
+        | session::Event::CurrentSplit(_)
+        | session::Event::Target(_)
+        | session::Event::ComparisonName(_)
+        | session::Event::Overlay(..)
+        | session::Event::Ghost(_)
+        | session::Event::Editing(_)
+        | session::Event::Metadata(..)
+        | session::Event::Practice(_)
+        // TODO(@MattWindsor91): send these only to clients that ask for them.
+        | session::Event::Live(_)
+        | session::Event::Finish(_)
+        | session::Event::FinishPrompt
+        | session::Event::PaceCross { .. }
+        | session::Event::Error(_) => None,
     })
 }
 
@@ -50,16 +73,25 @@ fn comparison_total_type(ty: timing::comparison::run::TotalType) -> event::total
 fn split(sid: &short::Name, event: &session::event::Split) -> event::Split {
     event::Split {
         sid: sid.to_string(),
-        payload: Some(split_payload(event)),
+        payload: split_payload(event),
     }
 }
 
-fn split_payload(event: &session::event::Split) -> event::split::Payload {
+fn split_payload(event: &session::event::Split) -> Option<event::split::Payload> {
     use {event::split::Payload, session::event::Split};
     match event {
-        Split::Time(time, ty) => Payload::Time(split_time(*ty, *time)),
-        Split::Delta(delta) => Payload::Delta(super::timing::split_delta(delta)),
-        Split::Popped(ty) => Payload::Pop(super::pop(*ty)),
+        Split::Time(time, ty) => Some(Payload::Time(split_time(*ty, *time))),
+        Split::Delta(delta) => Some(Payload::Delta(super::timing::split_delta(delta))),
+        Split::Popped(ty) => Some(Payload::Pop(super::pop(*ty))),
+        // TODO(@MattWindsor91): trends, segment paces, timesaves, best segments, highlights, and
+        // adjustments aren't yet carried over the wire.
+        Split::Trend(_)
+        | Split::SegmentPace(_)
+        | Split::PaceBucket(_)
+        | Split::PossibleTimesave(_)
+        | Split::BestSegment(_)
+        | Split::Highlight(_)
+        | Split::Adjustment(_) => None,
     }
 }
 
@@ -30,6 +30,8 @@ fn split_in_run_pace(pace: timing::comparison::pace::SplitInRun) -> Pace {
         SplitInRun::AheadAndLosing => Pace::AheadButLosing,
         SplitInRun::AheadAndGaining => Pace::Ahead,
         SplitInRun::SplitPersonalBest => Pace::PersonalBest,
+        // TODO(@MattWindsor91): close calls aren't yet carried over the wire.
+        SplitInRun::Even => Pace::Ahead,
     }
 }
 
@@ -44,7 +46,8 @@ fn pace(pace: timing::comparison::Pace) -> Pace {
     match pace {
         timing::comparison::Pace::Inconclusive => Pace::None,
         timing::comparison::Pace::Behind => Pace::Behind,
-        timing::comparison::Pace::Ahead => Pace::Ahead,
+        // TODO(@MattWindsor91): close calls aren't yet carried over the wire.
+        timing::comparison::Pace::Even | timing::comparison::Pace::Ahead => Pace::Ahead,
         timing::comparison::Pace::PersonalBest => Pace::PersonalBest,
     }
 }
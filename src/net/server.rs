@@ -12,22 +12,34 @@ pub use error::{Error, Result};
 
 use crate::model::session::event::observer::{Observable, Observer};
 use crate::model::{
+    load::Loadable,
     session::{event::Event, sink},
-    timing::comparison::provider,
+    short,
+    timing::comparison::{
+        goal::Goal, provider, weighted, BalancedProvider, CompositeProvider, FileProvider,
+        GoalProvider, LatestRunProvider, WeightedProvider,
+    },
 };
 
-use super::super::{
-    config,
-    db::{self, inspect::Inspector},
-    model::{
-        self,
-        game::category::ShortDescriptor,
-        session::{self, action::Handler, event::Debug},
+use super::{
+    super::{
+        config,
+        db::{self, inspect::Inspector},
+        model::{
+            self,
+            game::category::ShortDescriptor,
+            session::{self, action::Handler, event::Debug},
+        },
     },
+    discovery,
 };
 
 mod error;
 mod grpc;
+pub mod metrics;
+pub mod shutdown;
+pub mod sound;
+pub mod trigger;
 
 /// A manager of a zombiesplit server.
 ///
@@ -59,6 +71,58 @@ pub struct Manager {
     //
     observers: Vec<Arc<dyn session::Observer>>,
     obs_mux: session::event::Mux,
+    /// Observers that the mux feeds through queues, on their own threads.
+    ///
+    /// These come after the mux so that, on drop, the mux drains its queues into them first.
+    queued_observers: Vec<Arc<dyn session::Observer + Send + Sync>>,
+    /// The metrics registry, if the metrics endpoint is enabled.
+    metrics: Option<Arc<metrics::Registry>>,
+}
+
+/// Adds or updates every game file in `dir` in the database.
+///
+/// Game files that fail to load are logged and skipped.
+fn sync_games(db: &db::Db, dir: &std::path::Path) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |e| e != "toml") {
+            continue;
+        }
+        let short = match path.file_stem().and_then(std::ffi::OsStr::to_str) {
+            Some(short) => short,
+            None => {
+                log::warn!("skipping game file {path:?}: can't derive a short name");
+                continue;
+            }
+        };
+        if let Err(e) = short::Name::new(short) {
+            log::warn!("skipping game file {path:?}: {e}");
+            continue;
+        }
+        match model::game::Config::load(&path) {
+            Ok(game) => {
+                let added = db.upsert_game(short, &game)?;
+                log::info!("{} game {short}", if added { "added" } else { "updated" });
+            }
+            Err(e) => log::warn!("skipping game file {path:?}: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Adds a sound player for `sounds` to `observers`, if there are any sounds to play.
+#[cfg_attr(not(feature = "sound"), allow(unused_variables))]
+fn push_sounds(
+    observers: &mut Vec<Arc<dyn session::Observer>>,
+    sounds: &config::server::sound::Sounds,
+) {
+    if sounds.is_empty() {
+        return;
+    }
+    #[cfg(feature = "sound")]
+    observers.push(Arc::new(sound::Player::new(sounds.clone())));
+    #[cfg(not(feature = "sound"))]
+    log::warn!("sounds are configured, but this build doesn't support playing them");
 }
 
 struct Broadcast(tokio::sync::broadcast::Sender<session::event::Event>);
@@ -75,9 +139,18 @@ impl Manager {
     ///
     /// # Errors
     ///
-    /// Returns any errors from trying to open the database.
+    /// Returns any errors from trying to open the database, or if the configured pace buckets don't
+    /// cover every delta.
     pub fn new(cfg: config::Server) -> Result<Self> {
+        cfg.pace_buckets.validate()?;
         let db = std::rc::Rc::new(db::Db::new(&cfg.db.path)?);
+        let migrated = db.migrate()?;
+        if 0 < migrated {
+            log::info!("applied {migrated} database migration(s)");
+        }
+        if let Some(dir) = &cfg.db.games {
+            sync_games(&db, dir)?;
+        }
         let reader = db.reader()?;
 
         let debug_obs: Arc<dyn session::Observer> = Arc::new(Debug);
@@ -85,18 +158,54 @@ impl Manager {
         let bcast = tokio::sync::broadcast::channel(BCAST_CAPACITY);
         let bcast_obs: Arc<dyn session::Observer> = Arc::new(Broadcast(bcast.0.clone()));
 
+        let mut observers = vec![debug_obs, bcast_obs];
+        let mut queued_observers: Vec<Arc<dyn session::Observer + Send + Sync>> = vec![];
+
+        let metrics = cfg
+            .metrics_address()
+            .map(|_| Arc::new(metrics::Registry::default()));
+        if let Some(ref registry) = metrics {
+            observers.push(registry.clone());
+        }
+
+        push_sounds(&mut observers, &cfg.sounds);
+        for obs in &cfg.observers {
+            match obs {
+                config::server::observer::Observer::File { path } => {
+                    queued_observers.push(Arc::new(session::event::File::open(path)?));
+                }
+                config::server::observer::Observer::Sounds { cues } => {
+                    push_sounds(&mut observers, cues);
+                }
+                // The metrics registry is set up above.
+                config::server::observer::Observer::Metrics { .. } => {}
+            }
+        }
+
         let mut m = Self {
             cfg,
             reader,
             bcast,
             sink: db::Sink::new(db),
-            observers: vec![debug_obs, bcast_obs],
+            observers,
             obs_mux: session::event::Mux::default(),
+            queued_observers,
+            metrics,
         };
 
         for obs in &m.observers {
             m.obs_mux.add_observer(Arc::downgrade(obs));
         }
+        // File writes can be slow, so they shouldn't hold up the session.
+        for obs in &m.queued_observers {
+            m.obs_mux.add_observer_with(
+                Arc::downgrade(obs),
+                session::event::mux::Dispatch::Queued {
+                    capacity: OBSERVER_QUEUE_CAPACITY,
+                    when_full: session::event::mux::WhenFull::Block,
+                },
+            );
+        }
 
         Ok(m)
     }
@@ -108,35 +217,211 @@ impl Manager {
     /// Returns any database or UI errors caught during the session.
     pub fn server(&self, desc: &ShortDescriptor) -> Result<Server> {
         let insp = self.reader.inspect(desc)?;
+        let mut session = self.session(insp)?;
+        for overlay in &self.cfg.comparison.overlays {
+            let insp = self.reader.inspect(desc)?;
+            session.add_overlay_provider(self.provider(*overlay, insp)?);
+        }
+        if let Some(ghost) = self.cfg.comparison.ghost {
+            let insp = self.reader.inspect(desc)?;
+            session.set_ghost_provider(self.provider(ghost, insp)?);
+        }
         let (message_send, message_recv) = tokio::sync::mpsc::channel(MPSC_CAPACITY);
         Ok(Server {
             addr: self.cfg.net.address,
+            metrics: self.cfg.metrics_address().zip(self.metrics.clone()),
+            trigger: self.cfg.trigger.address,
+            discovery: self.announcement(desc),
             handler: grpc::Handler {
                 message_send,
                 event_broadcast: self.bcast.0.clone(),
             },
             state: State {
-                session: self.session(insp)?,
+                session,
                 message_recv,
             },
         })
     }
 
-    fn session<'a, 'db>(
+    /// Gets the destination, interval, and content of LAN announcements for a server running
+    /// `desc`, if discovery is enabled.
+    fn announcement(
+        &self,
+        desc: &ShortDescriptor,
+    ) -> Option<(
+        std::net::SocketAddr,
+        std::time::Duration,
+        discovery::Announcement,
+    )> {
+        let cfg = &self.cfg.discovery;
+        cfg.address.map(|dest| {
+            let announcement = discovery::Announcement {
+                version: SERVER_VERSION,
+                address: self.cfg.net.address,
+                target: *desc,
+            };
+            (dest, cfg.interval(), announcement)
+        })
+    }
+
+    /// Shuts down the manager, consuming it.
+    ///
+    /// This waits for queued observers to see every event already sent to them, then closes the
+    /// database.  Any server made by this manager should already have stopped running.
+    pub fn shutdown(mut self) {
+        self.obs_mux.shutdown();
+    }
+
+    fn session<'a>(
         &'a self,
-        mut insp: Inspector<'db>,
-    ) -> Result<session::Session<'db, 'a, model::session::event::Mux>> {
+        mut insp: Inspector<'a>,
+    ) -> Result<session::Session<'a, 'a, model::session::event::Mux>> {
+        let target = insp.info.info.short;
         let mut session = insp.init_session(&self.obs_mux)?;
-        session.set_comparison_provider(self.comparison_provider(insp));
+        session.set_best_segments(insp.best_segments()?);
+        session.set_comparison_provider(self.comparison_provider(insp)?);
         session.set_sink(self.sink());
+        session.set_entry_mode(self.cfg.entry_mode);
+        session.set_on_last_split(self.cfg.on_last_split);
+        session.set_tolerance(self.cfg.comparison.tolerance.unwrap_or_default());
+        session.set_pace_buckets(self.cfg.pace_buckets.clone());
+        if let Some(dir) = &self.cfg.export_dir {
+            session.set_export_dir(dir);
+        }
+        if let Some(dir) = &self.cfg.checkpoint.dir {
+            let checkpoint = session::checkpoint::File::new(dir, &target);
+            self.resume_checkpoint(&mut session, &checkpoint)?;
+            session.set_checkpoint(Box::new(checkpoint));
+        }
         Ok(session)
     }
 
-    fn comparison_provider<'a>(&self, insp: Inspector<'a>) -> Box<dyn provider::Provider + 'a> {
-        match self.cfg.comparison.provider {
-            config::server::comparison::Provider::Database => Box::new(insp),
+    /// Resumes the run in `checkpoint`, if there is one and we've been asked to resume it.
+    fn resume_checkpoint(
+        &self,
+        session: &mut session::Session<model::session::event::Mux>,
+        checkpoint: &session::checkpoint::File,
+    ) -> Result<()> {
+        if let Some(run) = checkpoint.load()? {
+            let path = checkpoint.path().display();
+            if self.cfg.checkpoint.resume {
+                log::info!("resuming run from checkpoint {path}");
+                session.resume(&run)?;
+            } else {
+                log::warn!("found a checkpoint at {path}; it will be overwritten unless resumed");
+            }
+        }
+        Ok(())
+    }
+
+    fn comparison_provider<'a>(
+        &'a self,
+        mut insp: Inspector<'a>,
+    ) -> Result<Box<dyn provider::Provider + 'a>> {
+        let configured = match self.cfg.comparison.provider {
+            Some(provider) => provider,
+            None => insp
+                .cat
+                .preferred_comparison(&insp.info.id)?
+                .map(Into::into)
+                .unwrap_or_default(),
+        };
+        self.provider(configured, insp)
+    }
+
+    /// Makes the comparison provider `kind`, drawing on the database through `insp`.
+    fn provider<'a>(
+        &'a self,
+        kind: config::server::comparison::Provider,
+        mut insp: Inspector<'a>,
+    ) -> Result<Box<dyn provider::Provider + 'a>> {
+        Ok(match kind {
+            config::server::comparison::Provider::Database => Box::new(provider::Cached::new(insp)),
+            config::server::comparison::Provider::Balanced => {
+                let order = Self::split_order(&mut insp)?;
+                Box::new(provider::Cached::new(BalancedProvider::new(insp, order)))
+            }
+            config::server::comparison::Provider::Latest => {
+                let order = Self::split_order(&mut insp)?;
+                Box::new(provider::Cached::new(LatestRunProvider::new(insp, order)))
+            }
+            config::server::comparison::Provider::Weighted => {
+                let order = Self::split_order(&mut insp)?;
+                let half_life = self
+                    .cfg
+                    .comparison
+                    .half_life
+                    .unwrap_or(weighted::DEFAULT_HALF_LIFE);
+                let decay = weighted::decay_for_half_life(half_life);
+                Box::new(provider::Cached::new(WeightedProvider::new(
+                    insp, order, decay,
+                )))
+            }
+            config::server::comparison::Provider::Goal => Box::new(self.goal_provider(&mut insp)?),
+            config::server::comparison::Provider::File => Box::new(self.file_provider(&mut insp)?),
+            config::server::comparison::Provider::Composite => {
+                Box::new(self.composite_provider(&mut insp)?)
+            }
             _ => Box::new(provider::Null),
+        })
+    }
+
+    fn goal_provider(&self, insp: &mut Inspector) -> Result<GoalProvider> {
+        let path = self
+            .cfg
+            .comparison
+            .goal
+            .as_ref()
+            .ok_or(Error::MissingGoal)?;
+        let goal = Goal::from_toml_file(path)?;
+        Ok(GoalProvider::new(&goal, Self::split_order(insp)?))
+    }
+
+    /// Makes a composite provider over the configured composite providers, each drawing on the
+    /// database through its own inspector for the same game-category as `insp`.
+    fn composite_provider<'a>(&'a self, insp: &mut Inspector) -> Result<CompositeProvider<'a>> {
+        let kinds = &self.cfg.comparison.composite;
+        if kinds.is_empty() {
+            return Err(Error::MissingCompositeSources);
         }
+        let mut sources = Vec::with_capacity(kinds.len());
+        for kind in kinds {
+            if *kind == config::server::comparison::Provider::Composite {
+                return Err(Error::NestedComposite);
+            }
+            sources.push(self.provider(*kind, self.reader.inspect(&insp.info)?)?);
+        }
+        Ok(CompositeProvider::new(sources, Self::split_order(insp)?))
+    }
+
+    fn file_provider(&self, insp: &mut Inspector) -> Result<FileProvider> {
+        let path = self
+            .cfg
+            .comparison
+            .file
+            .as_ref()
+            .ok_or(Error::MissingComparisonFile)?;
+        let provider = FileProvider::load(path)?;
+
+        let order = Self::split_order(insp)?;
+        if !provider.overlaps(&order) {
+            return Err(Error::DisjointComparisonFile(path.clone()));
+        }
+        let unknown = provider.unknown_splits(&order);
+        if !unknown.is_empty() {
+            let unknown: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+            log::warn!(
+                "comparison file {path:?} has splits not in the category: {}",
+                unknown.join(", ")
+            );
+        }
+        Ok(provider)
+    }
+
+    /// Gets the short names of the splits in the category being inspected by `insp`, in order.
+    fn split_order(insp: &mut Inspector) -> Result<Vec<short::Name>> {
+        let attempt = insp.cat.run(&insp.info)?;
+        Ok(attempt.splits.iter().map(|s| s.info.short).collect())
     }
 
     fn sink(&self) -> Box<dyn sink::Sink> {
@@ -162,17 +447,52 @@ impl Observable for Manager {
 /// The lifetime `m` generally reflects that of its underlying `Manager`.
 pub struct Server<'m> {
     addr: std::net::SocketAddr,
+    /// The metrics endpoint address and registry, if the endpoint is enabled.
+    metrics: Option<(std::net::SocketAddr, Arc<metrics::Registry>)>,
+    /// The trigger endpoint address, if the endpoint is enabled.
+    trigger: Option<std::net::SocketAddr>,
+    /// The destination, interval, and content of LAN announcements, if discovery is enabled.
+    discovery: Option<(
+        std::net::SocketAddr,
+        std::time::Duration,
+        discovery::Announcement,
+    )>,
     handler: grpc::Handler,
     state: State<'m>,
 }
 
 impl<'cmp> Server<'cmp> {
     /// Runs the server, consuming it.
+    ///
+    /// The server runs for as long as its handler can send it messages; see [Self::run_until] to
+    /// stop it sooner.
     pub async fn run(self) {
+        self.run_until(std::future::pending()).await;
+    }
+
+    /// Runs the server until `shutdown` completes, consuming it.
+    ///
+    /// On shutdown, the session checkpoints any run in progress (see
+    /// [session::Session::shutdown]).  To finish shutting down, the manager should then be shut
+    /// down with [Manager::shutdown].
+    pub async fn run_until(self, shutdown: impl std::future::Future<Output = ()>) {
+        if let Some(addr) = self.trigger {
+            tokio::spawn(run_trigger(addr, self.handler.message_send.clone()));
+        }
         tokio::spawn(run_grpc(self.addr, self.handler));
+        if let Some((addr, registry)) = self.metrics {
+            tokio::spawn(run_metrics(addr, registry));
+        }
+        if let Some((dest, interval, announcement)) = self.discovery {
+            tokio::spawn(run_discovery(dest, interval, announcement));
+        }
 
         let mut state = self.state;
-        state.run().await;
+        tokio::select! {
+            () = state.run() => {}
+            () = shutdown => {}
+        }
+        state.session.shutdown();
     }
 }
 
@@ -189,6 +509,28 @@ async fn run_grpc(addr: std::net::SocketAddr, handler: grpc::Handler) {
     }
 }
 
+async fn run_metrics(addr: std::net::SocketAddr, registry: Arc<metrics::Registry>) {
+    if let Err(e) = metrics::serve(addr, registry).await {
+        log::error!("error in metrics endpoint: {e}");
+    }
+}
+
+async fn run_trigger(addr: std::net::SocketAddr, send: mpsc::Sender<Message>) {
+    if let Err(e) = trigger::serve(addr, send).await {
+        log::error!("error in trigger endpoint: {e}");
+    }
+}
+
+async fn run_discovery(
+    dest: std::net::SocketAddr,
+    interval: std::time::Duration,
+    announcement: discovery::Announcement,
+) {
+    if let Err(e) = discovery::announce(dest, &announcement, interval).await {
+        log::error!("error announcing server: {e}");
+    }
+}
+
 /// The state part of the server.
 struct State<'m> {
     /// The session being wrapped by this server.
@@ -212,19 +554,41 @@ impl<'m> State<'m> {
     /// Runs the state main loop, which constantly drains messages from clients and applies them.
     ///
     /// These messages, in turn, give rise to observations that will bubble up through the broadcast
-    /// channel and into clients.
+    /// channel and into clients.  Between messages, the loop also ticks the session every
+    /// [TICK_INTERVAL].
     async fn run(&mut self) {
-        while let Some(msg) = self.message_recv.recv().await {
-            match msg {
-                Message::Action(act) => infallible(self.session.handle(act)),
-                Message::Dump(rx) => {
-                    // TODO(@MattWindsor91): handle drop?
-                    let _res = rx.send(infallible(self.session.dump()));
+        let mut ticks = tokio::time::interval(TICK_INTERVAL);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                msg = self.message_recv.recv() => match msg {
+                    Some(msg) => self.handle(msg),
+                    None => break,
+                },
+                _ = ticks.tick() => self.session.tick(),
+            }
+        }
+    }
+
+    /// Handles a single message from a client.
+    fn handle(&mut self, msg: Message) {
+        match msg {
+            Message::Action(act) => {
+                // Clients hear about errors through the observers, so we just log them here.
+                if let Err(e) = self.session.handle(act.clone()) {
+                    log::warn!("couldn't perform {act:?}: {e}");
                 }
-                Message::ServerInfo(rx) => {
-                    // TODO(@MattWindsor91): handle drop?
-                    let _res = rx.send(info());
+            }
+            Message::Dump(rx) => match self.session.dump() {
+                // TODO(@MattWindsor91): handle drop?
+                Ok(state) => {
+                    let _res = rx.send(state);
                 }
+                Err(e) => log::error!("couldn't dump session: {e}"),
+            },
+            Message::ServerInfo(rx) => {
+                // TODO(@MattWindsor91): handle drop?
+                let _res = rx.send(info());
             }
         }
     }
@@ -237,15 +601,9 @@ fn info() -> super::metadata::Server {
     }
 }
 
-fn infallible<T>(x: std::result::Result<T, std::convert::Infallible>) -> T {
-    match x {
-        Ok(t) => t,
-        Err(e) => match e {},
-    }
-}
-
 const SERVER_IDENT: &str = "zsserver";
-const SERVER_VERSION: semver::Version = semver::Version::new(0, 1, 0);
+/// The protocol version this server speaks.
+pub const SERVER_VERSION: semver::Version = semver::Version::new(0, 1, 0);
 
 // TODO(@MattWindsor91): https://github.com/MattWindsor91/zombiesplit/issues/23
 
@@ -254,3 +612,9 @@ const BCAST_CAPACITY: usize = 100;
 
 /// Number of actions for which we reserve space in the MPSC channel.
 const MPSC_CAPACITY: usize = 16;
+
+/// Number of events each queued observer can fall behind before the session waits for it.
+const OBSERVER_QUEUE_CAPACITY: usize = 64;
+
+/// How often the server ticks the session, moving any ghost.
+const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
@@ -9,6 +9,26 @@ pub enum Error {
     Db(#[from] crate::db::Error),
     #[error("i/o error")]
     IO(#[from] std::io::Error),
+    #[error("couldn't load file")]
+    Load(#[from] crate::model::load::Error),
+    #[error("couldn't get comparison")]
+    Comparison(#[from] crate::model::timing::comparison::provider::Error),
+    #[error("couldn't load checkpoint")]
+    Checkpoint(#[from] crate::model::session::checkpoint::Error),
+    #[error("couldn't resume checkpoint")]
+    Resume(#[from] crate::model::Error),
+    #[error("bad pace buckets")]
+    PaceBuckets(#[from] crate::model::timing::comparison::pace::bucket::Error),
+    #[error("the goal comparison provider needs a goal file")]
+    MissingGoal,
+    #[error("the file comparison provider needs a comparison file")]
+    MissingComparisonFile,
+    #[error("the composite comparison provider needs at least one provider to merge")]
+    MissingCompositeSources,
+    #[error("the composite comparison provider can't merge composite providers")]
+    NestedComposite,
+    #[error("comparison file {0:?} has no splits in common with the category")]
+    DisjointComparisonFile(std::path::PathBuf),
     #[error("couldn't join task")]
     Join(#[from] tokio::task::JoinError),
     #[error("couldn't send action to session")]
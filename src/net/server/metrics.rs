@@ -0,0 +1,231 @@
+/*! Metrics endpoint for the server.
+
+The metrics endpoint is a minimal HTTP responder that serves the current run's totals, split
+times, and attempt counts in the Prometheus text exposition format.  It gets its information by
+observing the session, in the same way as any other client.
+*/
+
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::model::{
+    game::category,
+    session::{self, event},
+    short,
+    timing::{aggregate, comparison, time},
+};
+
+/// An observer that keeps track of the metrics exposed by the endpoint.
+#[derive(Default)]
+pub struct Registry {
+    metrics: Mutex<Metrics>,
+}
+
+/// The metrics themselves.
+#[derive(Default)]
+struct Metrics {
+    /// Attempt counts.
+    attempts: category::AttemptInfo,
+    /// The total time of the current attempt, if any.
+    attempt_total: Option<time::Time>,
+    /// The comparison totals.
+    comparison_totals: BTreeMap<&'static str, time::Time>,
+    /// Aggregate times for each split.
+    splits: BTreeMap<(String, &'static str, &'static str), time::Time>,
+}
+
+impl session::Observer for Registry {
+    fn observe(&self, evt: session::Event) {
+        match self.metrics.lock() {
+            Ok(mut metrics) => metrics.observe(evt),
+            Err(e) => log::error!("couldn't lock metrics registry: {e}"),
+        }
+    }
+}
+
+impl Registry {
+    /// Renders the current metrics in the Prometheus text exposition format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the registry's lock has been poisoned.
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.metrics
+            .lock()
+            .expect("metrics registry lock poisoned")
+            .render()
+    }
+}
+
+impl Metrics {
+    fn observe(&mut self, evt: session::Event) {
+        match evt {
+            session::Event::Reset(info) => {
+                self.attempts = info;
+                self.attempt_total = None;
+                self.splits.retain(|(_, source, _), _| *source != "attempt");
+            }
+            session::Event::Total(event::Total::Attempt(_), time) => self.attempt_total = time,
+            session::Event::Total(event::Total::Comparison(ty), time) => {
+                let ty = comparison_total_label(ty);
+                if let Some(time) = time {
+                    self.comparison_totals.insert(ty, time);
+                } else {
+                    self.comparison_totals.remove(ty);
+                }
+            }
+            session::Event::Split(
+                split,
+                event::Split::Time(time, event::Time::Aggregate(kind)),
+            ) => {
+                self.splits.insert(split_key(split, kind), time);
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        // Writing to a string can't fail.
+        let _ = self.render_to(&mut out);
+        out
+    }
+
+    fn render_to(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "# HELP zombiesplit_attempts Number of runs stored.")?;
+        writeln!(out, "# TYPE zombiesplit_attempts gauge")?;
+        writeln!(out, "zombiesplit_attempts {}", self.attempts.total)?;
+        writeln!(
+            out,
+            "# HELP zombiesplit_attempts_completed Number of runs stored and completed."
+        )?;
+        writeln!(out, "# TYPE zombiesplit_attempts_completed gauge")?;
+        writeln!(
+            out,
+            "zombiesplit_attempts_completed {}",
+            self.attempts.completed
+        )?;
+
+        writeln!(
+            out,
+            "# HELP zombiesplit_total_ms Run totals, in milliseconds."
+        )?;
+        writeln!(out, "# TYPE zombiesplit_total_ms gauge")?;
+        if let Some(total) = self.attempt_total {
+            writeln!(
+                out,
+                "zombiesplit_total_ms{{source=\"attempt\"}} {}",
+                total.into_millis()
+            )?;
+        }
+        for (ty, total) in &self.comparison_totals {
+            writeln!(
+                out,
+                "zombiesplit_total_ms{{source=\"comparison\",type=\"{ty}\"}} {}",
+                total.into_millis()
+            )?;
+        }
+
+        writeln!(
+            out,
+            "# HELP zombiesplit_split_ms Split aggregate times, in milliseconds."
+        )?;
+        writeln!(out, "# TYPE zombiesplit_split_ms gauge")?;
+        for ((split, source, scope), time) in &self.splits {
+            writeln!(
+                out,
+                "zombiesplit_split_ms{{split=\"{split}\",source=\"{source}\",scope=\"{scope}\"}} {}",
+                time.into_millis()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn split_key(split: short::Name, kind: aggregate::Kind) -> (String, &'static str, &'static str) {
+    let source = match kind.source {
+        aggregate::Source::Attempt => "attempt",
+        aggregate::Source::Comparison => "comparison",
+    };
+    let scope = match kind.scope {
+        aggregate::Scope::Split => "split",
+        aggregate::Scope::Cumulative => "cumulative",
+    };
+    (split.to_string(), source, scope)
+}
+
+fn comparison_total_label(ty: comparison::run::TotalType) -> &'static str {
+    match ty {
+        comparison::run::TotalType::TotalInPbRun => "total-in-pb-run",
+        comparison::run::TotalType::SumOfBest => "sum-of-best",
+    }
+}
+
+/// Serves the metrics in `registry` over HTTP on `addr`, until the server shuts down.
+///
+/// Every request gets the metrics in response, regardless of its method or path.
+///
+/// # Errors
+///
+/// Fails if we can't bind to `addr`.  Errors on individual connections are logged instead.
+pub async fn serve(
+    addr: std::net::SocketAddr,
+    registry: std::sync::Arc<Registry>,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    log::info!("serving metrics on {addr}");
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &registry).await {
+                log::warn!("couldn't serve metrics to {peer}: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: tokio::net::TcpStream, registry: &Registry) -> std::io::Result<()> {
+    // We don't care what the request is, but we should consume its head before responding.
+    let mut buf = [0; 1024];
+    let _ = stream.read(&mut buf).await?;
+
+    let body = registry.render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::session::Observer;
+
+    #[test]
+    fn render_after_events() {
+        let registry = Registry::default();
+        registry.observe(session::Event::Reset(category::AttemptInfo {
+            total: 4,
+            completed: 2,
+        }));
+        registry.observe(session::Event::Split(
+            short::Name::from("pp1"),
+            event::Split::Time(
+                time::Time::from_millis(25_060),
+                event::Time::Aggregate(aggregate::Kind::ATTEMPT_SPLIT),
+            ),
+        ));
+
+        let out = registry.render();
+        assert!(out.contains("zombiesplit_attempts 4\n"));
+        assert!(out.contains("zombiesplit_attempts_completed 2\n"));
+        assert!(out.contains(
+            "zombiesplit_split_ms{split=\"pp1\",source=\"attempt\",scope=\"split\"} 25060\n"
+        ));
+    }
+}
@@ -0,0 +1,63 @@
+/*! Signals asking the server to shut down.
+
+The server shuts down gracefully on the first interrupt (`SIGINT`, as from Ctrl-C) or, on Unix,
+terminate (`SIGTERM`) signal: it checkpoints the run in progress, lets queued observers catch up,
+and closes the database.  Shutting down shouldn't take long, but in case it stalls, a second signal
+or a timeout makes the process exit straight away.
+*/
+
+use std::time::Duration;
+
+/// Waits until the process is asked to quit.
+///
+/// Once it is, this sets up a watchdog that exits the process if it is asked to quit again, or if
+/// `timeout` passes, before the process gets round to exiting by itself.
+pub async fn signal(timeout: Duration) {
+    requested().await;
+    log::info!("shutting down; interrupt again to quit immediately");
+    tokio::spawn(async move {
+        tokio::select! {
+            () = requested() => log::warn!("quitting before shutdown finished"),
+            () = tokio::time::sleep(timeout) => log::error!("shutdown timed out, quitting"),
+        }
+        std::process::exit(1);
+    });
+}
+
+/// Waits for an interrupt or terminate signal.
+async fn requested() {
+    tokio::select! {
+        () = interrupt() => {}
+        () = terminate() => {}
+    }
+}
+
+async fn interrupt() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        // Without a handler, we'd never hear about the signal anyway.
+        log::error!("couldn't listen for interrupts: {e}");
+        std::future::pending::<()>().await;
+    }
+}
+
+#[cfg(unix)]
+async fn terminate() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut sig) => {
+            sig.recv().await;
+        }
+        Err(e) => {
+            log::error!("couldn't listen for terminate signals: {e}");
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn terminate() {
+    std::future::pending::<()>().await;
+}
+
+/// The default time the server has to shut down before the process exits anyway.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -0,0 +1,256 @@
+/*! Sound cues for the server.
+
+The sound observer plays a sound file whenever the session does something worth hearing about,
+such as getting a gold split or finishing ahead of the comparison; which files play for which
+events comes from the `[sounds]` section of the server configuration.
+
+Working out which cue an event triggers is always available, but actually playing sounds needs
+zombiesplit to be built with the `sound` feature, which uses SDL2_mixer.  Playback happens on its
+own thread, so loading and mixing sounds never holds up the session.
+*/
+
+use std::sync::Mutex;
+
+use crate::{
+    config::server::sound::Cue,
+    model::{
+        session::{self, event},
+        short,
+        timing::{comparison::run::TotalType, time},
+    },
+};
+
+/// Tracks enough of the session to work out which events trigger which cues.
+#[derive(Debug, Default)]
+struct Tracker {
+    /// The total time of the comparison run, if there is one.
+    comparison_total: Option<time::Time>,
+    /// The split that has just had a time pushed, if the live summary hasn't covered it yet.
+    ///
+    /// The session re-sends its live summary whenever the run changes, so a gold in the summary
+    /// is only news just after a push to that split.
+    pushed: Option<short::Name>,
+}
+
+impl Tracker {
+    /// Observes `evt`, returning the cue it triggers (if any).
+    fn cue(&mut self, evt: &session::Event) -> Option<Cue> {
+        match evt {
+            session::Event::Total(event::Total::Comparison(TotalType::TotalInPbRun), time) => {
+                self.comparison_total = *time;
+                None
+            }
+            session::Event::Reset(_) => {
+                self.pushed = None;
+                None
+            }
+            session::Event::Split(short, event::Split::Time(_, event::Time::Pushed)) => {
+                self.pushed = Some(*short);
+                None
+            }
+            session::Event::Live(live) if live.split.is_some() && live.split == self.pushed => {
+                self.pushed = None;
+                if live.is_gold {
+                    Some(Cue::GoldSplit)
+                } else {
+                    None
+                }
+            }
+            session::Event::PaceCross { ahead: true } => Some(Cue::PaceAhead),
+            session::Event::PaceCross { ahead: false } => Some(Cue::PaceBehind),
+            session::Event::Finish(time) => self
+                .comparison_total
+                .filter(|total| time < total)
+                .map(|_| Cue::PersonalBest),
+            _ => None,
+        }
+    }
+}
+
+/// An observer that plays the configured sound for each cue.
+///
+/// Events whose cues have no sound configured are ignored.
+pub struct Player {
+    /// Tracker for working out cues.
+    tracker: Mutex<Tracker>,
+    /// The configured sounds.
+    sounds: crate::config::server::sound::Sounds,
+    /// Sends paths of sounds to the playback thread.
+    #[cfg(feature = "sound")]
+    send: Mutex<std::sync::mpsc::Sender<std::path::PathBuf>>,
+}
+
+impl Player {
+    /// Constructs a player for `sounds`, starting its playback thread.
+    #[cfg(feature = "sound")]
+    #[must_use]
+    pub fn new(sounds: crate::config::server::sound::Sounds) -> Self {
+        let (send, recv) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if let Err(e) = playback::run(&recv) {
+                log::error!("couldn't start sound playback: {e}");
+            }
+        });
+        Self {
+            tracker: Mutex::default(),
+            sounds,
+            send: Mutex::new(send),
+        }
+    }
+
+    fn play(&self, cue: Cue) {
+        if let Some(path) = self.sounds.get(&cue) {
+            log::debug!("playing {cue:?} sound {path:?}");
+            #[cfg(feature = "sound")]
+            match self.send.lock() {
+                Ok(send) => {
+                    if send.send(path.clone()).is_err() {
+                        log::warn!("sound playback has stopped; not playing {path:?}");
+                    }
+                }
+                Err(e) => log::error!("couldn't lock sound player: {e}"),
+            }
+        }
+    }
+}
+
+impl session::Observer for Player {
+    fn observe(&self, evt: session::Event) {
+        let cue = match self.tracker.lock() {
+            Ok(mut tracker) => tracker.cue(&evt),
+            Err(e) => {
+                log::error!("couldn't lock sound cue tracker: {e}");
+                return;
+            }
+        };
+        if let Some(cue) = cue {
+            self.play(cue);
+        }
+    }
+}
+
+#[cfg(feature = "sound")]
+mod playback {
+    use std::{collections::HashMap, path::PathBuf, sync::mpsc};
+
+    use sdl2::mixer;
+
+    /// Size of each chunk the mixer processes, in samples.
+    ///
+    /// Smaller chunks mean less latency between an event and its sound.
+    const CHUNK_SIZE: i32 = 1024;
+
+    /// Number of sounds that can play at once.
+    const CHANNELS: i32 = 8;
+
+    /// Plays every sound whose path arrives on `recv`, until the sending side closes.
+    ///
+    /// Sounds are loaded the first time they are played, and kept for later.  Sounds that fail to
+    /// load or play are logged and skipped.
+    pub(super) fn run(recv: &mpsc::Receiver<PathBuf>) -> Result<(), String> {
+        let sdl = sdl2::init()?;
+        let _audio = sdl.audio()?;
+        mixer::open_audio(
+            mixer::DEFAULT_FREQUENCY,
+            mixer::DEFAULT_FORMAT,
+            mixer::DEFAULT_CHANNELS,
+            CHUNK_SIZE,
+        )?;
+        let _mixer = mixer::init(mixer::InitFlag::OGG | mixer::InitFlag::FLAC)?;
+        mixer::allocate_channels(CHANNELS);
+
+        let mut chunks = HashMap::new();
+        for path in recv {
+            if !chunks.contains_key(&path) {
+                match mixer::Chunk::from_file(&path) {
+                    Ok(chunk) => {
+                        chunks.insert(path.clone(), chunk);
+                    }
+                    Err(e) => {
+                        log::warn!("couldn't load sound {path:?}: {e}");
+                        continue;
+                    }
+                }
+            }
+            // If every channel is busy, we'd rather drop this sound than wait for one.
+            if let Err(e) = mixer::Channel::all().play(&chunks[&path], 0) {
+                log::warn!("couldn't play sound {path:?}: {e}");
+            }
+        }
+
+        mixer::close_audio();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that finishing gives a PB cue only when it beats the comparison total.
+    #[test]
+    fn personal_best_cue() {
+        let t = time::Time::from_millis;
+        let mut tracker = Tracker::default();
+
+        assert_eq!(None, tracker.cue(&session::Event::Finish(t(1000))));
+
+        tracker.cue(&session::Event::Total(
+            event::Total::Comparison(TotalType::TotalInPbRun),
+            Some(t(2000)),
+        ));
+        assert_eq!(None, tracker.cue(&session::Event::Finish(t(3000))));
+        assert_eq!(
+            Some(Cue::PersonalBest),
+            tracker.cue(&session::Event::Finish(t(1000)))
+        );
+    }
+
+    /// Tests that gold splits cue once, when pushed, and not when later changes resend them.
+    #[test]
+    fn gold_split_cue() {
+        let pushed = |name| {
+            session::Event::Split(
+                short::Name::from(name),
+                event::Split::Time(time::Time::from_millis(1000), event::Time::Pushed),
+            )
+        };
+        let live = |name, is_gold| {
+            session::Event::Live(session::state::Live {
+                split: Some(short::Name::from(name)),
+                delta: None,
+                is_gold,
+            })
+        };
+
+        let mut tracker = Tracker::default();
+        assert_eq!(None, tracker.cue(&live("pp1", true)), "nothing was pushed");
+
+        tracker.cue(&pushed("pp1"));
+        assert_eq!(Some(Cue::GoldSplit), tracker.cue(&live("pp1", true)));
+        assert_eq!(
+            None,
+            tracker.cue(&live("pp1", true)),
+            "pp1's gold is old news"
+        );
+
+        tracker.cue(&pushed("pp2"));
+        assert_eq!(None, tracker.cue(&live("pp2", false)));
+        tracker.cue(&pushed("pp2"));
+        assert_eq!(Some(Cue::GoldSplit), tracker.cue(&live("pp2", true)));
+    }
+
+    /// Tests that pace crosses give the appropriate cues.
+    #[test]
+    fn pace_cross_cues() {
+        let mut tracker = Tracker::default();
+        assert_eq!(
+            Some(Cue::PaceAhead),
+            tracker.cue(&session::Event::PaceCross { ahead: true })
+        );
+        assert_eq!(
+            Some(Cue::PaceBehind),
+            tracker.cue(&session::Event::PaceCross { ahead: false })
+        );
+    }
+}
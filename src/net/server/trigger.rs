@@ -0,0 +1,97 @@
+/*! Trigger endpoint for the server.
+
+The trigger endpoint lets external tools (autosplitters, streamdeck buttons, and so on) drive the
+session's current split without speaking `gRPC`.  Tools connect over TCP and send one command per
+line:
+
+- `split TIME` pushes `TIME` (in the usual time syntax, such as `1m23s456`) to the current split,
+  then advances to the next split (see [session::Action::Split]);
+- `split-reset` undoes the most recent split (see [session::Action::SplitReset]).
+
+The endpoint answers each command with `ok` once it has passed the command to the session, or
+with `error: ` followed by a reason.  As with any other action, the session reports any problem
+carrying out the command to its observers.
+*/
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::mpsc,
+};
+
+use crate::model::{session, timing::time};
+
+/// Serves the trigger endpoint on `addr`, sending actions to the session through `send`.
+///
+/// # Errors
+///
+/// Fails if we can't bind to `addr`.  Errors on individual connections are logged instead.
+pub async fn serve(
+    addr: std::net::SocketAddr,
+    send: mpsc::Sender<super::Message>,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    log::info!("serving triggers on {addr}");
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let send = send.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &send).await {
+                log::warn!("couldn't serve triggers to {peer}: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(
+    stream: tokio::net::TcpStream,
+    send: &mpsc::Sender<super::Message>,
+) -> std::io::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    while let Some(line) = lines.next_line().await? {
+        let reply = match parse(&line) {
+            Ok(action) => match send.send(super::Message::Action(action)).await {
+                Ok(()) => "ok".to_owned(),
+                Err(e) => format!("error: {e}"),
+            },
+            Err(e) => format!("error: {e}"),
+        };
+        write.write_all(format!("{reply}\n").as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Parses a trigger command into the action it stands for.
+///
+/// # Errors
+///
+/// Fails if the command isn't one we know, or its time doesn't parse.
+fn parse(line: &str) -> std::result::Result<session::Action, String> {
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("split"), Some(t), None) => t
+            .parse::<time::Time>()
+            .map(session::Action::Split)
+            .map_err(|e| e.to_string()),
+        (Some("split-reset"), None, None) => Ok(session::Action::SplitReset),
+        _ => Err(format!("unknown command {line:?}")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            Ok(session::Action::Split(time::Time::from_millis(83_456))),
+            parse("split 1m23s456")
+        );
+        assert_eq!(Ok(session::Action::SplitReset), parse("  split-reset "));
+        assert!(parse("split").is_err());
+        assert!(parse("split-reset 1s").is_err());
+        assert!(parse("split soon").is_err());
+        assert!(parse("pop").is_err());
+    }
+}
@@ -1,11 +1,14 @@
 //! Tests the database functionality on an in-memory database.
 
-use std::{convert::TryFrom, ops::Add, rc::Rc};
+use std::{ops::Add, rc::Rc};
 use tempfile::{tempdir, TempDir};
 use zombiesplit::{
     db::{self, Db, Reader, Sink},
     model::{
-        game::{self, category::ShortDescriptor},
+        game::{
+            self,
+            category::{PreferredComparison, ShortDescriptor},
+        },
         history,
         session::{
             self,
@@ -13,7 +16,7 @@ use zombiesplit::{
             event,
         },
         short,
-        timing::time::human::Time,
+        timing::time,
         Loadable,
     },
 };
@@ -75,6 +78,107 @@ fn test_sample_session() {
     );
 }
 
+/// Tests that a category's preferred comparison makes it into the database.
+#[test]
+fn test_sample_preferred_comparison() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let mut game = load_game();
+    game.categories
+        .get_mut(&short::Name::from(SAMPLE_CATEGORY_NAME))
+        .expect("sample category should exist")
+        .comparison = Some(PreferredComparison::Balanced);
+    let db = setup_db(&game, &tdir);
+
+    let handle = db.reader().expect("couldn't open reader");
+    let mut insp = handle
+        .inspect(&short_descriptor())
+        .expect("couldn't open category db");
+    let id = insp.info.id;
+    assert_eq!(
+        Some(PreferredComparison::Balanced),
+        insp.cat
+            .preferred_comparison(&id)
+            .expect("couldn't get preferred comparison")
+    );
+}
+
+/// Tests that the latest run follows the most recently dated run, and is absent before any runs.
+#[test]
+fn test_sample_latest_run() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    {
+        let handle = db.reader().expect("couldn't open reader");
+        let mut insp = handle
+            .inspect(&short_descriptor())
+            .expect("couldn't open category db");
+        assert!(insp
+            .latest_run()
+            .expect("couldn't get latest run")
+            .is_none());
+    }
+
+    let old = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    let mut new = old.clone();
+    new.date = old.date + chrono::Duration::days(1);
+    let pp1 = short::Name::from("pp1");
+    new.timing
+        .times
+        .insert(pp1, vec![time::Time::from_millis(12_345)]);
+    // Insert the newer run first, to make sure dates win over insertion order.
+    db.add_run(&new).expect("couldn't insert run");
+    db.add_run(&old).expect("couldn't insert run");
+
+    let handle = db.reader().expect("couldn't open reader");
+    let mut insp = handle
+        .inspect(&short_descriptor())
+        .expect("couldn't open category db");
+    let latest = insp
+        .latest_run()
+        .expect("couldn't get latest run")
+        .expect("there should be a latest run");
+    assert_eq!(
+        Some(&time::Time::from_millis(12_345)),
+        latest.totals.get(&pp1)
+    );
+}
+
+/// Tests that the dated history lists runs oldest first, whatever order they went in.
+#[test]
+fn test_sample_dated_history() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let old = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    let mut new = old.clone();
+    new.date = old.date + chrono::Duration::days(1);
+    let pp1 = short::Name::from("pp1");
+    new.timing
+        .times
+        .insert(pp1, vec![time::Time::from_millis(12_345)]);
+    db.add_run(&new).expect("couldn't insert run");
+    db.add_run(&old).expect("couldn't insert run");
+
+    let handle = db.reader().expect("couldn't open reader");
+    let mut insp = handle
+        .inspect(&short_descriptor())
+        .expect("couldn't open category db");
+    let history = insp.dated_history().expect("couldn't get history");
+    assert_eq!(2, history.len());
+    assert_eq!(
+        Some(&time::Time::from_millis(12_345)),
+        history[1].totals.get(&pp1)
+    );
+}
+
 /// Tests initialising the database and adding a run to it.
 #[test]
 fn test_sample_add_run() {
@@ -96,6 +200,484 @@ fn test_sample_add_run() {
     // TODO(MattWindsor91): check run specifics
 }
 
+/// Tests that adding a run with splits outside its category fails.
+#[test]
+fn test_sample_add_run_unknown_split() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    run.timing
+        .times
+        .insert(short::Name::from("bogus"), vec![Default::default()]);
+
+    let err = db
+        .add_run(&run)
+        .expect_err("run with bogus split should fail");
+    assert!(
+        matches!(
+            &err,
+            db::Error::UnknownRunSplits { shorts } if shorts == &[short::Name::from("bogus")]
+        ),
+        "unexpected error: {err}"
+    );
+
+    let runs = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert!(runs.is_empty(), "the bad run shouldn't be inserted");
+}
+
+/// Tests that a near-miss category descriptor finds the sample category as its candidate.
+#[test]
+fn test_sample_category_candidates() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let exact = db
+        .category_candidates(&short_descriptor())
+        .expect("couldn't get candidates");
+    assert_eq!(0, exact.first().expect("should be a candidate").distance);
+
+    let near = db
+        .category_candidates(&ShortDescriptor::new("scd11", "btg_sonic"))
+        .expect("couldn't get candidates");
+    let best = near.first().expect("should be a candidate");
+    assert_eq!(short_descriptor(), best.target.short);
+    assert_eq!(1, best.distance);
+
+    let far = db
+        .category_candidates(&ShortDescriptor::new("s3k", "knuckles"))
+        .expect("couldn't get candidates");
+    assert!(
+        far.is_empty(),
+        "unrelated categories shouldn't be candidates"
+    );
+}
+
+/// Tests that adding a batch of runs is all-or-nothing.
+#[test]
+fn test_sample_add_runs() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    let mut bad_run = run.clone();
+    bad_run
+        .timing
+        .times
+        .insert(short::Name::from("nope"), vec![Default::default()]);
+
+    assert!(
+        db.add_runs(&[run.clone(), bad_run]).is_err(),
+        "a run with an unknown split should fail to insert"
+    );
+    let runs = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert!(runs.is_empty(), "a failed batch shouldn't insert any runs");
+
+    let count = db.add_runs(&[run]).expect("couldn't insert runs");
+    assert_eq!(1, count, "one run should have been inserted");
+}
+
+/// Tests that skipped splits and paused time survive a round trip through the database.
+#[test]
+fn test_sample_add_run_skips_and_pauses() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    // Runs from before skipping and pausing were tracked should still load.
+    assert!(run.timing.skipped.is_empty());
+    assert_eq!(0, run.timing.paused.into_millis());
+
+    let skipped = short::Name::from("pp2");
+    run.timing.times.remove(&skipped);
+    run.timing.skipped.insert(skipped);
+    run.timing.paused = time::Time::from_millis(90_000);
+    db.add_run(&run).expect("couldn't insert run");
+
+    let summary = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries")
+        .pop()
+        .expect("there should be a run");
+    let reader = db.reader().expect("couldn't open reader");
+    let mut getter = reader.runs().expect("couldn't open run getter");
+    let id = getter
+        .runs_for(summary.category_locator)
+        .expect("couldn't get run IDs")
+        .pop()
+        .expect("there should be a run")
+        .id;
+
+    let timing = getter.timing_for(id).expect("couldn't get run timing");
+    assert_eq!(run.timing, timing);
+}
+
+/// Tests that skipped splits with times, such as merged splits, don't set best segments.
+#[test]
+fn test_sample_skipped_split_best_segments() {
+    let tdir = tempdir().expect("can't open dir");
+    let db = setup_db(&load_game(), &tdir);
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    let merged = short::Name::from("pp1");
+    run.timing.skipped.insert(merged);
+    db.add_run(&run).expect("couldn't insert run");
+
+    let handle = db.reader().expect("couldn't open reader");
+    let mut insp = handle
+        .inspect(&short_descriptor())
+        .expect("couldn't open category db");
+    let bests = insp.best_segments().expect("couldn't get best segments");
+    assert!(!bests.contains_key(&merged));
+    assert!(bests.contains_key(&short::Name::from("pp2")));
+}
+
+/// Tests that split adjustments survive a round trip through the database, and count towards totals.
+#[test]
+fn test_sample_add_run_adjustments() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    assert!(run.timing.adjustments.is_empty());
+
+    let adjusted = short::Name::from("pp1");
+    run.timing
+        .adjustments
+        .insert(adjusted, time::Time::from_millis(5_000));
+    db.add_run(&run).expect("couldn't insert run");
+
+    let summary = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries")
+        .pop()
+        .expect("there should be a run");
+    let reader = db.reader().expect("couldn't open reader");
+    let mut getter = reader.runs().expect("couldn't open run getter");
+    let id = getter
+        .runs_for(summary.category_locator)
+        .expect("couldn't get run IDs")
+        .pop()
+        .expect("there should be a run")
+        .id;
+
+    let timing = getter.timing_for(id).expect("couldn't get run timing");
+    assert_eq!(run.timing, timing);
+
+    let totals = getter
+        .split_totals_for(id)
+        .expect("couldn't get split totals");
+    assert_eq!(
+        Some(&timing.split_total(adjusted)),
+        totals.totals.get(&adjusted)
+    );
+}
+
+/// Tests that run metadata survives a round trip through the database.
+#[test]
+fn test_sample_add_run_metadata() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let mut run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    // Runs from before metadata was tracked should still load.
+    assert!(run.metadata.is_empty());
+    db.add_run(&run).expect("couldn't insert run");
+
+    run.date = run.date + chrono::Duration::days(1);
+    run.metadata
+        .insert("controller".to_owned(), "keyboard".to_owned());
+    run.metadata.insert("patch".to_owned(), "1.1".to_owned());
+    db.add_run(&run).expect("couldn't insert run");
+
+    let runs = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert_eq!(2, runs.len(), "there should be two runs");
+    assert_eq!(
+        1,
+        runs.iter()
+            .filter(|r| r.has_metadata("controller", "keyboard"))
+            .count()
+    );
+    assert!(runs.iter().any(|r| r.metadata == run.metadata));
+    assert!(runs.iter().any(|r| r.metadata.is_empty()));
+}
+
+/// Tests that renaming a game and category carries their runs with them.
+#[test]
+fn test_sample_rename() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    let (old_game, old_category) = (
+        short::Name::from(SAMPLE_GAME_NAME),
+        short::Name::from(SAMPLE_CATEGORY_NAME),
+    );
+    let (new_game, new_category) = (short::Name::from("scdpc"), short::Name::from("sonic"));
+
+    db.rename_game(old_game, new_game)
+        .expect("couldn't rename game");
+    db.rename_category(new_game, old_category, new_category)
+        .expect("couldn't rename category");
+
+    let runs = db
+        .runs_for(&ShortDescriptor::new(new_game, new_category))
+        .expect("couldn't get run summaries under new names");
+    assert_eq!(1, runs.len(), "the run should follow the renames");
+    assert!(
+        db.runs_for(&short_descriptor()).is_err(),
+        "the old names should no longer resolve"
+    );
+
+    let err = db
+        .rename_game(short::Name::from("bogus"), new_game)
+        .expect_err("renaming a missing game should fail");
+    assert!(
+        matches!(err, db::Error::MissingGame { .. }),
+        "unexpected error: {err}"
+    );
+
+    db.add_game(SAMPLE_GAME_NAME, &game)
+        .expect("couldn't re-add game under old name");
+    let err = db
+        .rename_game(old_game, new_game)
+        .expect_err("renaming onto an existing game should fail");
+    assert!(
+        matches!(err, db::Error::NameTaken { short } if short == new_game),
+        "unexpected error: {err}"
+    );
+}
+
+/// Tests backing up a database and restoring it into a fresh one.
+#[test]
+fn test_sample_backup_restore() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let game = load_game();
+    let db = setup_db(&game, &tdir);
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    let backup = tdir.path().join("backup.db");
+    db.backup(&backup).expect("couldn't back up");
+
+    let fresh = Db::new(tdir.path().join("fresh.db")).expect("couldn't open fresh db");
+    fresh.restore(&backup, false).expect("couldn't restore");
+    let runs = fresh
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert_eq!(1, runs.len(), "the run should have been restored");
+
+    assert!(
+        matches!(
+            db.restore(&backup, false),
+            Err(db::Error::RestoreWouldOverwrite)
+        ),
+        "restoring over an existing database needs forcing"
+    );
+    db.restore(&backup, true).expect("couldn't force restore");
+
+    let incompatible = tdir.path().join("incompatible.db");
+    rusqlite::Connection::open(&incompatible)
+        .expect("couldn't open incompatible db")
+        .execute_batch("CREATE TABLE foo (bar INTEGER);")
+        .expect("couldn't set up incompatible db");
+    let other = Db::new(tdir.path().join("other.db")).expect("couldn't open other db");
+    assert!(
+        matches!(
+            other.restore(&incompatible, true),
+            Err(db::Error::IncompatibleBackup)
+        ),
+        "backups with a different schema should be rejected"
+    );
+}
+
+/// Tests that checking the database finds deliberately orphaned rows, and that repairing it deletes
+/// them and anything they orphan in turn.
+#[test]
+fn test_sample_fsck() {
+    use db::fsck::Problem;
+
+    let tdir = tempdir().expect("can't open dir");
+    let db = setup_db(&load_game(), &tdir);
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+    assert!(
+        db.check().expect("couldn't check db").is_clean(),
+        "a fresh database should be clean"
+    );
+
+    rusqlite::Connection::open(tdir.path().join("test.db"))
+        .expect("couldn't open db directly")
+        .execute_batch(
+            "DELETE FROM run;
+             INSERT INTO run (run_id, game_category_id, timestamp, is_completed)
+                    VALUES (9999, 999, 0, 1);
+             INSERT INTO run_split_time (run_split_id, position, time_ms) VALUES (9999, 0, 1000);",
+        )
+        .expect("couldn't seed orphans");
+
+    let report = db.check().expect("couldn't check db");
+    assert_eq!(1, report.count(Problem::RunWithoutCategory));
+    assert!(0 < report.count(Problem::RunSplitWithoutRun));
+    assert_eq!(0, report.count(Problem::RunSplitWithoutSplit));
+    assert_eq!(1, report.count(Problem::TimeWithoutRunSplit));
+
+    let repaired = db.repair().expect("couldn't repair db");
+    assert_eq!(1, repaired.count(Problem::RunWithoutCategory));
+    assert_eq!(
+        report.count(Problem::RunSplitWithoutRun),
+        repaired.count(Problem::RunSplitWithoutRun)
+    );
+    assert!(
+        1 < repaired.count(Problem::TimeWithoutRunSplit),
+        "times of deleted run splits should be deleted too"
+    );
+    assert!(
+        db.check().expect("couldn't check db").is_clean(),
+        "a repaired database should be clean"
+    );
+
+    // Repairing leaves the game alone.
+    db.runs_for(&short_descriptor())
+        .expect("game should still be there");
+}
+
+/// Tests that the comparison revision changes whenever the runs do, even if a run is replaced by
+/// one with the same ID.
+#[test]
+fn test_sample_revision() {
+    use zombiesplit::model::timing::comparison::provider::Provider;
+
+    let tdir = tempdir().expect("can't open dir");
+    let db = setup_db(&load_game(), &tdir);
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    let handle = db.reader().expect("couldn't open reader");
+    let mut insp = handle
+        .inspect(&short_descriptor())
+        .expect("couldn't open category db");
+    let before = insp.revision().expect("couldn't get revision");
+    assert_eq!(
+        before,
+        insp.revision().expect("couldn't get revision"),
+        "revision shouldn't change without writes"
+    );
+
+    // Deleting the only run and adding another reuses its ID, and keeps the run count.
+    rusqlite::Connection::open(tdir.path().join("test.db"))
+        .expect("couldn't open db directly")
+        .execute_batch(
+            "DELETE FROM run_split_time;
+             DELETE FROM run_split;
+             DELETE FROM run;",
+        )
+        .expect("couldn't delete run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    let after = insp.revision().expect("couldn't get revision");
+    assert_ne!(before, after, "replacing a run should change the revision");
+}
+
+/// Tests that initialising a database brings it up to the latest schema version.
+#[test]
+fn test_sample_migrate() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let blank = Db::new(tdir.path().join("blank.db")).expect("couldn't open blank db");
+    assert!(
+        matches!(blank.migrate(), Err(db::Error::Uninitialised)),
+        "migrating needs an initialised database"
+    );
+
+    let db = setup_db(&load_game(), &tdir);
+    let status = db
+        .migration_status()
+        .expect("couldn't get migration status");
+    assert_eq!(db::migrate::MIGRATIONS.len(), status.latest);
+    assert!(
+        status.is_up_to_date(),
+        "a new database should be up to date"
+    );
+    assert!(status.pending().is_empty());
+    assert_eq!(0, db.migrate().expect("couldn't migrate"));
+}
+
+/// Tests that upserting an existing game updates it in place, keeping its runs.
+#[test]
+fn test_sample_upsert_game() {
+    let tdir = tempdir().expect("can't open dir");
+
+    let mut game = load_game();
+    let db = setup_db(&game, &tdir);
+
+    let run = history::run::FullyTimed::<ShortDescriptor>::from_toml_file(SAMPLE_RUN_PATH)
+        .expect("couldn't load run");
+    db.add_run(&run).expect("couldn't insert run");
+
+    game.name = "Sonic CD (2011 remaster)".to_owned();
+    let added = db
+        .upsert_game(SAMPLE_GAME_NAME, &game)
+        .expect("couldn't upsert game");
+    assert!(!added, "the game should have been updated, not added");
+
+    let targets = db
+        .categories_of(short::Name::from(SAMPLE_GAME_NAME))
+        .expect("couldn't list categories");
+    assert_eq!(
+        game.categories.len(),
+        targets.len(),
+        "categories shouldn't duplicate"
+    );
+    assert!(
+        targets.iter().all(|t| t.game == game.name),
+        "the name should change"
+    );
+
+    let runs = db
+        .runs_for(&short_descriptor())
+        .expect("couldn't get run summaries");
+    assert_eq!(1, runs.len(), "the run should survive the update");
+
+    let added = db
+        .upsert_game("scdpc", &game)
+        .expect("couldn't upsert new game");
+    assert!(added, "a new game should be added");
+}
+
 /// Tests initialising the database and adding a run through observation.
 #[test]
 fn test_sample_observe_run() {
@@ -112,7 +694,7 @@ fn test_sample_observe_run() {
         .handle(Action::NewRun(OldDestination::Save))
         .unwrap();
 
-    let time = Time::try_from(8675309).expect("time didn't parse");
+    let time = time::Time::from_millis(8_675_309);
 
     // This also shouldn't.
     session.set_timestamper(chrono::Utc::now);
@@ -160,6 +742,15 @@ fn test_sample_observe_run() {
         "the timestamper should have given these different times"
     );
 
+    let by_date = db
+        .runs_by_date(&short_descriptor())
+        .expect("couldn't get run summaries by date");
+    assert_eq!(2, by_date.len(), "there should still be two runs");
+    assert!(
+        by_date[0].date < by_date[1].date,
+        "runs should be ordered by date"
+    );
+
     for (i, run) in runs.iter().enumerate() {
         let s_run = insp
             .run_at_index(i, &db::inspect::Summary)
@@ -175,7 +766,7 @@ fn inject_session_actions(
     dest: OldDestination,
 ) {
     for action in actions {
-        session.handle(action).unwrap()
+        session.handle(action).unwrap();
     }
     session.handle(Action::NewRun(dest)).unwrap();
 }
@@ -1,13 +1,16 @@
 //! Integration tests for sessions, comparisons, etc without involving the database.
 
+use std::time::Duration;
+
 use zombiesplit::model::{
-    game,
-    session::{event, Attempt, Session},
+    self,
+    game::{self, category::SplitTimePolicy},
+    session::{self, action::Handler, event, test_util::Recorder, Attempt, Session},
     short,
     timing::{
         aggregate,
         comparison::{self, delta, Comparison},
-        time::human,
+        time::{self, human},
     },
     Loadable,
 };
@@ -29,15 +32,21 @@ fn make_attempt() -> Attempt {
     .expect("couldn't load game/category")
 }
 
-fn split(name: &str, h: u32, m: u32, s: u32, ms: u32) -> (short::Name, comparison::Segment) {
+fn split(
+    name: &'static str,
+    h: u16,
+    m: u16,
+    s: u16,
+    ms: u16,
+) -> (short::Name, comparison::Segment) {
     let in_pb_run = aggregate::Set {
-        split: human::Time::new(h, m, s, ms).expect("time overflowed"),
+        split: time::Time::try_from(human::Time::new(h, m, s, ms)).expect("time overflowed"),
         cumulative: Default::default(),
     };
     (
         name.into(),
         comparison::Segment {
-            split_pb: human::Time::default(),
+            split_pb: time::Time::default(),
             in_pb_run,
         },
     )
@@ -70,13 +79,14 @@ fn comparison() -> Comparison {
     ];
 
     // Fix up the cumulatives
-    let mut accum = human::Time::default();
+    let mut accum = time::Time::default();
     for (_, split) in &mut splits {
         accum += split.in_pb_run.split;
         split.in_pb_run.cumulative = accum;
     }
 
     Comparison {
+        name: None,
         splits: short::Map::from(splits),
         run: Default::default(),
     }
@@ -91,7 +101,8 @@ fn make_session<'o, T: event::observer::Observer>(obs: &'o T) -> Session<'static
 }
 
 fn delta(string: &str) -> delta::Delta {
-    string.parse().expect("couldn't parse delta")
+    let time = string.parse().expect("couldn't parse delta");
+    delta::Delta::of_comparison(time, time::Time::default())
 }
 
 fn split_delta(split: &str, run: &str) -> delta::Split {
@@ -126,9 +137,1076 @@ fn test_session_deltas() {
     assert_eq!(Some(split_delta("-0s", "+2s")), obs.delta("pp3"));
 }
 
-fn push(session: &mut Session<DeltaLogger>, name: &str, h: u32, m: u32, s: u32, ms: u32) {
-    let time = human::Time::new(h, m, s, ms).expect("time construction error");
-    session.push_to(short::Name::from(name), time);
+/// Tests that splits missing from the comparison get no delta, but still count towards the total.
+#[test]
+fn test_session_deltas_missing_split() {
+    let obs = DeltaLogger::default();
+    let mut s = Session::new(make_attempt(), &obs);
+    let mut cmp = comparison();
+    cmp.splits.remove(&short::Name::from("pp2"));
+    s.set_comparison_provider(Box::new(Some(cmp)));
+
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    push(&mut s, "pp2", 0, 0, 27, 300);
+    assert_eq!(Some(split_delta("-1s", "-1s")), obs.delta("pp1"));
+    assert_eq!(None, obs.delta("pp2"), "pp2 has nothing to compare against");
+
+    let mut state = s.dump().expect("dump shouldn't fail");
+    let total = state
+        .total
+        .take()
+        .expect("the total should survive a missing delta");
+    assert_eq!(time::Time::from_millis(51_360), total.time);
+    assert_eq!(delta::Delta::default(), total.delta);
+
+    // Later splits still compare against the comparison's cumulative times.
+    push(&mut s, "pp3", 0, 0, 24, 260);
+    assert_eq!(Some(split_delta("-0s", "+1s")), obs.delta("pp3"));
+}
+
+/// Tests that crossing the comparison's projected total raises pace-cross events.
+#[test]
+fn test_session_pace_cross() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    let ahead = event::Event::PaceCross { ahead: true };
+    let behind = event::Event::PaceCross { ahead: false };
+
+    // 1 second ahead; this is the first projection, so isn't a crossing.
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    assert!(!obs.contains(&ahead) && !obs.contains(&behind));
+
+    // 2 seconds behind on the split, so 1 second behind overall.
+    push(&mut s, "pp2", 0, 0, 27, 300);
+    assert!(obs.contains(&behind), "falling behind should be observed");
+    assert!(!obs.contains(&ahead));
+
+    // 2 seconds ahead on the split, so 1 second ahead overall.
+    push(&mut s, "pp3", 0, 0, 22, 260);
+    assert!(obs.contains(&ahead), "moving ahead should be observed");
+}
+
+/// Tests that opening and closing an editor is observed, and that resetting closes it.
+#[test]
+fn test_session_editing() {
+    use session::action::Outcome;
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    let mut handle = |action| s.handle(action).expect("action shouldn't fail");
+
+    assert_eq!(Outcome::Applied, handle(session::Action::Edit(Some(1))));
+    assert_eq!(Outcome::Unchanged, handle(session::Action::Edit(Some(1))));
+    assert_eq!(
+        Outcome::Unchanged,
+        handle(session::Action::Edit(Some(9001)))
+    );
+    let opened = event::Event::Editing(Some(short::Name::from("pp2")));
+    assert!(
+        obs.contains(&opened),
+        "opening the editor should be observed"
+    );
+
+    assert_eq!(Outcome::Applied, handle(session::Action::Edit(None)));
+    assert!(obs.contains(&event::Event::Editing(None)));
+
+    handle(session::Action::Edit(Some(0)));
+    handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ));
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(None, state.editing, "resetting should close the editor");
+}
+
+/// Tests that resetting a run highlights its best and worst splits.
+#[test]
+fn test_session_highlights() {
+    use event::split::Highlight;
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    // 1 second ahead, 2 seconds behind, then 2 seconds ahead on the splits.
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    push(&mut s, "pp2", 0, 0, 27, 300);
+    push(&mut s, "pp3", 0, 0, 22, 260);
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+
+    let highlight =
+        |name, h| event::Event::Split(short::Name::from(name), event::Split::Highlight(h));
+    assert!(obs.contains(&highlight("pp3", Highlight::Best)));
+    assert!(obs.contains(&highlight("pp2", Highlight::Worst)));
+    assert!(!obs.contains(&highlight("pp1", Highlight::Best)));
+}
+
+/// Tests that segment paces distinguish a good segment in a run that is still behind.
+#[test]
+fn test_session_segment_pace() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    let segment_pace =
+        |pace| event::Event::Split(short::Name::from("pp2"), event::Split::SegmentPace(pace));
+
+    // 2 seconds behind, then 1 second ahead on the split, so still 1 second behind overall.
+    push(&mut s, "pp1", 0, 0, 27, 60);
+    push(&mut s, "pp2", 0, 0, 24, 300);
+
+    assert!(obs.contains(&segment_pace(comparison::Pace::Ahead)));
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(Some(false), state.is_projected_ahead());
+}
+
+/// Tests that segments within the tolerance of their comparison have an even pace.
+#[test]
+fn test_session_tolerance() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    s.set_tolerance(model::timing::time::Time::from_millis(500));
+
+    let segment_pace =
+        |name, pace| event::Event::Split(short::Name::from(name), event::Split::SegmentPace(pace));
+
+    // 300ms behind, then a full second ahead.
+    push(&mut s, "pp1", 0, 0, 25, 360);
+    push(&mut s, "pp2", 0, 0, 24, 300);
+
+    assert!(obs.contains(&segment_pace("pp1", comparison::Pace::Even)));
+    assert!(obs.contains(&segment_pace("pp2", comparison::Pace::Ahead)));
+
+    let state = s.dump().expect("dump shouldn't fail");
+    let note = state
+        .notes
+        .get(&short::Name::from("pp1"))
+        .expect("note should exist");
+    assert_eq!(comparison::pace::SplitInRun::Even, note.pace);
+}
+
+/// Tests each of the things a session can do when the last split gets a time.
+#[test]
+fn test_session_on_last_split() {
+    for on_last_split in [
+        session::OnLastSplit::Stay,
+        session::OnLastSplit::AutoFinish,
+        session::OnLastSplit::Prompt,
+    ] {
+        let obs = Recorder::default();
+        let mut s = make_session(&obs);
+        s.set_on_last_split(on_last_split);
+
+        push(&mut s, "mm2", 0, 0, 45, 110);
+        assert!(
+            !obs.contains(&event::Event::FinishPrompt),
+            "{on_last_split:?}: only the last split should prompt"
+        );
+
+        push(&mut s, "mm3", 0, 1, 37, 550);
+        let state = s.dump().expect("dump shouldn't fail");
+        assert_eq!(
+            on_last_split == session::OnLastSplit::AutoFinish,
+            state.finished,
+            "{on_last_split:?}: wrong finished state"
+        );
+        assert_eq!(
+            on_last_split == session::OnLastSplit::Prompt,
+            obs.contains(&event::Event::FinishPrompt),
+            "{on_last_split:?}: wrong prompting"
+        );
+    }
+}
+
+/// Tests that splits go into the first pace bucket that matches them.
+#[test]
+fn test_session_pace_buckets() {
+    use comparison::pace::bucket::{Bucket, Buckets, Range};
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    let ms = |ms| Some(model::timing::time::Time::from_millis(ms));
+    let bucket = |name: &str, split| Bucket {
+        name: name.to_owned(),
+        colour: "#ffffff".to_owned(),
+        symbol: None,
+        gold: None,
+        split,
+        run: Range::default(),
+    };
+    let buckets = Buckets(vec![
+        bucket(
+            "close",
+            Range {
+                from: ms(-500),
+                to: ms(501),
+            },
+        ),
+        bucket("other", Range::default()),
+    ]);
+    buckets.validate().expect("buckets should be valid");
+    s.set_pace_buckets(buckets);
+
+    let pace_bucket = |split, name: &str| {
+        event::Event::Split(
+            short::Name::from(split),
+            event::Split::PaceBucket(name.to_owned()),
+        )
+    };
+
+    // 300ms behind, then a full second ahead.
+    push(&mut s, "pp1", 0, 0, 25, 360);
+    push(&mut s, "pp2", 0, 0, 24, 300);
+
+    assert!(obs.contains(&pace_bucket("pp1", "close")));
+    assert!(obs.contains(&pace_bucket("pp2", "other")));
+    assert!(!obs.contains(&pace_bucket("pp2", "close")));
+}
+
+/// Tests that the target is the comparison's segment time for the split after the last timed one.
+#[test]
+fn test_session_target() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    let ms = |ms| Some(model::timing::time::Time::from_millis(ms));
+
+    assert_eq!(ms(25_060), s.dump().expect("dump shouldn't fail").target());
+    assert!(obs.contains(&event::Event::Target(ms(25_060))));
+
+    // Pushing leaves the pushed split current, but the runner has moved on to the next one.
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(Some(short::Name::from("pp1")), state.current_split());
+    assert_eq!(ms(25_300), state.target());
+    assert!(obs.contains(&event::Event::Target(ms(25_300))));
+
+    // Splitting, on the other hand, also moves the current split on to the split being run.
+    s.handle(session::Action::Split(time::Time::from_millis(1_000)))
+        .expect("action shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(Some(short::Name::from("pp2")), state.current_split());
+    assert_eq!(ms(25_300), state.target());
+
+    let mut bare = Session::new(make_attempt(), &event::observer::Null);
+    assert_eq!(
+        None,
+        bare.dump().expect("dump shouldn't fail").target(),
+        "there's no target without a comparison"
+    );
+}
+
+/// Tests that the comparison takes its name from its provider.
+#[test]
+fn test_session_comparison_name() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    // The plain comparison has no name.
+    assert!(obs.contains(&event::Event::ComparisonName(None)));
+
+    let goal = comparison::goal::Goal::default();
+    let order = make_attempt()
+        .splits
+        .iter()
+        .map(|s| s.info.short)
+        .collect::<Vec<_>>();
+    s.set_comparison_provider(Box::new(comparison::GoalProvider::new(&goal, order)));
+
+    let name = Some("Goal".to_owned());
+    assert!(obs.contains(&event::Event::ComparisonName(name.clone())));
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(name, state.comparison.name);
+}
+
+/// Tests that overlay comparisons get their own named aggregates and deltas.
+#[test]
+fn test_session_overlays() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    let ghost = Comparison {
+        name: Some("Ghost".to_owned()),
+        ..comparison()
+    };
+    s.add_overlay_provider(Box::new(Some(ghost)));
+
+    let pp1 = short::Name::from("pp1");
+    let (_, segment) = split("pp1", 0, 0, 25, 60);
+    let overlay = |evt| event::Event::Overlay("Ghost".to_owned(), evt);
+    assert!(obs.contains(&overlay(event::Overlay::Aggregate(
+        pp1,
+        aggregate::Set {
+            cumulative: segment.in_pb_run.split,
+            ..segment.in_pb_run
+        }
+    ))));
+
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    assert!(obs.contains(&overlay(event::Overlay::Delta(
+        pp1,
+        split_delta("-1s", "-1s")
+    ))));
+
+    let state = s.dump().expect("dump shouldn't fail");
+    assert!(state.overlays.contains_key("Ghost"));
+    assert!(
+        state.comparison.name.is_none(),
+        "overlays shouldn't touch the main comparison"
+    );
+}
+
+/// Tests pushing times in cumulative entry mode.
+#[test]
+fn test_session_cumulative_entry() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    s.set_entry_mode(session::EntryMode::Cumulative);
+
+    let time = |secs| time::Time::from_millis(secs * 1000);
+    s.push_to(0, time(25)).expect("first push shouldn't fail");
+    s.push_to(1, time(45)).expect("second push shouldn't fail");
+
+    let state = s.dump().expect("dump shouldn't fail");
+    let second = state.attempt.splits.get(1).expect("split should exist");
+    assert_eq!(vec![time(20)], second.all_times());
+    let pushed = event::Event::Split(
+        short::Name::from("pp2"),
+        event::Split::Time(time(20), event::Time::Pushed),
+    );
+    assert!(obs.contains(&pushed), "the segment time should be observed");
+
+    let err = s
+        .push_to(2, time(40))
+        .expect_err("pushing an earlier cumulative should fail");
+    assert_eq!(
+        session::Error::CumulativeBehind {
+            split: short::Name::from("pp3"),
+            previous: time(45),
+        },
+        err
+    );
+}
+
+/// Tests entering a one-off cumulative time, leaving the entry mode alone.
+#[test]
+fn test_session_push_total() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+
+    let time = |secs| time::Time::from_millis(secs * 1000);
+    s.push_to(0, time(25)).expect("first push shouldn't fail");
+    s.handle(session::Action::PushTotal(1, time(45)))
+        .expect("total push shouldn't fail");
+
+    let state = s.dump().expect("dump shouldn't fail");
+    let second = state.attempt.splits.get(1).expect("split should exist");
+    assert_eq!(vec![time(20)], second.all_times());
+    let pushed = event::Event::Split(
+        short::Name::from("pp2"),
+        event::Split::Time(time(20), event::Time::Pushed),
+    );
+    assert!(obs.contains(&pushed), "the segment time should be observed");
+    assert_eq!(session::EntryMode::Segment, state.entry_mode);
+
+    let err = s
+        .handle(session::Action::PushTotal(2, time(40)))
+        .expect_err("pushing an earlier total should fail");
+    assert_eq!(
+        model::Error::from(session::Error::CumulativeBehind {
+            split: short::Name::from("pp3"),
+            previous: time(45),
+        }),
+        err
+    );
+}
+
+/// Tests the outcomes of actions on empty and non-empty splits.
+#[test]
+fn test_session_outcomes() {
+    use session::{
+        action::{OldDestination, Outcome, Pop},
+        Action,
+    };
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    let mut handle = |action| s.handle(action).expect("action shouldn't fail");
+    let time = time::Time::from_millis(25_000);
+
+    assert_eq!(Outcome::Unchanged, handle(Action::Pop(0, Pop::One)));
+    assert_eq!(Outcome::Unchanged, handle(Action::Pop(0, Pop::All)));
+    assert_eq!(Outcome::Unchanged, handle(Action::SplitReset));
+    assert_eq!(Outcome::Unchanged, handle(Action::SetCurrent(0)));
+    assert_eq!(Outcome::Unchanged, handle(Action::Push(9001, time)));
+
+    assert_eq!(Outcome::Applied, handle(Action::Push(0, time)));
+    assert_eq!(Outcome::Applied, handle(Action::Pop(0, Pop::One)));
+    assert_eq!(Outcome::Applied, handle(Action::Push(0, time)));
+    assert_eq!(Outcome::Applied, handle(Action::Pop(0, Pop::All)));
+    assert_eq!(Outcome::Applied, handle(Action::SetCurrent(1)));
+    assert_eq!(Outcome::Applied, handle(Action::Split(time)));
+    assert_eq!(Outcome::Applied, handle(Action::SplitReset));
+    assert_eq!(
+        Outcome::Applied,
+        handle(Action::NewRun(OldDestination::Discard))
+    );
+}
+
+/// Tests that clearing the whole run blanks every split without counting an attempt.
+#[test]
+fn test_session_clear_all() {
+    use session::action::{Outcome, Pop};
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    push(&mut s, "pp2", 0, 0, 27, 300);
+    let before = s.dump().expect("dump shouldn't fail").attempt.info;
+
+    s.set_clear_confirmation(|_| false);
+    assert_eq!(Outcome::Unchanged, clear_all(&mut s));
+
+    s.set_clear_confirmation(|state| 1 < state.attempt.splits.len());
+    assert_eq!(Outcome::Applied, clear_all(&mut s));
+    for split in ["pp1", "pp2"] {
+        assert!(obs.contains(&event::Event::Split(
+            short::Name::from(split),
+            event::Split::Popped(Pop::All)
+        )));
+    }
+
+    let state = s.dump().expect("dump shouldn't fail");
+    assert!(state.attempt.splits.iter().all(|s| s.num_times() == 0));
+    assert_eq!(0, state.current);
+    assert_eq!(
+        before, state.attempt.info,
+        "clearing shouldn't count an attempt"
+    );
+
+    // There's nothing left to clear.
+    assert_eq!(Outcome::Unchanged, clear_all(&mut s));
+}
+
+/// Tests that a run can be checkpointed by one session and resumed by another.
+#[test]
+fn test_session_checkpoint_resume() {
+    use session::checkpoint;
+
+    let dir = tempfile::tempdir().expect("couldn't make temp dir");
+    let target = game::category::ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME);
+    let file = || checkpoint::File::new(dir.path(), &target);
+    let time = time::Time::from_millis(25_000);
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    s.set_checkpoint(Box::new(file()));
+    s.handle(session::Action::Push(0, time))
+        .expect("action shouldn't fail");
+    s.handle(session::Action::Push(1, time))
+        .expect("action shouldn't fail");
+
+    let run = file()
+        .load()
+        .expect("checkpoint should load")
+        .expect("pushing should have made a checkpoint");
+    let mut resumed = make_session(&obs);
+    resumed.resume(&run).expect("resuming shouldn't fail");
+    let state = resumed.dump().expect("dump shouldn't fail");
+    assert_eq!(2, state.current, "the first empty split should be current");
+    let split = state.attempt.splits.get(1).expect("split should exist");
+    assert_eq!(vec![time], split.all_times());
+
+    let mut stray = run.clone();
+    let nope = short::Name::from("nope");
+    stray.timing.times.insert(nope, vec![time]);
+    assert_eq!(
+        Err(model::Error::UnknownSplit(nope)),
+        resumed.resume(&stray)
+    );
+    let mut other = run;
+    other.category_locator.category = short::Name::from("btg-tails");
+    assert!(matches!(
+        resumed.resume(&other),
+        Err(model::Error::CategoryMismatch { .. })
+    ));
+
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+    let run = file().load().expect("checkpoint should load");
+    assert!(run.is_none(), "resetting should clear the checkpoint");
+}
+
+/// Tests that shutting down keeps the run in progress, without clobbering older checkpoints.
+#[test]
+fn test_session_shutdown() {
+    use session::checkpoint;
+
+    let dir = tempfile::tempdir().expect("couldn't make temp dir");
+    let target = game::category::ShortDescriptor::new(SAMPLE_GAME_NAME, SAMPLE_CATEGORY_NAME);
+    let file = || checkpoint::File::new(dir.path(), &target);
+    let time = time::Time::from_millis(25_000);
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    s.set_checkpoint(Box::new(file()));
+    s.handle(session::Action::Push(0, time))
+        .expect("action shouldn't fail");
+
+    // A session that never touched its run shouldn't wipe the other session's checkpoint.
+    let mut idle = make_session(&obs);
+    idle.set_checkpoint(Box::new(file()));
+    idle.shutdown();
+    idle.shutdown();
+    assert!(file().load().expect("checkpoint should load").is_some());
+
+    s.shutdown();
+    s.shutdown();
+    let run = file()
+        .load()
+        .expect("checkpoint should load")
+        .expect("shutting down should keep the checkpoint");
+    let mut resumed = make_session(&obs);
+    resumed.resume(&run).expect("resuming shouldn't fail");
+    let state = resumed.dump().expect("dump shouldn't fail");
+    let split = state.attempt.splits.get(0).expect("split should exist");
+    assert_eq!(vec![time], split.all_times());
+}
+
+/// Tests that a snapshot of a partial run exports to a loadable comparison file.
+#[test]
+fn test_session_export_snapshot() {
+    let dir = tempfile::tempdir().expect("couldn't make temp dir");
+    let path = dir.path().join("midrun.toml");
+
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    s.set_export_dir(dir.path());
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    push(&mut s, "pp2", 0, 0, 27, 300);
+
+    s.handle(session::Action::ExportSnapshot("midrun.toml".to_owned()))
+        .expect("export shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    let split = state.attempt.splits.get(1).expect("split should exist");
+    assert_eq!(1, split.num_times(), "exporting shouldn't reset the run");
+
+    let mut provider =
+        comparison::FileProvider::load(&path).expect("snapshot should load as a comparison");
+    let snapshot = comparison::Provider::comparison(&mut provider)
+        .expect("comparison shouldn't fail")
+        .expect("file provider should have a comparison");
+    assert_eq!(Some("midrun".to_owned()), snapshot.name);
+    assert!(snapshot.aggregate_for(short::Name::from("pp3")).is_none());
+    assert_eq!(
+        Some(time::Time::from_millis(51_360)),
+        snapshot.run.total_in_pb_run
+    );
+}
+
+/// Tests that snapshots can't be exported without an export directory, or outside it.
+#[test]
+fn test_session_export_snapshot_confined() {
+    let obs = Recorder::default();
+    let mut s = make_session(&obs);
+    push(&mut s, "pp1", 0, 0, 24, 60);
+
+    assert_eq!(
+        Err(session::Error::NoExportDir.into()),
+        export(&mut s, "midrun.toml")
+    );
+
+    let dir = tempfile::tempdir().expect("couldn't make temp dir");
+    let inner = dir.path().join("exports");
+    std::fs::create_dir(&inner).expect("couldn't make export dir");
+    s.set_export_dir(&inner);
+
+    let outside = dir.path().join("escaped.toml");
+    for name in [
+        "../escaped.toml",
+        outside.to_str().expect("temp path should be UTF-8"),
+        "sub/midrun.toml",
+        "",
+    ] {
+        assert_eq!(
+            Err(session::Error::ExportName(name.to_owned()).into()),
+            export(&mut s, name),
+            "{name:?} should be rejected"
+        );
+    }
+    assert!(!outside.exists());
+}
+
+/// Tests that pushing a second time to a split under a reject-extra policy raises an error.
+#[test]
+fn test_session_reject_extra() {
+    let obs = Recorder::default();
+    let mut attempt = make_attempt();
+    attempt.time_policy = SplitTimePolicy::RejectExtra;
+    let mut s = Session::new(attempt, &obs);
+
+    push(&mut s, "pp1", 0, 0, 24, 60);
+    push(&mut s, "pp1", 0, 0, 1, 0);
+
+    let error = event::Event::Error(session::Error::ExtraTime(short::Name::from("pp1")).into());
+    assert!(obs.contains(&error), "the second push should be rejected");
+
+    let state = s.dump().expect("dump shouldn't fail");
+    let split = state
+        .attempt
+        .splits
+        .get(short::Name::from("pp1"))
+        .expect("split should exist");
+    assert_eq!(
+        1,
+        split.num_times(),
+        "the rejected time shouldn't be pushed"
+    );
+}
+
+/// Tests that pushing to a split, or setting it explicitly, moves the current split.
+#[test]
+fn test_session_current_split() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    push(&mut s, "pp2", 0, 0, 27, 300);
+    let pp2 = event::Event::CurrentSplit(short::Name::from("pp2"));
+    assert!(obs.contains(&pp2), "pushing should move the current split");
+
+    s.handle(session::Action::SetCurrent(3))
+        .expect("action shouldn't fail");
+    let cc1 = event::Event::CurrentSplit(short::Name::from("cc1"));
+    assert!(obs.contains(&cc1), "setting should move the current split");
+    assert_eq!(3, s.dump().expect("dump shouldn't fail").current);
+}
+
+/// Tests that split actions push to, and advance, the current split.
+#[test]
+fn test_session_split_actions() {
+    let obs = event::observer::Null;
+    let mut s = Session::new(make_attempt(), &obs);
+
+    let time = time::Time::from_millis(25_000);
+    s.handle(session::Action::Split(time))
+        .expect("action shouldn't fail");
+    s.handle(session::Action::Split(time))
+        .expect("action shouldn't fail");
+    assert_eq!(2, s.dump().expect("dump shouldn't fail").current);
+
+    s.handle(session::Action::SplitReset)
+        .expect("action shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(1, state.current, "undoing a split should move back");
+    let pp2 = state.attempt.splits.get(1).expect("split should exist");
+    assert_eq!(0, pp2.num_times(), "undoing a split should pop its time");
+}
+
+/// Tests that a freerun session grows its splits as the runner names them.
+#[test]
+fn test_session_freerun() {
+    let obs = Recorder::default();
+    let target = make_attempt().category;
+    let mut s = Session::new(Attempt::freerun(target), &obs);
+
+    let time = time::Time::from_millis(25_000);
+    for name in ["first", "second"] {
+        s.handle(session::Action::PushNewSplit(short::Name::from(name), time))
+            .expect("action shouldn't fail");
+    }
+    let duplicate = s.handle(session::Action::PushNewSplit(
+        short::Name::from("first"),
+        time,
+    ));
+    assert_eq!(
+        Err(session::Error::DuplicateSplit(short::Name::from("first")).into()),
+        duplicate,
+        "the duplicate split should be reported to the caller"
+    );
+
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(2, state.attempt.splits.len(), "there should be two splits");
+    assert_eq!(1, state.current, "the newest split should be current");
+
+    let added = event::Event::AddSplit(1, game::Split::new("second", "second"));
+    assert!(obs.contains(&added), "adding a split should be observed");
+    let error =
+        event::Event::Error(session::Error::DuplicateSplit(short::Name::from("first")).into());
+    assert!(obs.contains(&error), "duplicate splits should be rejected");
+}
+
+/// Tests that finishing a run requires a last split time, and locks the run.
+#[test]
+fn test_session_finish() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    let result = s.handle(session::Action::Finish);
+    assert_eq!(
+        Err(model::Error::Session(session::Error::Unfinished)),
+        result
+    );
+    let error = event::Event::Error(session::Error::Unfinished.into());
+    assert!(obs.contains(&error), "an empty run shouldn't finish");
+
+    let last = s.dump().expect("dump shouldn't fail").attempt.splits.len() - 1;
+    let time = time::Time::from_millis(25_000);
+    s.handle(session::Action::Push(last, time))
+        .expect("action shouldn't fail");
+    s.handle(session::Action::Finish)
+        .expect("action shouldn't fail");
+    assert!(s.dump().expect("dump shouldn't fail").finished);
+
+    s.handle(session::Action::Push(0, time))
+        .expect("action shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    let first = state.attempt.splits.get(0).expect("split should exist");
+    assert_eq!(0, first.num_times(), "finished runs shouldn't take pushes");
+
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    assert!(!state.finished, "resetting should unlock the run");
+    assert_eq!(1, state.attempt.info.total, "finished runs count as saved");
+}
+
+/// Tests that best segments are observed for the splits that have them.
+#[test]
+fn test_session_best_segments() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    let time = model::timing::time::Time::from_millis(24_000);
+    let mut best = short::Map::new();
+    best.insert(short::Name::from("pp1"), time);
+    s.set_best_segments(best);
+
+    let event = event::Event::Split(short::Name::from("pp1"), event::Split::BestSegment(time));
+    assert!(obs.contains(&event), "the best segment should be observed");
+    let state = s.dump().expect("dump shouldn't fail");
+    assert!(
+        !state.best_segments.contains_key(&short::Name::from("pp2")),
+        "splits without history shouldn't get a best segment"
+    );
+}
+
+/// Tests that the live comparison follows the most recently timed split, including on undo.
+#[test]
+fn test_session_live() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    let t = model::timing::time::Time::from_millis;
+    let pp1 = short::Name::from("pp1");
+    let mut comparison = Comparison::default();
+    comparison.splits.insert(
+        pp1,
+        comparison::Segment {
+            split_pb: t(20_000),
+            in_pb_run: aggregate::Set {
+                split: t(25_000),
+                cumulative: t(25_000),
+            },
+        },
+    );
+    s.set_comparison_provider(Box::new(Some(comparison)));
+
+    s.handle(session::Action::Split(t(19_000)))
+        .expect("action shouldn't fail");
+    let live = s.dump().expect("dump shouldn't fail").live();
+    assert_eq!(Some(pp1), live.split);
+    assert!(live.is_gold, "beating the split PB should be gold");
+    assert_eq!(
+        Some(delta::Delta::of_comparison(t(19_000), t(25_000))),
+        live.delta.map(|d| d.split),
+        "the segment delta should be against the comparison run"
+    );
+    assert!(obs.contains(&event::Event::Live(live)));
+
+    s.handle(session::Action::SplitReset)
+        .expect("action shouldn't fail");
+    assert!(
+        obs.contains(&event::Event::Live(session::state::Live::default())),
+        "undoing the only split should clear the live comparison"
+    );
+}
+
+/// Tests looping over a practice range, keeping best segments from each loop.
+#[test]
+fn test_session_practice() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+    let t = model::timing::time::Time::from_millis;
+
+    s.handle(session::Action::Practice(Some(session::action::Practice {
+        start: 1,
+        end: 2,
+        save: false,
+    })))
+    .expect("action shouldn't fail");
+    assert_eq!(1, s.dump().expect("dump shouldn't fail").current);
+
+    for time in [t(20_000), t(30_000)] {
+        s.handle(session::Action::Split(time))
+            .expect("action shouldn't fail");
+    }
+
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(
+        1, state.current,
+        "the run should loop back to the range start"
+    );
+    assert!(
+        state.attempt.splits.iter().all(|s| s.num_times() == 0),
+        "looping should clear the practice times"
+    );
+    let pp2 = short::Name::from("pp2");
+    assert_eq!(Some(&t(20_000)), state.best_segments.get(&pp2));
+    assert!(obs.contains(&event::Event::Split(
+        pp2,
+        event::Split::BestSegment(t(20_000))
+    )));
+
+    let bad = s.handle(session::Action::Practice(Some(session::action::Practice {
+        start: 2,
+        end: 1,
+        save: false,
+    })));
+    assert!(bad.is_err(), "backwards ranges should be rejected");
+}
+
+/// Tests merging a split with the split after it.
+#[test]
+fn test_session_merge_splits() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    let time = time::Time::from_millis(25_000);
+    s.handle(session::Action::Push(1, time))
+        .expect("action shouldn't fail");
+    let before = s.dump().expect("dump shouldn't fail").attempt.splits.len();
+
+    s.handle(session::Action::MergeSplits(
+        0,
+        session::action::MergeName::Both,
+    ))
+    .expect("action shouldn't fail");
+
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(
+        before - 1,
+        state.attempt.splits.len(),
+        "one split should go"
+    );
+    let merged = state.attempt.splits.get(0).expect("split should exist");
+    assert_eq!(short::Name::from("pp1"), merged.info.short);
+    assert_eq!(
+        1,
+        merged.num_times(),
+        "the merged split should take both times"
+    );
+    assert!(
+        state
+            .attempt
+            .timing_as_historic()
+            .is_skipped(merged.info.short),
+        "the merged split's times shouldn't be saved as segment times"
+    );
+    assert!(
+        state.attempt.splits.get(short::Name::from("pp2")).is_none(),
+        "the absorbed split should be gone"
+    );
+    assert_eq!(
+        0, state.current,
+        "the current split should follow the merge"
+    );
+    let event = event::Event::MergeSplits(0, merged.info.clone());
+    assert!(obs.contains(&event), "merging should be observed");
+
+    let last = state.attempt.splits.len() - 1;
+    let last_short = state
+        .attempt
+        .splits
+        .get(last)
+        .expect("split should exist")
+        .info
+        .short;
+    let result = s.handle(session::Action::MergeSplits(
+        last,
+        session::action::MergeName::Both,
+    ));
+    assert_eq!(
+        Err(session::Error::NothingToMerge(last_short).into()),
+        result,
+        "the last split shouldn't merge"
+    );
+}
+
+fn clear_all<O: event::observer::Observer>(s: &mut Session<O>) -> session::action::Outcome {
+    s.handle(session::Action::ClearAll)
+        .expect("action shouldn't fail")
+}
+
+fn export<O: event::observer::Observer>(
+    s: &mut Session<O>,
+    name: &str,
+) -> Result<session::action::Outcome, model::Error> {
+    s.handle(session::Action::ExportSnapshot(name.to_owned()))
+}
+
+fn push<O: event::observer::Observer>(
+    session: &mut Session<O>,
+    name: &'static str,
+    h: u16,
+    m: u16,
+    s: u16,
+    ms: u16,
+) {
+    let time = time::Time::try_from(human::Time::new(h, m, s, ms)).expect("time overflowed");
+    // Rejected pushes also reach the observers, which is where the tests check for them.
+    let _ = session.push_to(short::Name::from(name), time);
+}
+
+/// Tests setting and removing run metadata, and that it carries over resets.
+#[test]
+fn test_session_set_meta() {
+    let obs = Recorder::default();
+    let mut s = Session::new(make_attempt(), &obs);
+
+    assert!(set_meta(&mut s, "controller", "pad").is_applied());
+    assert!(
+        !set_meta(&mut s, "controller", "pad").is_applied(),
+        "setting the same value again should do nothing"
+    );
+    assert!(obs.contains(&event::Event::Metadata(
+        "controller".to_owned(),
+        Some("pad".to_owned())
+    )));
+
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+    let state = s.dump().expect("dump shouldn't fail");
+    assert_eq!(
+        Some("pad"),
+        state.attempt.metadata.get("controller").map(String::as_str),
+        "metadata should survive a reset"
+    );
+
+    assert!(set_meta(&mut s, "controller", "").is_applied());
+    assert!(obs.contains(&event::Event::Metadata("controller".to_owned(), None)));
+    let state = s.dump().expect("dump shouldn't fail");
+    assert!(state.attempt.metadata.is_empty());
+}
+
+/// Tests that elapsed time comes from the session's clock, and starts with the run.
+#[test]
+fn test_session_elapsed() {
+    let clock = session::clock::Manual::default();
+    let mut s = Session::new(make_attempt(), &event::observer::Null);
+    s.set_clock(Box::new(clock.clone()));
+
+    clock.advance(Duration::from_secs(5));
+    assert!(s.elapsed().is_none(), "the run hasn't started yet");
+
+    let time = time::Time::from_millis(25_000);
+    s.handle(session::Action::Push(0, time))
+        .expect("action shouldn't fail");
+    clock.advance(Duration::from_millis(1500));
+    assert_eq!(
+        Some(model::timing::time::Time::from_millis(1500)),
+        s.elapsed(),
+        "elapsed time should count from the first edit"
+    );
+
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+    assert!(s.elapsed().is_none(), "resetting should stop the clock");
+}
+
+/// Tests that a ghost replays the comparison's run against elapsed time, and reports lead changes.
+#[test]
+fn test_session_ghost() {
+    let obs = Recorder::default();
+    let clock = session::clock::Manual::default();
+    let mut s = make_session(&obs);
+    s.set_clock(Box::new(clock.clone()));
+    s.set_ghost_provider(Box::new(Some(comparison())));
+
+    let ms = model::timing::time::Time::from_millis;
+    let ghost = event::Event::Ghost;
+
+    s.tick();
+    assert!(
+        !obs.contains(&ghost(event::Ghost::Lead { ahead: true })),
+        "the ghost shouldn't move before the run starts"
+    );
+
+    s.handle(session::Action::Push(0, ms(25_000)))
+        .expect("action shouldn't fail");
+    s.tick();
+    assert!(obs.contains(&ghost(event::Ghost::Lead { ahead: true })));
+
+    clock.advance(Duration::from_secs(26));
+    s.tick();
+    assert!(obs.contains(&ghost(event::Ghost::Passed("pp1".into(), ms(25_060)))));
+
+    clock.advance(Duration::from_secs(25));
+    s.tick();
+    assert!(obs.contains(&ghost(event::Ghost::Passed("pp2".into(), ms(50_360)))));
+    assert!(obs.contains(&ghost(event::Ghost::Lead { ahead: false })));
+    assert!(!obs.contains(&ghost(event::Ghost::Finished(ms(50_360)))));
+}
+
+/// Tests that the ghost is fetched again when the run resets.
+#[test]
+fn test_session_ghost_refresh() {
+    let obs = Recorder::default();
+    let clock = session::clock::Manual::default();
+    let mut s = make_session(&obs);
+    s.set_clock(Box::new(clock.clone()));
+    s.set_ghost_provider(Box::new(OneShot(Some(comparison()))));
+
+    // The provider has nothing left to give, so the new run has no ghost.
+    s.handle(session::Action::NewRun(
+        session::action::OldDestination::Discard,
+    ))
+    .expect("action shouldn't fail");
+
+    s.handle(session::Action::Push(0, time::Time::from_millis(25_000)))
+        .expect("action shouldn't fail");
+    clock.advance(Duration::from_secs(26));
+    s.tick();
+    assert!(!obs.contains(&event::Event::Ghost(event::Ghost::Passed(
+        "pp1".into(),
+        time::Time::from_millis(25_060)
+    ))));
+}
+
+/// A provider that gives its comparison once, and nothing afterwards.
+struct OneShot(Option<Comparison>);
+
+impl comparison::Provider for OneShot {
+    fn comparison(&mut self) -> comparison::provider::Result {
+        Ok(self.0.take())
+    }
+}
+
+fn set_meta<O: event::observer::Observer>(
+    s: &mut Session<O>,
+    key: &str,
+    value: &str,
+) -> session::action::Outcome {
+    s.handle(session::Action::SetMeta(key.to_owned(), value.to_owned()))
+        .expect("action shouldn't fail")
 }
 
 #[derive(Default)]